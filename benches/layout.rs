@@ -0,0 +1,84 @@
+//! Benchmarks of styling and laying out a long page
+//!
+//! Run with `cargo bench --bench layout`
+
+use bliss_dom::DocumentConfig;
+use bliss_html::HtmlDocument;
+use bliss_traits::shell::{ColorScheme, Viewport};
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+
+/// A page of `items` cards, most of which share their styles with each other
+fn long_page(items: usize) -> String {
+    let mut html = String::from(
+        r#"<html><head><style>
+            .list { display: flex; flex-direction: column; gap: 8px; padding: 16px; }
+            .card { display: grid; grid-template-columns: 64px 1fr auto; gap: 12px; padding: 12px; border: 1px solid #ccc; }
+            .card.featured { border-color: gold; }
+            .avatar { width: 64px; height: 64px; background: #eee; }
+            .title { font-weight: bold; }
+            .meta { color: #666; font-size: 12px; }
+        </style></head><body><div class="list">"#,
+    );
+    for i in 0..items {
+        let class = if i % 10 == 0 { "card featured" } else { "card" };
+        html.push_str(&format!(
+            r#"<div class="{class}"><div class="avatar"></div><div><div class="title">Item {i}</div><div class="meta">Posted {i} minutes ago</div></div><button>Open</button></div>"#
+        ));
+    }
+    html.push_str("</div></body></html>");
+    html
+}
+
+fn document(html: &str, style_cache: bool) -> HtmlDocument {
+    let mut doc = HtmlDocument::from_html(
+        html,
+        DocumentConfig {
+            viewport: Some(Viewport::new(1280, 800, 1.0, ColorScheme::Light)),
+            ..Default::default()
+        },
+    );
+    doc.set_style_conversion_cache(style_cache);
+    doc
+}
+
+fn layout(c: &mut Criterion) {
+    let html = long_page(2000);
+    let mut group = c.benchmark_group("layout");
+    group.sample_size(20);
+
+    for style_cache in [false, true] {
+        let suffix = match style_cache {
+            true => "style_cache",
+            false => "no_style_cache",
+        };
+
+        // Style and lay out a freshly parsed document
+        group.bench_function(format!("initial/{suffix}"), |b| {
+            b.iter_batched(
+                || document(&html, style_cache),
+                |mut doc| {
+                    doc.resolve(0.0);
+                    doc
+                },
+                BatchSize::LargeInput,
+            )
+        });
+
+        // Lay out an already styled document again after the viewport is resized
+        let mut doc = document(&html, style_cache);
+        doc.resolve(0.0);
+        let mut width = 1280;
+        group.bench_function(format!("resize/{suffix}"), |b| {
+            b.iter(|| {
+                width = if width == 1280 { 1024 } else { 1280 };
+                doc.set_viewport(Viewport::new(width, 800, 1.0, ColorScheme::Light));
+                doc.resolve(0.0);
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, layout);
+criterion_main!(benches);
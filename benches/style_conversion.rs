@@ -0,0 +1,158 @@
+//! Counts the allocations made when converting the styles of a page for layout
+//!
+//! Run with `cargo bench --bench style_conversion`. For each page, this compares converting every style
+//! eagerly (including the grid properties of non-grid elements, as conversion used to), converting with
+//! [`to_taffy_style`], converting through a [`TaffyStyleCache`] and reading the properties through the
+//! borrowed [`TaffyStyloStyle`] wrapper.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bliss_dom::DocumentConfig;
+use bliss_html::HtmlDocument;
+use bliss_traits::shell::{ColorScheme, Viewport};
+use stylo_taffy::{TaffyStyleCache, TaffyStyloStyle, convert, to_taffy_style};
+use taffy::{CoreStyle, FlexboxContainerStyle, GridContainerStyle};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// The number of allocations made while running `f`
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// A page of `items` cards laid out with flexbox
+fn flexbox_page(items: usize) -> String {
+    let mut html = String::from(
+        r#"<html><head><style>
+            .list { display: flex; flex-direction: column; gap: 8px; }
+            .card { display: flex; align-items: center; gap: 12px; padding: 12px; }
+            .avatar { width: 64px; height: 64px; flex-shrink: 0; }
+            .body { display: flex; flex-direction: column; flex-grow: 1; }
+        </style></head><body><div class="list">"#,
+    );
+    for i in 0..items {
+        html.push_str(&format!(
+            r#"<div class="card"><div class="avatar"></div><div class="body"><span>Item {i}</span><span>Details</span></div></div>"#
+        ));
+    }
+    html.push_str("</div></body></html>");
+    html
+}
+
+/// A page of `items` cards laid out with grid
+fn grid_page(items: usize) -> String {
+    let mut html = String::from(
+        r#"<html><head><style>
+            .list { display: grid; grid-template-columns: repeat(4, [col] 1fr); grid-auto-rows: minmax(100px, auto); gap: 8px; }
+            .card { display: grid; grid-template: "avatar title" auto "avatar meta" auto / 64px 1fr; }
+        </style></head><body><div class="list">"#,
+    );
+    for i in 0..items {
+        html.push_str(&format!(
+            r#"<div class="card"><div style="grid-area: avatar"></div><div style="grid-area: title">Item {i}</div><div style="grid-area: meta">Details</div></div>"#
+        ));
+    }
+    html.push_str("</div></body></html>");
+    html
+}
+
+fn report(name: &str, html: &str) {
+    let mut doc = HtmlDocument::from_html(
+        html,
+        DocumentConfig {
+            viewport: Some(Viewport::new(1280, 800, 1.0, ColorScheme::Light)),
+            ..Default::default()
+        },
+    );
+    doc.resolve(0.0);
+
+    let mut styles = Vec::new();
+    doc.visit(|_, node| {
+        let data = node.stylo_element_data.borrow();
+        if let Some(style) = data.as_ref().and_then(|data| data.styles.get_primary()) {
+            styles.push(style.clone());
+        }
+    });
+
+    let eager = count_allocations(|| {
+        for style in &styles {
+            // Conversion used to convert the grid container properties of every element
+            let pos = style.get_position();
+            let mut converted = to_taffy_style(style);
+            converted.grid_template_rows = convert::grid_template_tracks(&pos.grid_template_rows);
+            converted.grid_template_columns =
+                convert::grid_template_tracks(&pos.grid_template_columns);
+            converted.grid_auto_rows = convert::grid_auto_tracks(&pos.grid_auto_rows);
+            converted.grid_auto_columns = convert::grid_auto_tracks(&pos.grid_auto_columns);
+            std::hint::black_box(converted);
+        }
+    });
+    let converted = count_allocations(|| {
+        for style in &styles {
+            std::hint::black_box(to_taffy_style(style));
+        }
+    });
+    let cached = count_allocations(|| {
+        let mut cache = TaffyStyleCache::new();
+        for style in &styles {
+            std::hint::black_box(cache.get_or_convert(style));
+        }
+    });
+    let borrowed = count_allocations(|| {
+        for style in &styles {
+            let style = TaffyStyloStyle(&**style);
+            std::hint::black_box((style.size(), style.margin(), style.flex_direction()));
+            let tracks = style.grid_template_rows().map(Iterator::count).unwrap_or(0)
+                + style
+                    .grid_template_columns()
+                    .map(Iterator::count)
+                    .unwrap_or(0)
+                + style.grid_auto_rows().count()
+                + style.grid_auto_columns().count();
+            std::hint::black_box(tracks);
+        }
+    });
+
+    let per_style = |count: usize| count as f64 / styles.len() as f64;
+    println!("{name} ({} styles)", styles.len());
+    for (strategy, count) in [
+        ("eager conversion (previous)", eager),
+        ("to_taffy_style", converted),
+        ("TaffyStyleCache", cached),
+        ("TaffyStyloStyle (borrowed)", borrowed),
+    ] {
+        println!(
+            "  {strategy:<28} {count:>8} allocations ({:.2} per style)",
+            per_style(count)
+        );
+    }
+}
+
+fn main() {
+    report("flexbox", &flexbox_page(1000));
+    report("grid", &grid_page(1000));
+}
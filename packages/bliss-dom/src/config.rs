@@ -1,11 +1,24 @@
 use crate::HtmlParserProvider;
+use crate::script::BoxedScriptEngine;
 use bliss_traits::{
+    download::DownloadHandler,
     navigation::NavigationProvider,
     net::NetProvider,
     shell::{ShellProvider, Viewport},
 };
 use parley::FontContext;
 use std::sync::Arc;
+use std::time::Duration;
+
+/// The shape of the caret drawn in focused text inputs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CaretShape {
+    /// A thin vertical line between characters
+    #[default]
+    Bar,
+    /// A box covering the character after the caret
+    Block,
+}
 
 /// Options used when constructing a [`BaseDocument`](crate::BaseDocument)
 #[derive(Default)]
@@ -20,10 +33,24 @@ pub struct DocumentConfig {
     pub net_provider: Option<Arc<dyn NetProvider>>,
     /// Navigation provider to handle link clicks and form submissions
     pub navigation_provider: Option<Arc<dyn NavigationProvider>>,
+    /// Download handler to handle links with a `download` attribute
+    pub download_handler: Option<Arc<dyn DownloadHandler>>,
     /// Shell provider to redraw requests, clipboard, etc
     pub shell_provider: Option<Arc<dyn ShellProvider>>,
     /// HTML parser provider. Used to parse HTML for setInnerHTML
     pub html_parser_provider: Option<Arc<dyn HtmlParserProvider>>,
     /// Parley `FontContext`
     pub font_ctx: Option<FontContext>,
+    /// A color emoji font (COLR or bitmap) used to render emoji, e.g. a bundled copy of Noto Color Emoji.
+    /// If not provided then the system emoji font (if any) is used.
+    pub emoji_font: Option<Vec<u8>>,
+    /// Script engine. Whether or not one is provided determines whether `<noscript>` content is rendered
+    pub script_engine: Option<BoxedScriptEngine>,
+    /// Disable `document.write`-style injection of markup during parsing (see [`BaseDocument::write`](crate::BaseDocument::write))
+    pub disable_document_write: bool,
+    /// The shape of the caret in text inputs
+    pub caret_shape: CaretShape,
+    /// How long the caret in text inputs stays visible (and then hidden) for when blinking.
+    /// Defaults to 500ms. A zero interval disables blinking.
+    pub caret_blink_interval: Option<Duration>,
 }
@@ -4,7 +4,8 @@ use crate::layout::construct::ConstructionTask;
 use crate::layout::damage::ALL_DAMAGE;
 use crate::mutator::ViewportMut;
 use crate::net::{
-    Resource, ResourceHandler, ResourceLoadResponse, StylesheetHandler, StylesheetLoader,
+    NavigationHandler, Resource, ResourceHandler, ResourceLoadResponse, StylesheetHandler,
+    StylesheetLoader,
 };
 use crate::node::{ImageData, NodeFlags, RasterImageData, SpecialElementData, Status, TextBrush};
 use crate::selection::TextSelection;
@@ -13,17 +14,27 @@ use crate::traversal::TreeTraverser;
 use crate::url::DocumentUrl;
 use crate::util::ImageType;
 use crate::{
-    DocumentConfig, DocumentMutator, DummyHtmlParserProvider, ElementData, EventDriver,
+    CaretShape, DocumentConfig, DocumentMutator, DummyHtmlParserProvider, ElementData, EventDriver,
     HtmlParserProvider, Node, NodeData, NoopEventHandler, TextNodeData, DEFAULT_CSS,
 };
 use bliss_traits::devtools::DevtoolSettings;
+use bliss_traits::download::{
+    download_response, DownloadHandler, DownloadMetadata, DownloadNetHandler, DownloadReason,
+    DummyDownloadHandler,
+};
 use bliss_traits::events::{BlissScrollEvent, DomEvent, DomEventData, EventSink, HitResult, UiEvent};
-use bliss_traits::navigation::{DummyNavigationProvider, NavigationProvider};
-use bliss_traits::net::{DummyNetProvider, NetProvider, Request};
-use bliss_traits::shell::{ColorScheme, DummyShellProvider, ShellProvider, Viewport};
+use bliss_traits::navigation::{
+    DummyNavigationProvider, NavigationOptions, NavigationProvider, NavigationResponse,
+};
+use bliss_traits::net::{
+    AbortController, Bytes, DummyNetProvider, HeaderMap, NetProvider, Request, RequestPriority,
+};
+use bliss_traits::shell::{
+    ColorScheme, DummyShellProvider, FullscreenMode, ShellProvider, Viewport, WindowIcon,
+};
 use cursor_icon::CursorIcon;
 use linebender_resource_handle::Blob;
-use markup5ever::local_name;
+use markup5ever::{local_name, LocalName};
 use parley::{FontContext, PlainEditorDriver};
 use selectors::{matching::QuirksMode, Element};
 use slab::Slab;
@@ -37,7 +48,7 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::{Arc, Mutex, MutexGuard, RwLockReadGuard, RwLockWriteGuard};
 use std::task::Context as TaskContext;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use style::animation::DocumentAnimationSet;
 use style::attr::{AttrIdentifier, AttrValue};
 use style::data::{ElementData as StyloElementData, ElementStyles};
@@ -63,6 +74,20 @@ use url::Url;
 #[cfg(feature = "parallel-construct")]
 use thread_local::ThreadLocal;
 
+/// User agent stylesheet which hides `<noscript>` elements. Added when a script engine is attached.
+const NOSCRIPT_CSS: &str = "noscript { display: none !important; }";
+/// Applied when the user prefers reduced motion. As a user agent stylesheet, its `!important`
+/// declarations take precedence over the document's own.
+const REDUCED_MOTION_CSS: &str = "*, *::before, *::after {
+    animation-duration: 0.01ms !important;
+    animation-iteration-count: 1 !important;
+    transition-duration: 0.01ms !important;
+    scroll-behavior: auto !important;
+}";
+
+/// How long the caret is visible (and then hidden) for when blinking
+const DEFAULT_CARET_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+
 pub enum DocGuard<'a> {
     Ref(&'a BaseDocument),
     RefCell(std::cell::Ref<'a, BaseDocument>),
@@ -191,6 +216,8 @@ pub struct BaseDocument {
     // Config
     /// Base url for resolving linked resources (stylesheets, images, fonts, etc)
     pub(crate) url: DocumentUrl,
+    /// The default browsing context for link navigation (from a `<base target>` element)
+    pub(crate) base_target: Option<String>,
     // Devtool settings. Currently used to render debug overlays
     pub(crate) devtool_settings: DevtoolSettings,
     // Viewport details such as the dimensions, HiDPI scale, and zoom factor,
@@ -251,6 +278,9 @@ pub struct BaseDocument {
     /// Text selection state (for non-input text)
     pub(crate) text_selection: TextSelection,
 
+    /// Cache of stylo styles converted to taffy styles (enabled by default)
+    pub(crate) style_cache: Option<stylo_taffy::TaffyStyleCache>,
+
     // TODO: collapse animating state into a bitflags
     /// Whether there are active CSS animations/transitions (so we should re-render every frame)
     pub(crate) has_active_animations: bool,
@@ -258,6 +288,19 @@ pub struct BaseDocument {
     pub(crate) has_canvas: bool,
     /// Whether there are subdocuments that are animating (so we should re-render every frame)
     pub(crate) subdoc_is_animating: bool,
+    /// The animation time at which the document was last resolved
+    pub(crate) animation_time: f64,
+
+    /// The shape of the caret in text inputs
+    pub(crate) caret_shape: CaretShape,
+    /// How long the caret is visible (and then hidden) for when blinking. Zero disables blinking.
+    pub(crate) caret_blink_interval: Duration,
+    /// The animation time at which the caret (re)started blinking. The caret is always visible
+    /// immediately after focusing or typing, so this is reset when either happens.
+    pub(crate) caret_blink_start: f64,
+    /// The caret area (x, y, width, height) which was last reported to the shell for positioning
+    /// IME candidate windows
+    pub(crate) ime_cursor_area: Option<[f32; 4]>,
 
     /// Map of node ID's for fast lookups
     pub(crate) nodes_to_id: HashMap<String, usize>,
@@ -290,6 +333,8 @@ pub struct BaseDocument {
     /// Navigation provider. Can be used to navigate to a new page (bubbles up the event
     /// on e.g. clicking a Link)
     pub navigation_provider: Arc<dyn NavigationProvider>,
+    /// Download handler. Receives resources from links with a `download` attribute
+    pub download_handler: Arc<dyn DownloadHandler>,
     /// Shell provider. Can be used to request a redraw or set the cursor icon
     pub shell_provider: Arc<dyn ShellProvider>,
     /// HTML parser provider. Used to parse HTML for setInnerHTML
@@ -297,6 +342,8 @@ pub struct BaseDocument {
 
     /// Script engine for executing JavaScript, Lua, Python, etc.
     pub(crate) script_engine: Option<crate::script::BoxedScriptEngine>,
+    /// State for injecting markup at the parser's insertion point
+    pub(crate) document_write: crate::script::DocumentWriteState,
 
     /// Event sink for external event observation
     pub(crate) event_sink: Option<Arc<dyn EventSink>>,
@@ -329,7 +376,7 @@ impl BaseDocument {
 
         let id = ID_GENERATOR.fetch_add(1, Ordering::SeqCst);
 
-        let font_ctx = config
+        let mut font_ctx = config
             .font_ctx
             // .map(|mut font_ctx| {
             //     font_ctx.collection.make_shared();
@@ -350,6 +397,15 @@ impl BaseDocument {
                     .register_fonts(Blob::new(Arc::new(crate::BULLET_FONT) as _), None);
                 font_ctx
             });
+        if let Some(emoji_font) = config.emoji_font {
+            let families = font_ctx
+                .collection
+                .register_fonts(Blob::new(Arc::new(emoji_font) as _), None);
+            font_ctx.collection.set_generic_families(
+                parley::GenericFamily::Emoji,
+                families.into_iter().map(|(family_id, _)| family_id),
+            );
+        }
         let font_ctx = Arc::new(Mutex::new(font_ctx));
 
         let viewport = config.viewport.unwrap_or_default();
@@ -366,6 +422,7 @@ impl BaseDocument {
         style_config::set_bool("layout.legacy_layout", true);
         style_config::set_bool("layout.unimplemented", true);
         style_config::set_bool("layout.columns.enabled", true);
+        style_config::set_bool("layout.css.conic-gradient.enabled", true);
 
         let base_url = config
             .base_url
@@ -378,6 +435,9 @@ impl BaseDocument {
         let navigation_provider = config
             .navigation_provider
             .unwrap_or_else(|| Arc::new(DummyNavigationProvider));
+        let download_handler = config
+            .download_handler
+            .unwrap_or_else(|| Arc::new(DummyDownloadHandler));
         let shell_provider = config
             .shell_provider
             .unwrap_or_else(|| Arc::new(DummyShellProvider));
@@ -402,6 +462,7 @@ impl BaseDocument {
             devtool_settings: DevtoolSettings::default(),
             viewport_scroll: crate::Point::ZERO,
             url: base_url,
+            base_target: None,
             ua_stylesheets: HashMap::new(),
             nodes_to_stylesheet: BTreeMap::new(),
             font_ctx,
@@ -416,6 +477,13 @@ impl BaseDocument {
             mousedown_node_id: None,
             has_active_animations: false,
             subdoc_is_animating: false,
+            animation_time: 0.0,
+            caret_shape: config.caret_shape,
+            caret_blink_interval: config
+                .caret_blink_interval
+                .unwrap_or(DEFAULT_CARET_BLINK_INTERVAL),
+            caret_blink_start: 0.0,
+            ime_cursor_area: None,
             has_canvas: false,
             sub_document_nodes: HashSet::new(),
             changed_nodes: HashSet::new(),
@@ -425,9 +493,14 @@ impl BaseDocument {
             controls_to_form: HashMap::new(),
             net_provider,
             navigation_provider,
+            download_handler,
             shell_provider,
             html_parser_provider,
             script_engine: None,
+            document_write: crate::script::DocumentWriteState {
+                disabled: config.disable_document_write,
+                ..Default::default()
+            },
             event_sink: None,
             last_mousedown_time: None,
             mousedown_position: taffy::Point::ZERO,
@@ -435,6 +508,7 @@ impl BaseDocument {
             drag_mode: DragMode::None,
             scroll_animation: ScrollAnimationState::None,
             text_selection: TextSelection::default(),
+            style_cache: Some(stylo_taffy::TaffyStyleCache::new()),
         };
 
         // Initialise document with root Document node
@@ -463,12 +537,21 @@ impl BaseDocument {
         };
         *doc.root_node().stylo_element_data.borrow_mut() = Some(stylo_element_data);
 
+        if let Some(engine) = config.script_engine {
+            doc.set_script_engine(engine);
+        }
+
+        doc.apply_system_preferences();
+        doc.net_provider.set_document_url(doc.id, Some(doc.url()));
+
         doc
     }
 
     /// Set the Document's networking provider
     pub fn set_net_provider(&mut self, net_provider: Arc<dyn NetProvider>) {
+        self.net_provider.set_document_url(self.id, None);
         self.net_provider = net_provider;
+        self.net_provider.set_document_url(self.id, Some(self.url()));
     }
 
     /// Set the Document's navigation provider
@@ -476,6 +559,11 @@ impl BaseDocument {
         self.navigation_provider = navigation_provider;
     }
 
+    /// Set the Document's download handler
+    pub fn set_download_handler(&mut self, download_handler: Arc<dyn DownloadHandler>) {
+        self.download_handler = download_handler;
+    }
+
     /// Set the Document's shell provider
     pub fn set_shell_provider(&mut self, shell_provider: Arc<dyn ShellProvider>) {
         self.shell_provider = shell_provider;
@@ -490,6 +578,65 @@ impl BaseDocument {
     pub fn set_script_engine(&mut self, mut engine: crate::script::BoxedScriptEngine) {
         engine.init(self);
         self.script_engine = Some(engine);
+        if !self.ua_stylesheets.contains_key(NOSCRIPT_CSS) {
+            self.add_user_agent_stylesheet(NOSCRIPT_CSS);
+        }
+    }
+
+    /// Inject `markup` into the parser's input stream at the current insertion point
+    /// (the equivalent of `document.write`). The markup is parsed as soon as the parser resumes.
+    ///
+    /// Fails if injection is disabled, if the document is not currently being parsed, or if injected
+    /// markup has recursively injected markup too many times.
+    pub fn write(&mut self, markup: &str) -> Result<(), crate::DocumentWriteError> {
+        use crate::DocumentWriteError;
+        let state = &mut self.document_write;
+        if state.disabled {
+            return Err(DocumentWriteError::Disabled);
+        }
+        if !state.has_insertion_point {
+            return Err(DocumentWriteError::NoInsertionPoint);
+        }
+        if state.nesting_level >= crate::script::MAX_DOCUMENT_WRITE_NESTING {
+            return Err(DocumentWriteError::TooDeeplyNested);
+        }
+        state.pending.push_str(markup);
+        Ok(())
+    }
+
+    /// Called by the HTML parser when it begins (`true`) or finishes (`false`) parsing the document.
+    /// Markup can only be injected using [`write`](Self::write) while the parser is active.
+    pub fn set_parser_active(&mut self, active: bool) {
+        let state = &mut self.document_write;
+        state.has_insertion_point = active;
+        state.nesting_level = 0;
+        state.pending.clear();
+    }
+
+    /// Called by the HTML parser when it pauses (e.g. at the end of a `<script>` element).
+    /// Takes the markup written since the parser last paused, which should be parsed before the
+    /// remaining input. The parser must call [`written_markup_parsed`](Self::written_markup_parsed)
+    /// once it has finished parsing the returned markup.
+    pub fn take_written_markup(&mut self) -> Option<String> {
+        let state = &mut self.document_write;
+        if state.pending.is_empty() {
+            return None;
+        }
+        state.nesting_level += 1;
+        Some(std::mem::take(&mut state.pending))
+    }
+
+    /// Called by the HTML parser once it has finished parsing markup returned by
+    /// [`take_written_markup`](Self::take_written_markup)
+    pub fn written_markup_parsed(&mut self) {
+        let state = &mut self.document_write;
+        state.nesting_level = state.nesting_level.saturating_sub(1);
+    }
+
+    /// Whether scripting is enabled (a script engine is attached). When scripting is enabled,
+    /// `<noscript>` content is parsed as raw text and hidden.
+    pub fn scripting_enabled(&self) -> bool {
+        self.script_engine.is_some()
     }
 
     /// Set the event sink for this document
@@ -542,9 +689,50 @@ impl BaseDocument {
         }
     }
 
+    /// Enable or disable caching of converted layout styles (enabled by default). When enabled, elements
+    /// which share a computed style (or which weren't restyled) reuse the previously converted style.
+    pub fn set_style_conversion_cache(&mut self, enabled: bool) {
+        self.style_cache = enabled.then(stylo_taffy::TaffyStyleCache::new);
+    }
+
+    /// The URL which the document was loaded from
+    pub fn url(&self) -> &Url {
+        self.url.document_url()
+    }
+
     /// Set base url for resolving linked resources (stylesheets, images, fonts, etc)
     pub fn set_base_url(&mut self, url: &str) {
         self.url = DocumentUrl::from(Url::parse(url).unwrap());
+        self.net_provider.set_document_url(self.id, Some(self.url()));
+    }
+
+    /// Update the base URL and default link target from the first `<base>` elements in the document
+    /// with `href` and `target` attributes respectively
+    pub(crate) fn update_base_url(&mut self) {
+        let mut href = None;
+        let mut target = None;
+        self.visit(|_, node| {
+            let Some(el) = node.element_data() else {
+                return;
+            };
+            if el.name.local != local_name!("base") {
+                return;
+            }
+            if href.is_none() {
+                href = el.attr(local_name!("href")).map(str::to_string);
+            }
+            if target.is_none() {
+                target = el.attr(local_name!("target")).map(str::to_string);
+            }
+        });
+
+        self.url.set_base_href(href.as_deref());
+        self.base_target = target;
+    }
+
+    /// The default target for link navigation, as specified by a `<base target>` element
+    pub fn base_target(&self) -> Option<&str> {
+        self.base_target.as_deref()
     }
 
     pub fn guard(&self) -> &SharedRwLock {
@@ -727,6 +915,9 @@ impl BaseDocument {
             for &child in &node.children {
                 self.drop_node_ignoring_parent(child);
             }
+            if let Some(contents_id) = node.element_data().and_then(|el| el.template_contents) {
+                self.drop_node_ignoring_parent(contents_id);
+            }
         }
         node
     }
@@ -745,9 +936,16 @@ impl BaseDocument {
     pub fn deep_clone_node(&mut self, node_id: usize) -> usize {
         // Load existing node
         let node = &self.nodes[node_id];
-        let data = node.data.clone();
+        let mut data = node.data.clone();
         let children = node.children.clone();
 
+        // Clone template contents (so that the clone does not share them with the original)
+        if let Some(el) = data.downcast_element_mut() {
+            if let Some(contents_id) = el.template_contents {
+                el.template_contents = Some(self.deep_clone_node(contents_id));
+            }
+        }
+
         // Create new node
         let new_node_id = self.create_node(data);
 
@@ -795,6 +993,50 @@ impl BaseDocument {
         })
     }
 
+    /// Download the resource at `url` using the Document's download handler
+    pub fn start_download(&self, url: Url, suggested_filename: Option<String>) {
+        let mut metadata = DownloadMetadata::new(url, self.id, DownloadReason::DownloadAttribute);
+        if let Some(filename) = suggested_filename.filter(|name| !name.is_empty()) {
+            metadata.suggested_filename = Some(filename);
+        }
+        let url = metadata.url.clone();
+        let Some(sink) = self.download_handler.start(metadata) else {
+            return;
+        };
+
+        // The total length is taken from the `Content-Length` of the response once it is received
+        let controller = AbortController::default();
+        let request = Request::get(url).signal(controller.signal.clone());
+        let handler = DownloadNetHandler::new(sink, None, Some(controller));
+        self.net_provider.fetch(self.id, request, Box::new(handler));
+    }
+
+    /// Fetch the target of a navigation from this Document using the Document's net provider.
+    /// Responses which should be downloaded (see
+    /// [`is_download_response`](bliss_traits::download::is_download_response)) are handed to the
+    /// Document's download handler. Other responses are passed to `on_load` to be shown as a
+    /// document.
+    pub fn fetch_navigation(
+        &self,
+        options: NavigationOptions,
+        on_load: impl FnOnce(NavigationResponse) + Send + 'static,
+    ) {
+        let download_handler = self.download_handler.clone();
+        let handler = NavigationHandler::new(self.id, download_handler, Box::new(on_load));
+        self.net_provider
+            .fetch(self.id, options.into_request(), Box::new(handler));
+    }
+
+    /// Hand the response to a navigation from this Document to the Document's download handler.
+    /// Embedders which fetch navigations themselves (rather than with
+    /// [`fetch_navigation`](Self::fetch_navigation)) should call this instead of loading a new
+    /// document when [`is_download_response`](bliss_traits::download::is_download_response) returns
+    /// `true` for the response's headers. Returns `false` if the download handler declined the
+    /// download.
+    pub fn download_navigation_response(&self, url: Url, headers: &HeaderMap, body: Bytes) -> bool {
+        download_response(&*self.download_handler, url, self.id, headers, body)
+    }
+
     pub fn print_tree(&self) {
         crate::util::walk_tree(0, self.root_node());
     }
@@ -817,7 +1059,7 @@ impl BaseDocument {
                         let resolved_href = self.resolve_url(href);
                         self.net_provider.fetch(
                             self.id(),
-                            Request::get(resolved_href.clone()),
+                            Request::get(resolved_href.clone()).priority(RequestPriority::High),
                             ResourceHandler::boxed(
                                 self.tx.clone(),
                                 self.id,
@@ -942,6 +1184,18 @@ impl BaseDocument {
         }
     }
 
+    /// Use a loaded `<link rel="icon">` image as the window's icon.
+    /// Only raster images are supported (SVG icons are ignored).
+    pub(crate) fn apply_favicon(&self, image: &ImageData) {
+        if let ImageData::Raster(raster) = image {
+            self.shell_provider.set_window_icon(Some(WindowIcon {
+                rgba: raster.data.data().to_vec(),
+                width: raster.width,
+                height: raster.height,
+            }));
+        }
+    }
+
     pub fn load_resource(&mut self, res: ResourceLoadResponse) {
         let Ok(resource) = res.result else {
             // TODO: handle error
@@ -975,12 +1229,11 @@ impl BaseDocument {
 
                 // Apply to all waiting nodes
                 for (node_id, image_type) in waiting_nodes {
-                    let Some(node) = self.get_node_mut(node_id) else {
-                        continue;
-                    };
-
                     match image_type {
                         ImageType::Image => {
+                            let Some(node) = self.get_node_mut(node_id) else {
+                                continue;
+                            };
                             node.element_data_mut().unwrap().special_data =
                                 SpecialElementData::Image(Box::new(image.clone()));
 
@@ -989,6 +1242,9 @@ impl BaseDocument {
                             node.insert_damage(ALL_DAMAGE);
                         }
                         ImageType::Background(idx) => {
+                            let Some(node) = self.get_node_mut(node_id) else {
+                                continue;
+                            };
                             if let Some(Some(bg_image)) = node
                                 .element_data_mut()
                                 .and_then(|el| el.background_images.get_mut(idx))
@@ -997,6 +1253,7 @@ impl BaseDocument {
                                 bg_image.image = image.clone();
                             }
                         }
+                        ImageType::Favicon => self.apply_favicon(&image),
                     }
                 }
             }
@@ -1023,12 +1280,11 @@ impl BaseDocument {
 
                 // Apply to all waiting nodes
                 for (node_id, image_type) in waiting_nodes {
-                    let Some(node) = self.get_node_mut(node_id) else {
-                        continue;
-                    };
-
                     match image_type {
                         ImageType::Image => {
+                            let Some(node) = self.get_node_mut(node_id) else {
+                                continue;
+                            };
                             node.element_data_mut().unwrap().special_data =
                                 SpecialElementData::Image(Box::new(image.clone()));
 
@@ -1037,6 +1293,9 @@ impl BaseDocument {
                             node.insert_damage(ALL_DAMAGE);
                         }
                         ImageType::Background(idx) => {
+                            let Some(node) = self.get_node_mut(node_id) else {
+                                continue;
+                            };
                             if let Some(Some(bg_image)) = node
                                 .element_data_mut()
                                 .and_then(|el| el.background_images.get_mut(idx))
@@ -1045,6 +1304,7 @@ impl BaseDocument {
                                 bg_image.image = image.clone();
                             }
                         }
+                        ImageType::Favicon => self.apply_favicon(&image),
                     }
                 }
             }
@@ -1161,6 +1421,7 @@ impl BaseDocument {
         let focussed_node_id = self.get_focussed_node_id()?;
         let id = self.next_node(&self.nodes[focussed_node_id], |node| node.is_focussable())?;
         self.set_focus_to(id);
+        self.snapshot_node_and(id, |node| node.show_focus_ring());
         Some(id)
     }
 
@@ -1193,6 +1454,7 @@ impl BaseDocument {
 
         // Focus the new node
         self.snapshot_node_and(focus_node_id, |node| node.focus(shell_provider));
+        self.reset_caret_blink();
 
         self.focus_node_id = Some(focus_node_id);
 
@@ -1301,10 +1563,12 @@ impl BaseDocument {
     }
 
     pub fn set_viewport(&mut self, viewport: Viewport) {
-        let scale_has_changed = viewport.scale_f64() != self.viewport.scale_f64();
+        let scale_has_changed = viewport.scale_f64() != self.viewport.scale_f64()
+            || viewport.effective_text_scale() != self.viewport.effective_text_scale();
         self.viewport = viewport;
         self.set_stylist_device(make_device(&self.viewport, self.font_ctx.clone()));
         self.scroll_viewport_by(0.0, 0.0); // Clamp scroll offset
+        self.apply_system_preferences();
 
         if scale_has_changed {
             self.invalidate_inline_contexts();
@@ -1316,6 +1580,16 @@ impl BaseDocument {
         &self.viewport
     }
 
+    /// Apply the accessibility preferences in the viewport which aren't handled by media queries
+    pub(crate) fn apply_system_preferences(&mut self) {
+        let has_reduced_motion_css = self.ua_stylesheets.contains_key(REDUCED_MOTION_CSS);
+        if self.viewport.reduced_motion && !has_reduced_motion_css {
+            self.add_user_agent_stylesheet(REDUCED_MOTION_CSS);
+        } else if !self.viewport.reduced_motion && has_reduced_motion_css {
+            self.remove_user_agent_stylesheet(REDUCED_MOTION_CSS);
+        }
+    }
+
     pub fn viewport_mut(&mut self) -> ViewportMut<'_> {
         ViewportMut::new(self)
     }
@@ -1330,6 +1604,12 @@ impl BaseDocument {
         self.set_viewport(self.viewport.clone());
     }
 
+    pub fn set_text_scale(&mut self, text_scale: f32) {
+        let mut viewport = self.viewport.clone();
+        viewport.set_text_scale(text_scale);
+        self.set_viewport(viewport);
+    }
+
     pub fn get_viewport(&self) -> Viewport {
         self.viewport.clone()
     }
@@ -1349,6 +1629,117 @@ impl BaseDocument {
             | (self.scroll_animation != ScrollAnimationState::None)
     }
 
+    pub fn caret_shape(&self) -> CaretShape {
+        self.caret_shape
+    }
+
+    pub fn set_caret_shape(&mut self, shape: CaretShape) {
+        self.caret_shape = shape;
+        self.shell_provider.request_redraw();
+    }
+
+    /// Set how long the caret is visible (and then hidden) for when blinking. A zero interval disables blinking.
+    pub fn set_caret_blink_interval(&mut self, interval: Duration) {
+        self.caret_blink_interval = interval;
+        self.shell_provider.request_redraw();
+    }
+
+    /// Make the caret visible and restart its blink cycle (called when focusing or typing into a text input)
+    pub fn reset_caret_blink(&mut self) {
+        self.caret_blink_start = self.animation_time;
+    }
+
+    /// Whether a text input is focused and its caret is blinking
+    fn caret_is_blinking(&self) -> bool {
+        !self.caret_blink_interval.is_zero()
+            && !self.viewport.reduced_motion
+            && self.focus_node_id.is_some_and(|id| {
+                self.nodes[id]
+                    .element_data()
+                    .is_some_and(|el| el.text_input_data().is_some())
+            })
+    }
+
+    /// Whether the caret of the focused text input should currently be drawn
+    pub fn caret_visible(&self) -> bool {
+        let interval = self.caret_blink_interval.as_secs_f64();
+        if interval == 0.0 || self.viewport.reduced_motion {
+            return true;
+        }
+        let elapsed = (self.animation_time - self.caret_blink_start).max(0.0);
+        (elapsed / interval) as u64 % 2 == 0
+    }
+
+    /// The animation time (in seconds) at which the blinking caret of the focused text input is next shown or
+    /// hidden. The caret doesn't make the document animate, so embedders should redraw at this time instead.
+    pub fn next_caret_blink_time(&self) -> Option<f64> {
+        if !self.caret_is_blinking() {
+            return None;
+        }
+        let interval = self.caret_blink_interval.as_secs_f64();
+        let elapsed = (self.animation_time - self.caret_blink_start).max(0.0);
+        let next_toggle = (elapsed / interval).floor() + 1.0;
+        Some(self.caret_blink_start + next_toggle * interval)
+    }
+
+    /// The area of the caret of a text input in logical pixels relative to the viewport
+    fn text_input_caret_area(&self, node_id: usize) -> Option<[f32; 4]> {
+        let node = &self.nodes[node_id];
+        let input_data = node.element_data()?.text_input_data()?;
+        let cursor = input_data.editor.cursor_geometry(1.5)?;
+
+        // The editor's geometry is in physical pixels relative to the input's content box
+        let scale = self.viewport.scale_f64();
+        let layout = &node.final_layout;
+        let pos = node.absolute_position(0.0, 0.0);
+        let x = pos.x as f64 + layout.content_box_x() as f64 + cursor.x0 / scale
+            - self.viewport_scroll.x;
+        let y = pos.y as f64
+            + layout.content_box_y() as f64
+            + node.text_input_v_centering_offset(scale)
+            + cursor.y0 / scale
+            - self.viewport_scroll.y;
+
+        // Convert from CSS pixels to logical pixels
+        let zoom = self.viewport.zoom() as f64;
+        Some([
+            (x * zoom) as f32,
+            (y * zoom) as f32,
+            ((cursor.x1 - cursor.x0) / scale * zoom) as f32,
+            ((cursor.y1 - cursor.y0) / scale * zoom) as f32,
+        ])
+    }
+
+    /// Report the position of the focused text input's caret to the shell so that IME candidate
+    /// windows are shown next to the text being composed. Called after each layout, but the shell
+    /// is only notified when the caret has moved.
+    pub(crate) fn update_ime_cursor_area(&mut self) {
+        let area = self
+            .focus_node_id
+            .and_then(|id| self.text_input_caret_area(id));
+        if let Some([x, y, width, height]) = area {
+            if area != self.ime_cursor_area {
+                self.shell_provider.set_ime_cursor_area(x, y, width, height);
+            }
+        }
+        self.ime_cursor_area = area;
+    }
+
+    /// Make the document's window fill the screen (see [`ShellProvider::set_fullscreen`]).
+    /// This is the native counterpart of `Element.requestFullscreen()`.
+    pub fn request_fullscreen(&self, mode: FullscreenMode) {
+        self.shell_provider.set_fullscreen(mode);
+    }
+
+    /// Return the document's window to its normal size (`Document.exitFullscreen()`)
+    pub fn exit_fullscreen(&self) {
+        self.shell_provider.set_fullscreen(FullscreenMode::Windowed);
+    }
+
+    pub fn fullscreen_mode(&self) -> FullscreenMode {
+        self.shell_provider.fullscreen_mode()
+    }
+
     /// Update the device and reset the stylist to process the new size
     pub fn set_stylist_device(&mut self, device: Device) {
         let origins = {
@@ -1408,6 +1799,33 @@ impl BaseDocument {
         Some(CursorIcon::Default)
     }
 
+    /// Whether the point (in page coordinates) is within a region of the page which should drag
+    /// the window when pressed. These are elements with a `data-bliss-drag-region` attribute (and
+    /// their descendants), which apps with custom window chrome can use for their title bars.
+    ///
+    /// Links, focussable elements and elements with `data-bliss-drag-region="false"` are excluded so
+    /// that controls within a title bar remain usable.
+    pub fn is_drag_region(&self, x: f32, y: f32) -> bool {
+        let Some(hit) = self.hit(x, y) else {
+            return false;
+        };
+
+        let drag_region_attr = LocalName::from("data-bliss-drag-region");
+        let mut maybe_node = Some(&self.nodes[hit.node_id]);
+        while let Some(node) = maybe_node {
+            if node.is_link() || node.is_focussable() {
+                return false;
+            }
+            if let Some(value) = node.attr(drag_region_attr.clone()) {
+                return value != "false";
+            }
+
+            maybe_node = node.layout_parent.get().map(|node_id| node.with(node_id));
+        }
+
+        false
+    }
+
     pub fn scroll_node_by<F: FnMut(DomEvent)>(
         &mut self,
         node_id: usize,
@@ -1893,6 +2311,12 @@ pub struct BoundingRect {
     pub height: f64,
 }
 
+impl Drop for BaseDocument {
+    fn drop(&mut self) {
+        self.net_provider.set_document_url(self.id, None);
+    }
+}
+
 impl AsRef<BaseDocument> for BaseDocument {
     fn as_ref(&self) -> &BaseDocument {
         self
@@ -1904,3 +2328,184 @@ impl AsMut<BaseDocument> for BaseDocument {
         self
     }
 }
+
+#[cfg(test)]
+use bliss_traits::download::{DownloadControl, DownloadSink};
+#[cfg(test)]
+use bliss_traits::net::http::header;
+#[cfg(test)]
+use bliss_traits::net::NetHandler;
+
+/// A net provider which responds to every request with the same body (and its `Content-Length`
+/// and `Content-Type`)
+#[cfg(test)]
+struct StaticNetProvider {
+    body: &'static [u8],
+    content_type: Option<&'static str>,
+    requests: Mutex<Vec<Request>>,
+}
+
+#[cfg(test)]
+impl NetProvider for StaticNetProvider {
+    fn fetch(&self, _doc_id: usize, request: Request, handler: Box<dyn NetHandler>) {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_LENGTH, self.body.len().into());
+        if let Some(content_type) = self.content_type {
+            headers.insert(header::CONTENT_TYPE, content_type.parse().unwrap());
+        }
+        handler.headers(&headers);
+        handler.bytes(request.url.to_string(), Bytes::from_static(self.body));
+        self.requests.lock().unwrap().push(request);
+    }
+}
+
+#[cfg(test)]
+#[derive(Default)]
+struct RecordingDownloads {
+    metadata: Vec<DownloadMetadata>,
+    progress: Vec<(u64, Option<u64>)>,
+    finished: usize,
+    cancelled: usize,
+}
+
+#[cfg(test)]
+struct RecordingDownloadHandler {
+    record: Arc<Mutex<RecordingDownloads>>,
+    cancel: bool,
+}
+
+#[cfg(test)]
+impl DownloadHandler for RecordingDownloadHandler {
+    fn start(&self, metadata: DownloadMetadata) -> Option<Box<dyn DownloadSink>> {
+        self.record.lock().unwrap().metadata.push(metadata);
+        Some(Box::new(RecordingDownloadSink {
+            record: self.record.clone(),
+            cancel: self.cancel,
+        }))
+    }
+}
+
+#[cfg(test)]
+struct RecordingDownloadSink {
+    record: Arc<Mutex<RecordingDownloads>>,
+    cancel: bool,
+}
+
+#[cfg(test)]
+impl DownloadSink for RecordingDownloadSink {
+    fn chunk(&mut self, _bytes: Bytes) -> DownloadControl {
+        match self.cancel {
+            true => DownloadControl::Cancel,
+            false => DownloadControl::Continue,
+        }
+    }
+    fn progress(&mut self, received: u64, total: Option<u64>) {
+        self.record.lock().unwrap().progress.push((received, total));
+    }
+    fn finish(self: Box<Self>) {
+        self.record.lock().unwrap().finished += 1;
+    }
+    fn error(self: Box<Self>, _message: String) {
+        self.record.lock().unwrap().cancelled += 1;
+    }
+}
+
+#[cfg(test)]
+fn download_test_document(
+    cancel: bool,
+    content_type: Option<&'static str>,
+) -> (
+    BaseDocument,
+    Arc<StaticNetProvider>,
+    Arc<Mutex<RecordingDownloads>>,
+) {
+    let net_provider = Arc::new(StaticNetProvider {
+        body: b"hello",
+        content_type,
+        requests: Mutex::new(Vec::new()),
+    });
+    let record = Arc::new(Mutex::new(RecordingDownloads::default()));
+    let doc = BaseDocument::new(DocumentConfig {
+        base_url: Some(String::from("https://example.com/")),
+        net_provider: Some(net_provider.clone()),
+        download_handler: Some(Arc::new(RecordingDownloadHandler {
+            record: record.clone(),
+            cancel,
+        })),
+        ..Default::default()
+    });
+    (doc, net_provider, record)
+}
+
+#[test]
+fn download_attribute_reports_total_from_response() {
+    let (doc, _net_provider, record) = download_test_document(false, None);
+    let url = Url::parse("https://example.com/files/report.pdf").unwrap();
+    doc.start_download(url, Some(String::from("summary.pdf")));
+
+    let record = record.lock().unwrap();
+    assert_eq!(record.metadata.len(), 1);
+    assert_eq!(record.metadata[0].reason, DownloadReason::DownloadAttribute);
+    assert_eq!(
+        record.metadata[0].suggested_filename.as_deref(),
+        Some("summary.pdf")
+    );
+    assert_eq!(record.progress, vec![(5, Some(5))]);
+    assert_eq!(record.finished, 1);
+}
+
+#[test]
+fn unsupported_navigation_is_downloaded() {
+    let (doc, _net_provider, record) = download_test_document(false, Some("application/zip"));
+    let url = Url::parse("https://example.com/archive.zip").unwrap();
+    let loaded = Arc::new(Mutex::new(false));
+    let loaded_flag = loaded.clone();
+    let options = NavigationOptions::new(url, String::new(), doc.id());
+    doc.fetch_navigation(options, move |_| {
+        *loaded_flag.lock().unwrap() = true;
+    });
+    assert!(!*loaded.lock().unwrap());
+
+    let record = record.lock().unwrap();
+    assert_eq!(record.metadata.len(), 1);
+    let metadata = &record.metadata[0];
+    assert_eq!(metadata.reason, DownloadReason::UnsupportedContentType);
+    assert_eq!(metadata.content_type.as_deref(), Some("application/zip"));
+    assert_eq!(metadata.content_length, Some(5));
+    assert_eq!(metadata.suggested_filename.as_deref(), Some("archive.zip"));
+    assert_eq!(record.progress, vec![(5, Some(5))]);
+    assert_eq!(record.finished, 1);
+}
+
+#[test]
+fn renderable_navigation_is_loaded() {
+    let (doc, _net_provider, record) = download_test_document(false, Some("text/html"));
+    let url = Url::parse("https://example.com/page.html").unwrap();
+    let loaded = Arc::new(Mutex::new(None));
+    let loaded_response = loaded.clone();
+    let options = NavigationOptions::new(url, String::new(), doc.id());
+    doc.fetch_navigation(options, move |response| {
+        *loaded_response.lock().unwrap() = Some(response);
+    });
+
+    let loaded = loaded.lock().unwrap();
+    let response = loaded.as_ref().unwrap();
+    assert_eq!(response.url.as_str(), "https://example.com/page.html");
+    assert_eq!(&response.body[..], b"hello");
+    assert!(record.lock().unwrap().metadata.is_empty());
+}
+
+#[test]
+fn cancelled_download_aborts_request() {
+    let (doc, net_provider, record) = download_test_document(true, None);
+    let url = Url::parse("https://example.com/files/report.pdf").unwrap();
+    doc.start_download(url, None);
+
+    let requests = net_provider.requests.lock().unwrap();
+    let signal = requests[0].signal.as_ref().unwrap();
+    assert!(signal.aborted());
+
+    let record = record.lock().unwrap();
+    assert_eq!(record.finished, 0);
+    assert_eq!(record.cancelled, 1);
+}
@@ -1,7 +1,7 @@
 use crate::Document;
 use bliss_traits::events::{
-    BlissPointerEvent, BlissPointerId, DomEvent, DomEventData, EventPhase, EventSink, EventState,
-    UiEvent,
+    BlissDragEvent, BlissPointerEvent, BlissPointerId, DomEvent, DomEventData, EventPhase,
+    EventSink, EventState, UiEvent,
 };
 use std::collections::VecDeque;
 
@@ -187,6 +187,17 @@ impl<'doc, Handler: EventHandler> EventDriver<'doc, Handler> {
                     should_clear_hover = true;
                 }
             }
+            UiEvent::DragEnter(event) => {
+                // Hover state may be stale from before the pointer left the window
+                self.doc.inner_mut().clear_hover();
+                hover_node_id = self.handle_drag_move(event);
+            }
+            UiEvent::DragMove(event) | UiEvent::Drop(event) => {
+                hover_node_id = self.handle_drag_move(event);
+            }
+            UiEvent::DragLeave(_) => {
+                should_clear_hover = true;
+            }
             _ => {}
         };
 
@@ -198,6 +209,12 @@ impl<'doc, Handler: EventHandler> EventDriver<'doc, Handler> {
             UiEvent::KeyUp(_) => focussed_node_id,
             UiEvent::KeyDown(_) => focussed_node_id,
             UiEvent::Ime(_) => focussed_node_id,
+            UiEvent::DragEnter(_) => hover_node_id,
+            UiEvent::DragMove(_) => hover_node_id,
+            UiEvent::DragLeave(_) => hover_node_id,
+            UiEvent::Drop(_) => hover_node_id,
+            // Targets the root element
+            UiEvent::FullscreenChange(_) => None,
         };
         let target = target.unwrap_or_else(|| self.doc.inner().root_element().id);
 
@@ -238,6 +255,18 @@ impl<'doc, Handler: EventHandler> EventDriver<'doc, Handler> {
             UiEvent::Ime(data) => {
                 self.handle_dom_event(DomEvent::new(target, DomEventData::Ime(data)))
             }
+            UiEvent::DragEnter(data) | UiEvent::DragMove(data) => {
+                self.handle_dom_event(DomEvent::new(target, DomEventData::DragOver(data)))
+            }
+            UiEvent::DragLeave(data) => {
+                self.handle_dom_event(DomEvent::new(target, DomEventData::DragLeave(data)))
+            }
+            UiEvent::Drop(data) => {
+                self.handle_dom_event(DomEvent::new(target, DomEventData::Drop(data)))
+            }
+            UiEvent::FullscreenChange(data) => {
+                self.handle_dom_event(DomEvent::new(target, DomEventData::FullscreenChange(data)))
+            }
         };
 
         // Update document input state (hover, focus, active, etc)
@@ -246,6 +275,33 @@ impl<'doc, Handler: EventHandler> EventDriver<'doc, Handler> {
         }
     }
 
+    /// Update the hovered node to follow a drag, dispatching `dragleave` and `dragenter` events
+    /// when the node under the drag changes
+    fn handle_drag_move(&mut self, event: &BlissDragEvent) -> Option<usize> {
+        let mut doc = self.doc.inner_mut();
+        let prev_hover_node_id = doc.hover_node_id;
+        doc.set_hover_to(event.page_x(), event.page_y());
+        let hover_node_id = doc.hover_node_id;
+        drop(doc);
+
+        if hover_node_id != prev_hover_node_id {
+            if let Some(target) = prev_hover_node_id {
+                self.handle_dom_event(DomEvent::new(
+                    target,
+                    DomEventData::DragLeave(event.clone()),
+                ));
+            }
+            if let Some(target) = hover_node_id {
+                self.handle_dom_event(DomEvent::new(
+                    target,
+                    DomEventData::DragEnter(event.clone()),
+                ));
+            }
+        }
+
+        hover_node_id
+    }
+
     pub fn handle_dom_event(&mut self, event: DomEvent) {
         self.queue.push_back(event);
         self.process_queue();
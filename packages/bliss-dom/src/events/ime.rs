@@ -14,6 +14,7 @@ pub(crate) fn handle_ime_event<F: FnMut(DomEvent)>(
             .downcast_element_mut()
             .and_then(|el| el.text_input_data_mut());
         if let Some(input_data) = text_input_data {
+            doc.caret_blink_start = doc.animation_time;
             let editor = &mut input_data.editor;
             let mut font_ctx = doc.font_ctx.lock().unwrap();
             let mut driver = editor.driver(&mut font_ctx, &mut doc.layout_ctx);
@@ -63,6 +63,9 @@ pub(crate) fn handle_keypress<F: FnMut(DomEvent)>(
         };
 
         if let Some(input_data) = element_data.text_input_data_mut() {
+            // Keep the caret visible while typing
+            doc.caret_blink_start = doc.animation_time;
+
             let generated_event = apply_keypress_event(
                 input_data,
                 &mut doc.font_ctx.lock().unwrap(),
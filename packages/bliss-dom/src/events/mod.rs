@@ -5,13 +5,16 @@ mod keyboard;
 mod pointer;
 
 use crate::util::Point;
-use bliss_traits::events::{DomEvent, DomEventData, PointerCoords, UiEvent};
+use bliss_traits::events::{DomEvent, DomEventData, MouseEventButton, PointerCoords, UiEvent};
 pub use driver::{EventDriver, EventHandler, NoopEventHandler};
 use focus::generate_focus_events;
 pub(crate) use ime::handle_ime_event;
 pub(crate) use keyboard::handle_keypress;
 pub(crate) use pointer::{DragMode, ScrollAnimationState};
-use pointer::{handle_click, handle_pointerdown, handle_pointermove, handle_pointerup};
+use pointer::{
+    handle_click, handle_contextmenu, handle_drop, handle_pointerdown, handle_pointermove,
+    handle_pointerup,
+};
 
 use crate::{BaseDocument, events::pointer::handle_wheel};
 
@@ -88,6 +91,22 @@ pub(crate) fn handle_dom_event<F: FnMut(DomEvent)>(
             DomEventData::Blur(_) => None,
             DomEventData::FocusIn(_) => None,
             DomEventData::FocusOut(_) => None,
+
+            DomEventData::DragEnter(mut event) => {
+                adjust_coords_for_subdocument(&mut event.coords, pos, viewport_scroll);
+                Some(UiEvent::DragEnter(event))
+            }
+            DomEventData::DragOver(mut event) => {
+                adjust_coords_for_subdocument(&mut event.coords, pos, viewport_scroll);
+                Some(UiEvent::DragMove(event))
+            }
+            DomEventData::DragLeave(event) => Some(UiEvent::DragLeave(event)),
+            DomEventData::Drop(mut event) => {
+                adjust_coords_for_subdocument(&mut event.coords, pos, viewport_scroll);
+                Some(UiEvent::Drop(event))
+            }
+
+            DomEventData::FullscreenChange(data) => Some(UiEvent::FullscreenChange(data)),
         };
 
         if let Some(ui_event) = ui_event {
@@ -118,6 +137,16 @@ pub(crate) fn handle_dom_event<F: FnMut(DomEvent)>(
             // Do nothing (handled in PointerMove)
         }
         DomEventData::PointerDown(event) => {
+            // Right-clicking the focussed text input keeps its selection so that it can be cut or
+            // copied using the context menu
+            let is_focussed_text_input = doc.focus_node_id == Some(target_node_id)
+                && doc.nodes[target_node_id]
+                    .element_data()
+                    .is_some_and(|el| el.text_input_data().is_some());
+            if event.button == MouseEventButton::Secondary && is_focussed_text_input {
+                return;
+            }
+
             handle_pointerdown(
                 doc,
                 target_node_id,
@@ -154,8 +183,8 @@ pub(crate) fn handle_dom_event<F: FnMut(DomEvent)>(
         DomEventData::Input(_) => {
             // Do nothing (no default action)
         }
-        DomEventData::ContextMenu(_) => {
-            // TODO: Open context menu
+        DomEventData::ContextMenu(event) => {
+            handle_contextmenu(doc, target_node_id, event);
         }
         DomEventData::DoubleClick(_) => {
             // Do nothing (no default action)
@@ -202,5 +231,20 @@ pub(crate) fn handle_dom_event<F: FnMut(DomEvent)>(
         DomEventData::FocusOut(_) => {
             // Do nothing (no default action)
         }
+        DomEventData::DragEnter(_) => {
+            // Do nothing (no default action)
+        }
+        DomEventData::DragOver(_) => {
+            // Do nothing (no default action)
+        }
+        DomEventData::DragLeave(_) => {
+            // Do nothing (no default action)
+        }
+        DomEventData::Drop(event) => {
+            handle_drop(doc, target_node_id, event);
+        }
+        DomEventData::FullscreenChange(_) => {
+            // Do nothing (no default action)
+        }
     }
 }
@@ -5,10 +5,11 @@ use std::{
 
 use bliss_traits::{
     events::{
-        BlissInputEvent, BlissPointerEvent, BlissPointerId, BlissWheelDelta, BlissWheelEvent,
-        DomEvent, DomEventData, MouseEventButton, MouseEventButtons,
+        BlissDragEvent, BlissInputEvent, BlissPointerEvent, BlissPointerId, BlissWheelDelta,
+        BlissWheelEvent, DomEvent, DomEventData, MouseEventButton, MouseEventButtons,
     },
     navigation::NavigationOptions,
+    shell::ContextMenu,
 };
 use keyboard_types::Modifiers;
 use markup5ever::local_name;
@@ -366,6 +367,7 @@ pub(crate) fn handle_pointerdown(
                 }
 
                 drop(font_ctx);
+                doc.caret_blink_start = doc.animation_time;
             }
 
             generate_focus_events(
@@ -431,6 +433,99 @@ pub(crate) fn handle_pointerup<F: FnMut(DomEvent)>(
     }
 }
 
+/// Show the default context menu (cut/copy/paste) for text inputs
+pub(crate) fn handle_contextmenu(doc: &mut BaseDocument, target: usize, event: &BlissPointerEvent) {
+    let Some(input_data) = doc
+        .get_node(target)
+        .and_then(|node| node.element_data())
+        .and_then(|el| el.text_input_data())
+    else {
+        return;
+    };
+
+    let has_selection = input_data.editor.selected_text().is_some();
+    doc.shell_provider.show_context_menu(
+        ContextMenu::edit_menu(has_selection),
+        event.client_x(),
+        event.client_y(),
+    );
+}
+
+/// Set the files selected by a file input, updating its label
+#[cfg(feature = "file_input")]
+fn set_file_input_files(doc: &mut BaseDocument, node_id: usize, files: Vec<std::path::PathBuf>) {
+    use crate::qual_name;
+
+    let Some(el) = doc.nodes[node_id].element_data_mut() else {
+        return;
+    };
+
+    if let Some(file) = files.first() {
+        el.attrs
+            .set(qual_name!("value", html), &file.to_string_lossy());
+    }
+    let text_content = match files.len() {
+        0 => "No Files Selected".to_string(),
+        1 => files
+            .first()
+            .unwrap()
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string(),
+        x => format!("{x} Files Selected"),
+    };
+
+    if files.is_empty() {
+        el.special_data = SpecialElementData::None;
+    } else {
+        el.special_data = SpecialElementData::FileInput(files.into())
+    }
+    let child_label_id = doc.nodes[node_id].children[1];
+    let child_text_id = doc.nodes[child_label_id].children[0];
+    let text_data = doc.nodes[child_text_id]
+        .text_data_mut()
+        .expect("Text data not found");
+    text_data.content = text_content;
+}
+
+/// Dropping files onto a file input selects them
+pub(crate) fn handle_drop(doc: &mut BaseDocument, target: usize, event: &BlissDragEvent) {
+    #[cfg(feature = "file_input")]
+    {
+        if event.data_transfer.files.is_empty() {
+            return;
+        }
+
+        // The target may be the file input's label, so look for the input in its ancestors
+        let mut maybe_node_id = Some(target);
+        while let Some(node_id) = maybe_node_id {
+            let node = &doc.nodes[node_id];
+            if let Some(el) = node.element_data() {
+                if el.name.local == local_name!("input")
+                    && el.attr(local_name!("type")) == Some("file")
+                {
+                    if el.attr(local_name!("disabled")).is_some() {
+                        return;
+                    }
+
+                    let mut files = event.data_transfer.files.clone();
+                    if el.attr(local_name!("multiple")).is_none() {
+                        files.truncate(1);
+                    }
+                    set_file_input_files(doc, node_id, files);
+                    doc.shell_provider.request_redraw();
+                    return;
+                }
+            }
+            maybe_node_id = node.parent;
+        }
+    }
+
+    #[cfg(not(feature = "file_input"))]
+    let _ = (doc, target, event);
+}
+
 pub(crate) fn handle_click(
     doc: &mut BaseDocument,
     target: usize,
@@ -513,12 +608,20 @@ pub(crate) fn handle_click(
                 }
                 local_name!("a") => {
                     if let Some(href) = el.attr(local_name!("href")) {
+                        let download = el.attr(local_name!("download")).map(str::to_string);
                         if let Some(url) = doc.url.resolve_relative(href) {
-                            doc.navigation_provider.navigate_to(NavigationOptions::new(
-                                url,
-                                String::from("text/plain"),
-                                doc.id(),
-                            ));
+                            if download.is_some() {
+                                doc.start_download(url, download);
+                                break 'matched true;
+                            }
+                            let target = el
+                                .attr(local_name!("target"))
+                                .map(str::to_string)
+                                .or_else(|| doc.base_target.clone());
+                            doc.navigation_provider.navigate_to(
+                                NavigationOptions::new(url, String::from("text/plain"), doc.id())
+                                    .set_target(target),
+                            );
                         } else {
                             println!("{href} is not parseable as a url. : {:?}", *doc.url)
                         }
@@ -536,38 +639,10 @@ pub(crate) fn handle_click(
                 }
                 #[cfg(feature = "file_input")]
                 local_name!("input") if el.attr(local_name!("type")) == Some("file") => {
-                    use crate::qual_name;
                     //TODO: Handle accept attribute https://developer.mozilla.org/en-US/docs/Web/HTML/Reference/Attributes/accept by passing an appropriate filter
                     let multiple = el.attr(local_name!("multiple")).is_some();
                     let files = doc.shell_provider.open_file_dialog(multiple, None);
-
-                    if let Some(file) = files.first() {
-                        el.attrs
-                            .set(qual_name!("value", html), &file.to_string_lossy());
-                    }
-                    let text_content = match files.len() {
-                        0 => "No Files Selected".to_string(),
-                        1 => files
-                            .first()
-                            .unwrap()
-                            .file_name()
-                            .unwrap_or_default()
-                            .to_string_lossy()
-                            .to_string(),
-                        x => format!("{x} Files Selected"),
-                    };
-
-                    if files.is_empty() {
-                        el.special_data = SpecialElementData::None;
-                    } else {
-                        el.special_data = SpecialElementData::FileInput(files.into())
-                    }
-                    let child_label_id = doc.nodes[node_id].children[1];
-                    let child_text_id = doc.nodes[child_label_id].children[0];
-                    let text_data = doc.nodes[child_text_id]
-                        .text_data_mut()
-                        .expect("Text data not found");
-                    text_data.content = text_content;
+                    set_file_input_files(doc, node_id, files);
                 }
                 _ => {}
             }
@@ -203,65 +203,19 @@ pub(crate) fn collect_layout_children(
             doc.nodes[container_node_id].children = children;
         }
         DisplayInside::Flow | DisplayInside::FlowRoot | DisplayInside::TableCell => {
-            // TODO: make "all_inline" detection work in the presence of display:contents nodes
-            let mut all_block = true;
-            let mut all_inline = true;
-            let mut all_out_of_flow = true;
-            let mut has_contents = false;
-            for child in doc.nodes[container_node_id]
-                .children
-                .iter()
-                .copied()
-                .map(|child_id| &doc.nodes[child_id])
-            {
-                // Unwraps on Text and SVG nodes
-                let style = child.primary_styles();
-                let style = style.as_ref();
-                let display = style
-                    .map(|s| s.clone_display())
-                    .unwrap_or(Display::inline());
-                if matches!(display.inside(), DisplayInside::Contents) {
-                    has_contents = true;
-                    all_out_of_flow = false;
-                } else {
-                    let position = style
-                        .map(|s| s.clone_position())
-                        .unwrap_or(PositionProperty::Static);
-                    let float = style.map(|s| s.clone_float()).unwrap_or(Float::None);
-
-                    // Ignore nodes that are entirely whitespace
-                    if child.is_whitespace_node() {
-                        continue;
-                    }
-
-                    let is_in_flow = matches!(
-                        position,
-                        PositionProperty::Static
-                            | PositionProperty::Relative
-                            | PositionProperty::Sticky
-                    ) && matches!(float, Float::None);
-
-                    if !is_in_flow {
-                        continue;
-                    }
-
-                    all_out_of_flow = false;
-                    match display.outside() {
-                        DisplayOutside::None => {}
-                        DisplayOutside::Block
-                        | DisplayOutside::TableCaption
-                        | DisplayOutside::InternalTable => all_inline = false,
-                        DisplayOutside::Inline => {
-                            all_block = false;
-
-                            // We need the "complex" tree fixing when an inline contains a block
-                            if child.is_or_contains_block() {
-                                all_inline = false;
-                            }
-                        }
-                    }
-                }
-            }
+            let mut flags = FlowChildFlags {
+                all_block: true,
+                all_inline: true,
+                all_out_of_flow: true,
+                has_contents: false,
+            };
+            classify_flow_children(doc, container_node_id, &mut flags);
+            let FlowChildFlags {
+                all_block,
+                all_inline,
+                all_out_of_flow,
+                has_contents,
+            } = flags;
 
             if all_out_of_flow {
                 return push_non_whitespace_children_and_pseudos(
@@ -270,7 +224,6 @@ pub(crate) fn collect_layout_children(
                 );
             }
 
-            // TODO: fix display:contents
             if all_inline {
                 let existing_layout = doc.nodes[container_node_id]
                     .element_data_mut()
@@ -471,6 +424,72 @@ fn flush_pseudo_elements(doc: &mut BaseDocument, node_id: usize) {
     }
 }
 
+struct FlowChildFlags {
+    all_block: bool,
+    all_inline: bool,
+    all_out_of_flow: bool,
+    has_contents: bool,
+}
+
+/// Classify the in-flow children of a flow container. The children of `display: contents` nodes
+/// participate in the container's layout directly, so they are classified in place of the node itself.
+fn classify_flow_children(doc: &BaseDocument, parent_id: usize, flags: &mut FlowChildFlags) {
+    for child in doc.nodes[parent_id]
+        .children
+        .iter()
+        .copied()
+        .map(|child_id| &doc.nodes[child_id])
+    {
+        // Unwraps on Text and SVG nodes
+        let style = child.primary_styles();
+        let style = style.as_ref();
+        let display = style
+            .map(|s| s.clone_display())
+            .unwrap_or(Display::inline());
+        if stylo_taffy::convert::is_display_contents(display) {
+            flags.has_contents = true;
+            flags.all_out_of_flow = false;
+            classify_flow_children(doc, child.id, flags);
+            continue;
+        }
+
+        let position = style
+            .map(|s| s.clone_position())
+            .unwrap_or(PositionProperty::Static);
+        let float = style.map(|s| s.clone_float()).unwrap_or(Float::None);
+
+        // Ignore nodes that are entirely whitespace
+        if child.is_whitespace_node() {
+            continue;
+        }
+
+        let is_in_flow = matches!(
+            position,
+            PositionProperty::Static | PositionProperty::Relative | PositionProperty::Sticky
+        ) && matches!(float, Float::None);
+
+        if !is_in_flow {
+            continue;
+        }
+
+        flags.all_out_of_flow = false;
+        match display.outside() {
+            DisplayOutside::None => {}
+            DisplayOutside::Block
+            | DisplayOutside::TableCaption
+            | DisplayOutside::InternalTable => flags.all_inline = false,
+            DisplayOutside::Inline => {
+                flags.all_block = false;
+
+                // We need the "complex" tree fixing when an inline contains a block
+                if child.is_or_contains_block() {
+                    flags.all_inline = false;
+                }
+            }
+        }
+    }
+}
+
 /// Handles the cases where there are text nodes or inline nodes that need to be wrapped in an anonymous block node
 fn collect_complex_layout_children(
     doc: &mut BaseDocument,
@@ -597,11 +616,12 @@ fn collect_complex_layout_children(
 }
 
 fn create_text_editor(doc: &mut BaseDocument, input_element_id: usize, is_multiline: bool) {
+    let text_scale = doc.viewport.effective_text_scale();
     let node = &mut doc.nodes[input_element_id];
     let parley_style = node
         .primary_styles()
         .as_ref()
-        .map(|s| stylo_to_parley::style(node.id, s))
+        .map(|s| stylo_to_parley::style(node.id, s, text_scale))
         .unwrap_or_default();
 
     let element = &mut node.data.downcast_element_mut().unwrap();
@@ -790,6 +810,7 @@ pub(crate) fn build_inline_layout_into(
     font_ctx: &mut FontContext,
     text_layout: &mut TextLayout,
     scale: f32,
+    text_scale: f32,
     inline_context_root_node_id: usize,
 ) {
     // Get the inline context's root node's text styles
@@ -802,7 +823,7 @@ pub(crate) fn build_inline_layout_into(
 
     let parley_style = root_node_style
         .as_ref()
-        .map(|s| stylo_to_parley::style(inline_context_root_node_id, s))
+        .map(|s| stylo_to_parley::style(inline_context_root_node_id, s, text_scale))
         .unwrap_or_default();
 
     let root_line_height = resolve_line_height(parley_style.line_height, parley_style.font_size);
@@ -839,6 +860,7 @@ pub(crate) fn build_inline_layout_into(
             before_id,
             collapse_mode,
             root_line_height,
+            text_scale,
         );
     }
     for child_id in root_node.children.iter().copied() {
@@ -849,6 +871,7 @@ pub(crate) fn build_inline_layout_into(
             child_id,
             collapse_mode,
             root_line_height,
+            text_scale,
         );
     }
     if let Some(after_id) = root_node.after {
@@ -859,6 +882,7 @@ pub(crate) fn build_inline_layout_into(
             after_id,
             collapse_mode,
             root_line_height,
+            text_scale,
         );
     }
 
@@ -872,6 +896,7 @@ pub(crate) fn build_inline_layout_into(
         node_id: usize,
         collapse_mode: WhiteSpaceCollapse,
         root_line_height: f32,
+        text_scale: f32,
     ) {
         let node = &nodes[node_id];
 
@@ -924,6 +949,7 @@ pub(crate) fn build_inline_layout_into(
                                 child_id,
                                 collapse_mode,
                                 root_line_height,
+                                text_scale,
                             );
                         }
                     }
@@ -957,7 +983,7 @@ pub(crate) fn build_inline_layout_into(
                             // node.remove_damage(CONSTRUCT_DESCENDENT | CONSTRUCT_FC | CONSTRUCT_BOX);
                             let mut style = node
                                 .primary_styles()
-                                .map(|s| stylo_to_parley::style(node.id, &s))
+                                .map(|s| stylo_to_parley::style(node.id, &s, text_scale))
                                 .unwrap_or_default();
 
                             // dbg!(&style);
@@ -984,6 +1010,7 @@ pub(crate) fn build_inline_layout_into(
                                     before_id,
                                     collapse_mode,
                                     root_line_height,
+                                    text_scale,
                                 );
                             }
 
@@ -995,6 +1022,7 @@ pub(crate) fn build_inline_layout_into(
                                     child_id,
                                     collapse_mode,
                                     root_line_height,
+                                    text_scale,
                                 );
                             }
                             if let Some(after_id) = node.after {
@@ -1005,6 +1033,7 @@ pub(crate) fn build_inline_layout_into(
                                     after_id,
                                     collapse_mode,
                                     root_line_height,
+                                    text_scale,
                                 );
                             }
 
@@ -1,4 +1,5 @@
 use std::ops::Range;
+use std::sync::Arc;
 
 use crate::net::ResourceHandler;
 use crate::node::NodeFlags;
@@ -6,7 +7,7 @@ use crate::{
     BaseDocument, net::ImageHandler, node::BackgroundImageData, node::Status, util::ImageType,
 };
 use crate::{NON_INCREMENTAL, Node};
-use bliss_traits::net::Request;
+use bliss_traits::net::{Request, RequestPriority};
 use style::properties::ComputedValues;
 use style::properties::generated::longhands::position::computed_value::T as Position;
 use style::selector_parser::RestyleDamage;
@@ -367,6 +368,9 @@ impl BaseDocument {
 
     pub fn flush_styles_to_layout(&mut self, node_id: usize) {
         self.flush_styles_to_layout_impl(node_id, None);
+        if let Some(cache) = &mut self.style_cache {
+            cache.evict_unused();
+        }
     }
 
     /// Walk the whole tree, converting styles to layout
@@ -393,7 +397,10 @@ impl BaseDocument {
             };
 
             // if damage.intersects(RestyleDamage::RELAYOUT | CONSTRUCT_BOX) {
-            node.style = stylo_taffy::to_taffy_style(style);
+            node.style = match &mut self.style_cache {
+                Some(cache) => Arc::clone(cache.get_or_convert(style)),
+                None => Arc::new(stylo_taffy::to_taffy_style(style)),
+            };
             node.display_constructed_as = style.clone_display();
             // }
 
@@ -446,7 +453,8 @@ impl BaseDocument {
 
                                 self.net_provider.fetch(
                                     doc_id,
-                                    Request::get((**new_url).clone()),
+                                    Request::get((**new_url).clone())
+                                        .priority(RequestPriority::Low),
                                     ResourceHandler::boxed(
                                         self.tx.clone(),
                                         doc_id,
@@ -499,6 +507,40 @@ impl BaseDocument {
                 );
             }
 
+            if display == taffy::Display::Grid {
+                // Grid item placement is relative to the axes of the grid container
+                let writing_mode = self.nodes[node_id]
+                    .primary_styles()
+                    .map(|style| style.writing_mode);
+                if let Some(writing_mode) = writing_mode.filter(|mode| mode.is_vertical()) {
+                    for &child in children.iter() {
+                        stylo_taffy::convert::apply_grid_item_writing_mode(
+                            Arc::make_mut(&mut self.nodes[child].style),
+                            writing_mode,
+                        );
+                    }
+                }
+
+                // Resolve placements referencing named grid areas (`grid-area: header`) into line-based placements
+                let container_style = Arc::clone(&self.nodes[node_id].style);
+                let areas = &container_style.grid_template_areas;
+                if !areas.is_empty() {
+                    for &child in children.iter() {
+                        let style = Arc::make_mut(&mut self.nodes[child].style);
+                        style.grid_row = stylo_taffy::convert::resolve_area_placement(
+                            areas,
+                            style.grid_row.clone(),
+                            true,
+                        );
+                        style.grid_column = stylo_taffy::convert::resolve_area_placement(
+                            areas,
+                            style.grid_column.clone(),
+                            false,
+                        );
+                    }
+                }
+            }
+
             // Sort layout_children
             if is_flex_or_grid {
                 children.sort_by(|left, right| {
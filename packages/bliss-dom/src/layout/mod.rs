@@ -10,8 +10,6 @@ use markup5ever::local_name;
 use std::cell::Ref;
 use std::sync::Arc;
 use style::Atom;
-use style::values::computed::CSSPixelLength;
-use style::values::computed::length_percentage::CalcLengthPercentage;
 use taffy::{
     BlockContext, CollapsibleMarginSet, FlexDirection, LayoutPartialTree, NodeId, ResolveOrZero,
     RoundTree, Style, TraversePartialTree, TraverseTree, compute_block_layout,
@@ -30,9 +28,9 @@ use self::replaced::{ReplacedContext, replaced_measure_function};
 use self::table::TableTreeWrapper;
 
 pub(crate) fn resolve_calc_value(calc_ptr: *const (), parent_size: f32) -> f32 {
-    let calc = unsafe { &*(calc_ptr as *const CalcLengthPercentage) };
-    let result = calc.resolve(CSSPixelLength::new(parent_size));
-    result.px()
+    // SAFETY: Taffy only resolves calc values from node styles, which are converted by stylo_taffy
+    // and kept alive (along with the computed values they point into) by the node
+    unsafe { stylo_taffy::convert::resolve_calc(calc_ptr, parent_size) }
 }
 
 impl BaseDocument {
@@ -111,7 +109,7 @@ impl BaseDocument {
 
                     return compute_leaf_layout(
                         inputs,
-                        &node.style,
+                        &*node.style,
                         resolve_calc_value,
                         |_known_size, _available_space| taffy::Size {
                             width: cols
@@ -126,13 +124,13 @@ impl BaseDocument {
                     match element_data.attr(local_name!("type")) {
                         // if the input type is hidden, hide it
                         Some("hidden") => {
-                            node.style.display = Display::None;
+                            Arc::make_mut(&mut node.style).display = Display::None;
                             return taffy::LayoutOutput::HIDDEN;
                         }
                         Some("checkbox") => {
                             return compute_leaf_layout(
                                 inputs,
-                                &node.style,
+                                &*node.style,
                                 resolve_calc_value,
                                 |_known_size, _available_space| {
                                     let width = node.style.size.width.resolve_or_zero(
@@ -154,7 +152,7 @@ impl BaseDocument {
                         None | Some("text" | "password" | "email" | "tel" | "url" | "search") => {
                             return compute_leaf_layout(
                                 inputs,
-                                &node.style,
+                                &*node.style,
                                 resolve_calc_value,
                                 |_known_size, _available_space| taffy::Size {
                                     width: 300.0,
@@ -215,7 +213,7 @@ impl BaseDocument {
                         inputs.parent_size,
                         inputs.available_space,
                         &replaced_context,
-                        &node.style,
+                        &*node.style,
                         false,
                     );
 
@@ -257,6 +255,40 @@ impl BaseDocument {
                     return self.compute_inline_layout(usize::from(node_id), inputs, block_ctx);
                 }
 
+                // Size containment: the element is sized as if it had no contents, so sizing passes
+                // don't need to lay out its subtree at all.
+                let containment = node
+                    .primary_styles()
+                    .map(|style| stylo_taffy::convert::containment(&style))
+                    .unwrap_or_default();
+                let inputs = if containment.size || containment.inline_size {
+                    let contained_output = compute_leaf_layout(
+                        inputs,
+                        &*node.style,
+                        resolve_calc_value,
+                        |_known_size, _available_space| taffy::Size::ZERO,
+                    );
+                    if containment.skips_intrinsic_sizing()
+                        && inputs.run_mode == taffy::RunMode::ComputeSize
+                    {
+                        return contained_output;
+                    }
+
+                    let known_dimensions = taffy::Size {
+                        width: Some(contained_output.size.width),
+                        height: match containment.size {
+                            true => Some(contained_output.size.height),
+                            false => inputs.known_dimensions.height,
+                        },
+                    };
+                    taffy::LayoutInput {
+                        known_dimensions,
+                        ..inputs
+                    }
+                } else {
+                    inputs
+                };
+
                 // The default CSS file will set
                 match node.style.display {
                     Display::Block => compute_block_layout(self, node_id, inputs, block_ctx),
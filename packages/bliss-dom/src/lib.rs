@@ -69,7 +69,7 @@ pub mod util;
 #[cfg(feature = "accessibility")]
 mod accessibility;
 
-pub use config::DocumentConfig;
+pub use config::{CaretShape, DocumentConfig};
 pub use document::{BaseDocument, DocGuard, DocGuardMut, Document, PlainDocument};
 pub use markup5ever::{
     LocalName, Namespace, NamespaceStaticSet, Prefix, PrefixStaticSet, QualName, local_name,
@@ -79,8 +79,8 @@ pub use mutator::DocumentMutator;
 pub use node::{Attribute, ElementData, Node, NodeData, TextNodeData};
 pub use parley::FontContext;
 pub use script::{
-    BoxedScriptEngine, EventHandled, ExecutionContext, NoopScriptEngine, ScriptEngine, ScriptError,
-    ScriptErrorCallback, ScriptLanguage, ScriptValue,
+    BoxedScriptEngine, DocumentWriteError, EventHandled, ExecutionContext, NoopScriptEngine,
+    ScriptEngine, ScriptError, ScriptErrorCallback, ScriptLanguage, ScriptValue,
 };
 pub use style::Atom;
 pub use style::invalidation::element::restyle_hints::RestyleHint;
@@ -10,7 +10,7 @@ use crate::util::ImageType;
 use crate::{
     Attribute, BaseDocument, Document, ElementData, Node, NodeData, QualName, local_name, qual_name,
 };
-use bliss_traits::net::Request;
+use bliss_traits::net::{Request, RequestPriority};
 use bliss_traits::shell::Viewport;
 use style::Atom;
 use style::invalidation::element::restyle_hints::RestyleHint;
@@ -33,6 +33,7 @@ pub enum AppendTextErr {
 enum SpecialOp {
     LoadImage(usize),
     LoadStylesheet(usize),
+    LoadIcon(usize),
     UnloadStylesheet(usize),
     LoadCustomPaintSource(usize),
     ProcessButtonInput(usize),
@@ -53,6 +54,9 @@ pub struct DocumentMutator<'doc> {
     /// Whether an element/attribute that affect animation status has been seen
     recompute_is_animating: bool,
 
+    /// Whether a `<base>` element has been added, removed or modified
+    recompute_base_url: bool,
+
     /// The (latest) node which has been mounted in and had autofocus=true, if any
     #[cfg(feature = "autofocus")]
     node_to_autofocus: Option<usize>,
@@ -73,6 +77,7 @@ impl DocumentMutator<'_> {
             style_nodes: HashSet::new(),
             form_nodes: HashSet::new(),
             recompute_is_animating: false,
+            recompute_base_url: false,
             #[cfg(feature = "autofocus")]
             node_to_autofocus: None,
         }
@@ -147,6 +152,46 @@ impl DocumentMutator<'_> {
         self.doc.deep_clone_node(node_id)
     }
 
+    /// Get the id of the (inert) fragment node holding the contents of a `<template>` element,
+    /// creating it if it does not yet exist. The fragment is never attached to the document, so its
+    /// contents are not styled, rendered, or loaded.
+    pub fn template_contents(&mut self, template_id: usize) -> usize {
+        if let Some(contents_id) = self.doc.nodes[template_id]
+            .element_data()
+            .and_then(|el| el.template_contents)
+        {
+            return contents_id;
+        }
+
+        // There is no DocumentFragment node type, so a detached Document node is used instead
+        let contents_id = self.doc.create_node(NodeData::Document);
+        if let Some(el) = self.doc.nodes[template_id].element_data_mut() {
+            el.template_contents = Some(contents_id);
+        }
+        contents_id
+    }
+
+    /// Clone the contents of a `<template>` element, returning the ids of the (detached) top-level
+    /// nodes of the clone. These can then be attached to the document (e.g. with [`append_children`](Self::append_children)).
+    pub fn instantiate_template(&mut self, template_id: usize) -> Vec<usize> {
+        let Some(contents_id) = self.doc.nodes[template_id]
+            .element_data()
+            .and_then(|el| el.template_contents)
+        else {
+            return Vec::new();
+        };
+
+        let child_ids = self.doc.nodes[contents_id].children.clone();
+        child_ids
+            .into_iter()
+            .map(|child_id| {
+                let clone_id = self.doc.deep_clone_node(child_id);
+                self.doc.nodes[clone_id].parent = None;
+                clone_id
+            })
+            .collect()
+    }
+
     // Node mutation methods
 
     pub fn set_node_text(&mut self, node_id: usize, value: &str) {
@@ -277,7 +322,11 @@ impl DocumentMutator<'_> {
             return;
         }
 
-        if (tag, attr) == tag_and_attr!("input", "checked") {
+        if (tag, attr) == tag_and_attr!("base", "href")
+            || (tag, attr) == tag_and_attr!("base", "target")
+        {
+            self.recompute_base_url = true;
+        } else if (tag, attr) == tag_and_attr!("input", "checked") {
             set_input_checked_state(element, value.to_string());
         } else if (tag, attr) == tag_and_attr!("img", "src") {
             self.load_image(node_id);
@@ -285,6 +334,7 @@ impl DocumentMutator<'_> {
             self.load_custom_paint_src(node_id);
         } else if (tag, attr) == tag_and_attr!("link", "href") {
             self.load_linked_stylesheet(node_id);
+            self.load_linked_icon(node_id);
         }
     }
 
@@ -343,6 +393,10 @@ impl DocumentMutator<'_> {
             self.recompute_is_animating = true;
         } else if (tag, attr) == tag_and_attr!("link", "href") {
             self.unload_stylesheet(node_id);
+        } else if (tag, attr) == tag_and_attr!("base", "href")
+            || (tag, attr) == tag_and_attr!("base", "target")
+        {
+            self.recompute_base_url = true;
         }
     }
 
@@ -529,6 +583,10 @@ impl DocumentMutator<'_> {
 
 impl<'doc> DocumentMutator<'doc> {
     pub fn flush(&mut self) {
+        if mem::take(&mut self.recompute_base_url) {
+            self.doc.update_base_url();
+        }
+
         if self.recompute_is_animating {
             self.doc.has_canvas = self.doc.compute_has_canvas();
         }
@@ -569,6 +627,7 @@ impl<'doc> DocumentMutator<'doc> {
             match op {
                 SpecialOp::LoadImage(node_id) => self.load_image(node_id),
                 SpecialOp::LoadStylesheet(node_id) => self.load_linked_stylesheet(node_id),
+                SpecialOp::LoadIcon(node_id) => self.load_linked_icon(node_id),
                 SpecialOp::UnloadStylesheet(node_id) => self.unload_stylesheet(node_id),
                 SpecialOp::LoadCustomPaintSource(node_id) => self.load_custom_paint_src(node_id),
                 SpecialOp::ProcessButtonInput(node_id) => self.process_button_input(node_id),
@@ -598,7 +657,11 @@ impl<'doc> DocumentMutator<'doc> {
             let tag = element.name.local.as_ref();
             match tag {
                 "title" => self.title_node = Some(node_id),
-                "link" => self.eager_op_queue.push(SpecialOp::LoadStylesheet(node_id)),
+                "base" => self.recompute_base_url = true,
+                "link" => {
+                    self.eager_op_queue.push(SpecialOp::LoadStylesheet(node_id));
+                    self.eager_op_queue.push(SpecialOp::LoadIcon(node_id));
+                }
                 "img" => self.eager_op_queue.push(SpecialOp::LoadImage(node_id)),
                 "canvas" => self
                     .eager_op_queue
@@ -626,6 +689,11 @@ impl<'doc> DocumentMutator<'doc> {
             }
         });
 
+        // Update the base URL before loading any resources which may depend on it
+        if mem::take(&mut self.recompute_base_url) {
+            self.doc.update_base_url();
+        }
+
         self.flush_eager_ops();
     }
 
@@ -643,6 +711,10 @@ impl<'doc> DocumentMutator<'doc> {
                 return;
             };
 
+            if element.name.local == local_name!("base") {
+                self.recompute_base_url = true;
+            }
+
             match &element.special_data {
                 SpecialElementData::SubDocument(_) => {}
                 SpecialElementData::Stylesheet(_) => self
@@ -669,6 +741,11 @@ impl<'doc> DocumentMutator<'doc> {
             return;
         };
 
+        // Nodes outside of the document (such as template contents) are inert
+        if !self.doc.nodes[node_id].flags.is_in_document() {
+            return;
+        }
+
         let Some(tag_name) = self.doc.nodes[node_id]
             .data
             .downcast_element()
@@ -702,7 +779,7 @@ impl<'doc> DocumentMutator<'doc> {
         let url = self.doc.resolve_url(href);
         self.doc.net_provider.fetch(
             self.doc.id(),
-            Request::get(url.clone()),
+            Request::get(url.clone()).priority(RequestPriority::High),
             ResourceHandler::boxed(
                 self.doc.tx.clone(),
                 self.doc.id(),
@@ -717,6 +794,52 @@ impl<'doc> DocumentMutator<'doc> {
         );
     }
 
+    /// Load the image linked by a `<link rel="icon">` element and use it as the window's icon
+    fn load_linked_icon(&mut self, target_id: usize) {
+        let node = &self.doc.nodes[target_id];
+
+        let rel_attr = node.attr(local_name!("rel"));
+        let href_attr = node.attr(local_name!("href"));
+
+        let (Some(rels), Some(href)) = (rel_attr, href_attr) else {
+            return;
+        };
+        // Also matches the legacy "shortcut icon"
+        if !rels.split_ascii_whitespace().any(|rel| rel == "icon") || href.is_empty() {
+            return;
+        }
+
+        let url = self.doc.resolve_url(href);
+        let url_string = url.as_str();
+
+        if let Some(cached_image) = self.doc.image_cache.get(url_string) {
+            self.doc.apply_favicon(cached_image);
+            return;
+        }
+
+        if let Some(waiting_list) = self.doc.pending_images.get_mut(url_string) {
+            waiting_list.push((target_id, ImageType::Favicon));
+            return;
+        }
+
+        self.doc.pending_images.insert(
+            url_string.to_string(),
+            vec![(target_id, ImageType::Favicon)],
+        );
+
+        self.doc.net_provider.fetch(
+            self.doc.id(),
+            Request::get(url).priority(RequestPriority::Low),
+            ResourceHandler::boxed(
+                self.doc.tx.clone(),
+                self.doc.id(),
+                None, // Don't pass node_id, we'll handle it via pending_images
+                self.doc.shell_provider.clone(),
+                ImageHandler::new(ImageType::Favicon),
+            ),
+        );
+    }
+
     fn unload_stylesheet(&mut self, node_id: usize) {
         let node = &mut self.doc.nodes[node_id];
         let Some(element) = node.element_data_mut() else {
@@ -771,7 +894,7 @@ impl<'doc> DocumentMutator<'doc> {
 
                 self.doc.net_provider.fetch(
                     self.doc.id(),
-                    Request::get(src),
+                    Request::get(src).priority(RequestPriority::Low),
                     ResourceHandler::boxed(
                         self.doc.tx.clone(),
                         self.doc.id(),
@@ -897,9 +1020,12 @@ impl Drop for ViewportMut<'_> {
         self.doc
             .set_stylist_device(make_device(&self.doc.viewport, self.doc.font_ctx.clone()));
         self.doc.scroll_viewport_by(0.0, 0.0); // Clamp scroll offset
+        self.doc.apply_system_preferences();
 
-        let scale_has_changed =
-            self.doc.viewport().scale_f64() != self.initial_viewport.scale_f64();
+        let scale_has_changed = self.doc.viewport().scale_f64()
+            != self.initial_viewport.scale_f64()
+            || self.doc.viewport().effective_text_scale()
+                != self.initial_viewport.effective_text_scale();
         if scale_has_changed {
             self.doc.invalidate_inline_contexts();
             self.doc.shell_provider.request_redraw();
@@ -2,7 +2,7 @@ use selectors::context::QuirksMode;
 use std::sync::atomic::Ordering as Ao;
 use std::{
     io::Cursor,
-    sync::{Arc, atomic::AtomicUsize, mpsc::Sender},
+    sync::{Arc, Mutex, atomic::AtomicUsize, mpsc::Sender},
 };
 use style::{
     font_face::{FontFaceSourceFormat, FontFaceSourceFormatKeyword, Source},
@@ -18,7 +18,9 @@ use style::{
     values::{CssUrl, SourceLocation},
 };
 
-use bliss_traits::net::{Bytes, NetHandler, NetProvider, Request};
+use bliss_traits::download::{DownloadHandler, download_response, is_download_response};
+use bliss_traits::navigation::NavigationResponse;
+use bliss_traits::net::{Bytes, HeaderMap, NetHandler, NetProvider, Request, RequestPriority};
 use bliss_traits::shell::ShellProvider;
 
 use url::Url;
@@ -174,7 +176,7 @@ impl ServoStylesheetLoader for StylesheetLoader {
         let import = ServoArc::new(lock.wrap(import));
         self.net_provider.fetch(
             self.doc_id,
-            Request::get(url.as_ref().clone()),
+            Request::get(url.as_ref().clone()).priority(RequestPriority::High),
             ResourceHandler::boxed(
                 self.tx.clone(),
                 self.doc_id,
@@ -411,7 +413,7 @@ pub(crate) fn fetch_font_face(
             if let Some((url, format)) = preferred_source {
                 network_provider.fetch(
                     doc_id,
-                    Request::get(url),
+                    Request::get(url).priority(RequestPriority::High),
                     ResourceHandler::boxed(
                         tx.clone(),
                         doc_id,
@@ -468,3 +470,52 @@ impl ImageHandler {
         Err(String::from("Could not parse image"))
     }
 }
+
+/// A [`NetHandler`] for the response to a navigation. Responses which should be downloaded (see
+/// [`is_download_response`]) are handed to the download handler, and other responses are passed
+/// to the `on_load` callback to be shown as a document.
+pub(crate) struct NavigationHandler {
+    doc_id: usize,
+    download_handler: Arc<dyn DownloadHandler>,
+    headers: Mutex<HeaderMap>,
+    on_load: Mutex<Box<dyn FnOnce(NavigationResponse) + Send>>,
+}
+
+impl NavigationHandler {
+    pub(crate) fn new(
+        doc_id: usize,
+        download_handler: Arc<dyn DownloadHandler>,
+        on_load: Box<dyn FnOnce(NavigationResponse) + Send>,
+    ) -> Self {
+        Self {
+            doc_id,
+            download_handler,
+            headers: Mutex::new(HeaderMap::new()),
+            on_load: Mutex::new(on_load),
+        }
+    }
+}
+
+impl NetHandler for NavigationHandler {
+    fn headers(&self, headers: &HeaderMap) {
+        *self.headers.lock().unwrap() = headers.clone();
+    }
+
+    fn bytes(self: Box<Self>, resolved_url: String, bytes: Bytes) {
+        let Ok(url) = Url::parse(&resolved_url) else {
+            return;
+        };
+        let headers = self.headers.into_inner().unwrap();
+        if is_download_response(&headers) {
+            download_response(&*self.download_handler, url, self.doc_id, &headers, bytes);
+            return;
+        }
+
+        let on_load = self.on_load.into_inner().unwrap();
+        on_load(NavigationResponse {
+            url,
+            headers,
+            body: bytes,
+        });
+    }
+}
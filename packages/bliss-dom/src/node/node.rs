@@ -113,7 +113,8 @@ pub struct Node {
     pub after: Option<usize>,
 
     // Taffy layout data:
-    pub style: Style<Atom>,
+    /// The converted style. Shared with the style conversion cache (if enabled) until it's modified.
+    pub style: Arc<Style<Atom>>,
     pub has_snapshot: bool,
     pub snapshot_handled: AtomicBool,
     /// Whether any descendant of this node needs restyling.
@@ -352,8 +353,7 @@ impl Node {
     }
 
     pub fn focus(&mut self, shell_provider: Arc<dyn ShellProvider>) {
-        self.element_state
-            .insert(ElementState::FOCUS | ElementState::FOCUSRING);
+        self.element_state.insert(ElementState::FOCUS);
         self.set_restyle_hint(RestyleHint::restyle_subtree());
 
         // If focussing a text input, enable IME and set IME area
@@ -362,6 +362,9 @@ impl Node {
             .and_then(|elem| elem.text_input_data())
             .is_some()
         {
+            // Text inputs always show a focus ring (as they accept keyboard input)
+            self.element_state.insert(ElementState::FOCUSRING);
+
             shell_provider.set_ime_enabled(true);
             let mut pos = self.absolute_position(0.0, 0.0);
             pos.x += self.final_layout.content_box_x();
@@ -387,6 +390,12 @@ impl Node {
         }
     }
 
+    /// Show the focus ring (matching `:focus-visible`). Used when focus was moved using the keyboard.
+    pub fn show_focus_ring(&mut self) {
+        self.element_state.insert(ElementState::FOCUSRING);
+        self.set_restyle_hint(RestyleHint::restyle_subtree());
+    }
+
     pub fn is_focussed(&self) -> bool {
         self.element_state.contains(ElementState::FOCUS)
     }
@@ -813,6 +822,17 @@ impl Node {
         }
     }
 
+    /// The styles of the element's `::selection` pseudo-element (if any rules match it)
+    pub fn selection_styles(&self) -> Option<style::servo_arc::Arc<ComputedValues>> {
+        self.stylo_element_data
+            .borrow()
+            .as_ref()?
+            .styles
+            .pseudos
+            .get(&PseudoElement::Selection)
+            .cloned()
+    }
+
     pub fn text_content(&self) -> String {
         let mut out = String::new();
         self.write_text_content(&mut out);
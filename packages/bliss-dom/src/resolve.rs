@@ -47,6 +47,8 @@ impl BaseDocument {
             return;
         }
 
+        self.animation_time = current_time_for_animations;
+
         // Process messages that have been sent to our message channel (e.g. loaded resource)
         self.handle_messages();
 
@@ -90,6 +92,9 @@ impl BaseDocument {
             timer.record_time("c_damage");
         }
 
+        // Move IME candidate windows to follow the caret
+        self.update_ime_cursor_area();
+
         let mut subdoc_is_animating = false;
         for &node_id in &self.sub_document_nodes {
             let node = &mut self.nodes[node_id];
@@ -244,6 +249,7 @@ impl BaseDocument {
                         font_ctx_mut,
                         &mut layout,
                         self.viewport.scale(),
+                        self.viewport.effective_text_scale(),
                         task.node_id,
                     );
 
@@ -131,3 +131,42 @@ impl ScriptEngine for NoopScriptEngine {
 
 /// Boxed script engine for storage in documents
 pub type BoxedScriptEngine = Box<dyn ScriptEngine>;
+
+/// The maximum number of times markup injected by [`BaseDocument::write`] may itself inject markup
+/// before further writes are rejected
+pub(crate) const MAX_DOCUMENT_WRITE_NESTING: u32 = 100;
+
+/// Errors returned by [`BaseDocument::write`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentWriteError {
+    /// Markup injection is disabled by [`DocumentConfig::disable_document_write`](crate::DocumentConfig::disable_document_write)
+    Disabled,
+    /// The document is not being parsed, so there is no insertion point to write to
+    NoInsertionPoint,
+    /// Injected markup has recursively injected markup too many times
+    TooDeeplyNested,
+}
+
+impl std::fmt::Display for DocumentWriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocumentWriteError::Disabled => write!(f, "document.write is disabled"),
+            DocumentWriteError::NoInsertionPoint => write!(f, "document is not being parsed"),
+            DocumentWriteError::TooDeeplyNested => write!(f, "document.write nested too deeply"),
+        }
+    }
+}
+
+impl std::error::Error for DocumentWriteError {}
+
+/// State for `document.write`-style injection of markup at the parser's insertion point
+#[derive(Debug, Default)]
+pub(crate) struct DocumentWriteState {
+    pub(crate) disabled: bool,
+    /// Whether the document is being parsed (and therefore has an insertion point)
+    pub(crate) has_insertion_point: bool,
+    /// How many times injected markup has itself injected markup
+    pub(crate) nesting_level: u32,
+    /// Markup which has been written but not yet consumed by the parser
+    pub(crate) pending: String,
+}
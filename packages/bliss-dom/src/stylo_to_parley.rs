@@ -106,16 +106,17 @@ pub(crate) fn white_space_collapse(input: stylo::WhiteSpaceCollapse) -> parley::
 pub(crate) fn style(
     span_id: usize,
     style: &stylo::ComputedValues,
+    text_scale: f32,
 ) -> parley::TextStyle<'static, TextBrush> {
     let font_styles = style.get_font();
     let itext_styles = style.get_inherited_text();
 
-    // Convert font size and line height
-    let font_size = font_styles.font_size.used_size.0.px();
+    // Convert font size and line height (applying the text scale factor)
+    let font_size = font_styles.font_size.used_size.0.px() * text_scale;
     let line_height = match font_styles.line_height {
         stylo::LineHeight::Normal => parley::LineHeight::FontSizeRelative(1.2),
         stylo::LineHeight::Number(num) => parley::LineHeight::FontSizeRelative(num.0),
-        stylo::LineHeight::Length(value) => parley::LineHeight::Absolute(value.0.px()),
+        stylo::LineHeight::Length(value) => parley::LineHeight::Absolute(value.0.px() * text_scale),
     };
 
     let letter_spacing = itext_styles
@@ -131,7 +132,7 @@ pub(crate) fn style(
     let font_variations = self::font_variations(&font_styles.font_variation_settings);
 
     // Convert font family
-    let families: Vec<_> = font_styles
+    let mut families: Vec<_> = font_styles
         .font_family
         .families
         .list
@@ -160,6 +161,12 @@ pub(crate) fn style(
         })
         .collect();
 
+    // Fall back to the emoji font for emoji clusters which aren't covered by the specified families
+    let emoji_family = parley::FontFamily::Generic(parley::GenericFamily::Emoji);
+    if !families.contains(&emoji_family) {
+        families.push(emoji_family);
+    }
+
     // Wrapping and breaking
     let word_break = match itext_styles.word_break {
         stylo::WordBreak::Normal => parley::WordBreakStrength::Normal,
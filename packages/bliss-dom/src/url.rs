@@ -7,10 +7,34 @@ use url::Url;
 
 #[derive(Clone)]
 pub(crate) struct DocumentUrl {
+    /// The URL the document was loaded from
+    document_url: ServoArc<Url>,
+    /// The URL used to resolve relative URLs. This is the document's URL unless overridden by a `<base>` element.
     base_url: ServoArc<Url>,
 }
 
 impl DocumentUrl {
+    fn new(document_url: ServoArc<Url>) -> Self {
+        Self {
+            base_url: ServoArc::clone(&document_url),
+            document_url,
+        }
+    }
+
+    /// Set the base URL from the `href` of a `<base>` element (resolved against the document's URL).
+    /// Passing `None` (or an invalid URL) resets the base URL to the document's URL.
+    pub(crate) fn set_base_href(&mut self, href: Option<&str>) {
+        self.base_url = match href.and_then(|href| self.document_url.join(href).ok()) {
+            Some(url) => ServoArc::new(url),
+            None => ServoArc::clone(&self.document_url),
+        };
+    }
+
+    /// The URL the document was loaded from (ignoring any `<base>` element)
+    pub(crate) fn document_url(&self) -> &Url {
+        &self.document_url
+    }
+
     /// Create a stylo `UrlExtraData` from the URL
     pub(crate) fn url_extra_data(&self) -> UrlExtraData {
         UrlExtraData(ServoArc::clone(&self.base_url))
@@ -29,20 +53,17 @@ impl Default for DocumentUrl {
 impl FromStr for DocumentUrl {
     type Err = <Url as FromStr>::Err;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let base_url = ServoArc::new(Url::parse(s)?);
-        Ok(Self { base_url })
+        Ok(Self::new(ServoArc::new(Url::parse(s)?)))
     }
 }
 impl From<Url> for DocumentUrl {
-    fn from(base_url: Url) -> Self {
-        Self {
-            base_url: ServoArc::new(base_url),
-        }
+    fn from(document_url: Url) -> Self {
+        Self::new(ServoArc::new(document_url))
     }
 }
 impl From<ServoArc<Url>> for DocumentUrl {
-    fn from(base_url: ServoArc<Url>) -> Self {
-        Self { base_url }
+    fn from(document_url: ServoArc<Url>) -> Self {
+        Self::new(document_url)
     }
 }
 impl Deref for DocumentUrl {
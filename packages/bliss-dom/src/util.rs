@@ -19,6 +19,8 @@ pub(crate) static FONT_DB: LazyLock<Arc<fontdb::Database>> = LazyLock::new(|| {
 pub enum ImageType {
     Image,
     Background(usize),
+    /// The icon of the document (from a `<link rel="icon">` element)
+    Favicon,
 }
 
 /// A point
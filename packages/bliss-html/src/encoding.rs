@@ -0,0 +1,113 @@
+//! Detection and decoding of the character encoding of HTML documents
+//!
+//! Implements a simplified version of the [encoding sniffing algorithm](https://html.spec.whatwg.org/multipage/parsing.html#encoding-sniffing-algorithm).
+
+use std::borrow::Cow;
+
+use encoding_rs::{Encoding, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1252, X_USER_DEFINED};
+
+/// The number of bytes which are scanned for a `<meta charset>` declaration
+const PRESCAN_LENGTH: usize = 1024;
+
+/// Extract the value of the `charset` parameter from a `Content-Type` header (or `<meta content>` attribute)
+fn charset_param(content_type: &str) -> Option<&str> {
+    let lower = content_type.to_ascii_lowercase();
+    let idx = lower.find("charset")?;
+    let rest = content_type[idx + "charset".len()..].trim_start();
+    let rest = rest.strip_prefix('=')?.trim_start();
+    let rest = rest.trim_start_matches(['"', '\'']);
+    let end = rest
+        .find(|c: char| matches!(c, '"' | '\'' | ';' | '>' | '/') || c.is_ascii_whitespace())
+        .unwrap_or(rest.len());
+    Some(&rest[..end]).filter(|label| !label.is_empty())
+}
+
+/// Look for a `<meta charset="...">` or `<meta http-equiv="Content-Type" content="...; charset=...">`
+/// declaration within the first 1024 bytes of the document
+fn prescan(bytes: &[u8]) -> Option<&'static Encoding> {
+    let bytes = &bytes[..bytes.len().min(PRESCAN_LENGTH)];
+    let text = String::from_utf8_lossy(bytes);
+    let lower = text.to_ascii_lowercase();
+
+    let mut offset = 0;
+    while let Some(start) = lower[offset..].find("<meta") {
+        let start = offset + start;
+        let end = lower[start..]
+            .find('>')
+            .map(|end| start + end)
+            .unwrap_or(lower.len());
+        let tag = &text[start..end];
+
+        if let Some(encoding) =
+            charset_param(tag).and_then(|label| Encoding::for_label(label.as_bytes()))
+        {
+            // A meta declaration of UTF-16 is necessarily wrong (the prescan was performed on ASCII-compatible bytes)
+            return Some(match encoding {
+                enc if enc == UTF_16BE || enc == UTF_16LE => UTF_8,
+                enc if enc == X_USER_DEFINED => WINDOWS_1252,
+                enc => enc,
+            });
+        }
+        offset = end;
+    }
+
+    None
+}
+
+/// Detect the character encoding of an HTML document from (in order of precedence) its byte order mark,
+/// the `charset` parameter of its `Content-Type` header, and any `<meta charset>` declaration.
+/// If none of these are present, UTF-8 is assumed if the document is valid UTF-8, and windows-1252 otherwise.
+pub fn detect_encoding(bytes: &[u8], content_type: Option<&str>) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+
+    if let Some(encoding) = content_type
+        .and_then(charset_param)
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+    {
+        return encoding;
+    }
+
+    if let Some(encoding) = prescan(bytes) {
+        return encoding;
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(_) => UTF_8,
+        Err(_) => WINDOWS_1252,
+    }
+}
+
+/// Decode the bytes of an HTML document into a string, detecting its encoding using [`detect_encoding`].
+/// Malformed sequences are replaced with U+FFFD rather than causing an error.
+pub fn decode_html<'a>(bytes: &'a [u8], content_type: Option<&str>) -> Cow<'a, str> {
+    let encoding = detect_encoding(bytes, content_type);
+    let (html, _encoding, _had_errors) = encoding.decode(bytes);
+    html
+}
+
+#[test]
+fn detects_encodings() {
+    assert_eq!(detect_encoding(b"<p>hello</p>", None), UTF_8);
+    assert_eq!(
+        detect_encoding(b"<p>hello</p>", Some("text/html; charset=ISO-8859-1")),
+        WINDOWS_1252
+    );
+    assert_eq!(
+        detect_encoding(b"<meta charset=\"shift_jis\"><p>hello</p>", None),
+        encoding_rs::SHIFT_JIS
+    );
+    assert_eq!(
+        detect_encoding(
+            b"<meta http-equiv=\"Content-Type\" content=\"text/html; charset=koi8-r\">",
+            None
+        ),
+        encoding_rs::KOI8_R
+    );
+    assert_eq!(
+        detect_encoding(b"\xEF\xBB\xBF<p>hi</p>", Some("text/html; charset=latin1")),
+        UTF_8
+    );
+    assert_eq!(decode_html(b"caf\xE9", None), "caf\u{e9}");
+}
@@ -1,8 +1,8 @@
 //! An implementation for Html5ever's sink trait, allowing us to parse HTML into a DOM.
 
 use html5ever::ParseOpts;
-use html5ever::tokenizer::TokenizerOpts;
-use html5ever::tree_builder::TreeBuilderOpts;
+use html5ever::tokenizer::{BufferQueue, Tokenizer, TokenizerOpts, TokenizerResult};
+use html5ever::tree_builder::{TreeBuilder, TreeBuilderOpts};
 use std::borrow::Cow;
 use std::cell::{Cell, Ref, RefCell, RefMut};
 
@@ -48,6 +48,23 @@ pub struct DocumentHtmlParser<'m, 'doc> {
     pub is_xml: bool,
 }
 
+type HtmlTokenizer<'m, 'doc> = Tokenizer<TreeBuilder<usize, DocumentHtmlParser<'m, 'doc>>>;
+
+/// Feed `input` to the tokenizer. Whenever the parser pauses, any markup written to the document
+/// (see `BaseDocument::write`) is parsed before the rest of the input.
+fn feed_with_written_markup(tokenizer: &HtmlTokenizer<'_, '_>, input: &BufferQueue) {
+    while let TokenizerResult::Script(_script_id) = tokenizer.feed(input) {
+        // TODO: execute classic scripts here, once supported
+        let written = tokenizer.sink.sink.mutr().doc.take_written_markup();
+        if let Some(markup) = written {
+            let written_input = BufferQueue::default();
+            written_input.push_back(StrTendril::from(markup));
+            feed_with_written_markup(tokenizer, &written_input);
+            tokenizer.sink.sink.mutr().doc.written_markup_parsed();
+        }
+    }
+}
+
 impl<'m, 'doc> DocumentHtmlParser<'m, 'doc> {
     #[track_caller]
     /// Get a mutable borrow of the DocumentMutator
@@ -67,6 +84,7 @@ impl<'m, 'doc> DocumentHtmlParser<'m, 'doc> {
     }
 
     pub fn parse_into_mutator<'a, 'd>(mutr: &'a mut DocumentMutator<'d>, html: &str) {
+        let scripting_enabled = mutr.doc.scripting_enabled();
         let mut sink = DocumentHtmlParser::new(mutr);
 
         let is_xhtml_doc = html.starts_with("<?xml")
@@ -85,20 +103,28 @@ impl<'m, 'doc> DocumentHtmlParser<'m, 'doc> {
         } else {
             // Parse as HTML
             sink.is_xml = false;
-            let opts = ParseOpts {
-                tokenizer: TokenizerOpts::default(),
-                tree_builder: TreeBuilderOpts {
-                    exact_errors: false,
-                    scripting_enabled: false, // Enables parsing of <noscript> tags
-                    iframe_srcdoc: false,
-                    drop_doctype: true,
-                    quirks_mode: QuirksMode::NoQuirks,
-                },
+            let tree_builder_opts = TreeBuilderOpts {
+                exact_errors: false,
+                // With scripting disabled, the contents of <noscript> tags are parsed as markup
+                scripting_enabled,
+                iframe_srcdoc: false,
+                drop_doctype: true,
+                quirks_mode: QuirksMode::NoQuirks,
             };
-            html5ever::parse_document(sink, opts)
-                .from_utf8()
-                .read_from(&mut html.as_bytes())
-                .unwrap();
+
+            // Drive the tokenizer directly (rather than using `html5ever::parse_document`) so that markup
+            // written using `BaseDocument::write` can be inserted at the insertion point
+            sink.mutr().doc.set_parser_active(true);
+            let tokenizer = Tokenizer::new(
+                TreeBuilder::new(sink, tree_builder_opts),
+                TokenizerOpts::default(),
+            );
+            let input = BufferQueue::default();
+            input.push_back(StrTendril::from(html));
+            feed_with_written_markup(&tokenizer, &input);
+            tokenizer.end();
+            tokenizer.sink.sink.mutr().doc.set_parser_active(false);
+            tokenizer.sink.sink.finish();
         }
     }
 
@@ -107,29 +133,58 @@ impl<'m, 'doc> DocumentHtmlParser<'m, 'doc> {
         element_id: usize,
         html: &str,
     ) {
+        let child_ids = Self::parse_fragment_for_context(mutr, element_id, html);
+        mutr.append_children(element_id, &child_ids);
+    }
+
+    /// Parse `html` in the context of an element named `context` (e.g. `tr` or `select`), returning
+    /// the ids of the parsed top-level nodes. The nodes are detached and may be inserted anywhere in the document.
+    pub fn parse_fragment_into_mutator<'a, 'd>(
+        mutr: &'a mut DocumentMutator<'d>,
+        html: &str,
+        context: QualName,
+    ) -> Vec<usize> {
+        let context_id = mutr.create_element(context, Vec::new());
+        let child_ids = Self::parse_fragment_for_context(mutr, context_id, html);
+        mutr.remove_and_drop_node(context_id);
+        child_ids
+    }
+
+    /// Parse `html` using the element `context_id` as the context element and return the (detached)
+    /// top-level nodes
+    fn parse_fragment_for_context<'a, 'd>(
+        mutr: &'a mut DocumentMutator<'d>,
+        context_id: usize,
+        html: &str,
+    ) -> Vec<usize> {
+        let scripting_enabled = mutr.doc.scripting_enabled();
         let sink = DocumentHtmlParser::new(mutr);
 
         let opts = ParseOpts {
             tokenizer: TokenizerOpts::default(),
             tree_builder: TreeBuilderOpts {
                 exact_errors: false,
-                scripting_enabled: false, // Enables parsing of <noscript> tags
+                // With scripting disabled, the contents of <noscript> tags are parsed as markup
+                scripting_enabled,
                 iframe_srcdoc: false,
                 drop_doctype: true,
                 quirks_mode: QuirksMode::NoQuirks,
             },
         };
-        html5ever::driver::parse_fragment_for_element(sink, opts, element_id, false, None)
+        html5ever::driver::parse_fragment_for_element(sink, opts, context_id, false, None)
             .from_utf8()
             .read_from(&mut html.as_bytes())
             .unwrap();
 
         // html5ever creates a new fragment root node under the document node and parses the nodes into that fragment root.
-        // So here we move the children of the fragment root to element_id and then remove the fragment root
+        // So here we detach the children of the fragment root and then remove the fragment root
         let fragment_root_id = mutr.last_child_id(0).unwrap();
         let child_ids = mutr.child_ids(fragment_root_id);
-        mutr.append_children(element_id, &child_ids);
-        mutr.remove_node(fragment_root_id);
+        for &child_id in &child_ids {
+            mutr.remove_node(child_id);
+        }
+        mutr.remove_and_drop_node(fragment_root_id);
+        child_ids
     }
 }
 
@@ -249,8 +304,7 @@ impl<'m, 'doc> TreeSink for DocumentHtmlParser<'m, 'doc> {
     }
 
     fn get_template_contents(&self, target: &Self::Handle) -> Self::Handle {
-        // TODO: implement templates properly. This should allow to function like regular elements.
-        *target
+        self.mutr().template_contents(*target)
     }
 
     fn same_node(&self, x: &Self::Handle, y: &Self::Handle) -> bool {
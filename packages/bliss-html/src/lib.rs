@@ -1,8 +1,29 @@
 #![allow(clippy::collapsible_if)]
 
+mod encoding;
 mod html_document;
 mod html_sink;
+mod sanitize;
 
+pub use encoding::{decode_html, detect_encoding};
 pub use html_document::HtmlDocument;
 pub use html_sink::DocumentHtmlParser;
 pub use html_sink::HtmlProvider;
+pub use sanitize::{SanitizePolicy, sanitize};
+
+use bliss_dom::DocumentMutator;
+use html5ever::{LocalName, QualName, ns};
+
+/// Parse `html` as a fragment in the context of a `context_tag` element (e.g. `"tr"`, `"option"` or `"body"`).
+///
+/// The parsed top-level nodes are returned detached from the tree, ready to be attached using
+/// [`DocumentMutator::append_children`] or similar. Parsing in the correct context ensures that, for example,
+/// `<td>` elements parsed for insertion into a `<tr>` are not dropped or foster-parented.
+pub fn parse_fragment(mutr: &mut DocumentMutator<'_>, html: &str, context_tag: &str) -> Vec<usize> {
+    let context = QualName::new(
+        None,
+        ns!(html),
+        LocalName::from(context_tag.to_ascii_lowercase()),
+    );
+    DocumentHtmlParser::parse_fragment_into_mutator(mutr, html, context)
+}
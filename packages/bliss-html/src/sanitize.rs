@@ -0,0 +1,363 @@
+//! An allow-list based HTML sanitizer, for rendering untrusted content (chat messages, user bios, etc)
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::{
+    BufferQueue, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+    states::RawKind,
+};
+
+/// Elements which never have an end tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Attributes whose value is a URL, and which are therefore checked against the allowed URL schemes
+const URL_ATTRIBUTES: &[&str] = &[
+    "href",
+    "src",
+    "cite",
+    "action",
+    "formaction",
+    "poster",
+    "background",
+];
+
+/// Configures which tags, attributes and URL schemes [`sanitize`] allows
+#[derive(Debug, Clone)]
+pub struct SanitizePolicy {
+    /// Tags which are kept. The tags of other elements are removed, but their content is kept.
+    pub allowed_tags: HashSet<String>,
+    /// Attributes which are kept, keyed by tag name. Attributes keyed by `"*"` are allowed on all tags.
+    pub allowed_attributes: HashMap<String, HashSet<String>>,
+    /// URL schemes allowed in URL attributes (`href`, `src`, etc). Relative URLs are always allowed.
+    pub allowed_url_schemes: HashSet<String>,
+    /// Tags which are removed along with all of their content
+    pub removed_content_tags: HashSet<String>,
+    /// Whether comments are kept
+    pub allow_comments: bool,
+}
+
+impl Default for SanitizePolicy {
+    /// A policy allowing basic text formatting, links and images
+    fn default() -> Self {
+        Self::empty()
+            .allow_tags(&[
+                "a",
+                "abbr",
+                "b",
+                "blockquote",
+                "br",
+                "code",
+                "del",
+                "em",
+                "h1",
+                "h2",
+                "h3",
+                "h4",
+                "h5",
+                "h6",
+                "hr",
+                "i",
+                "img",
+                "ins",
+                "kbd",
+                "li",
+                "mark",
+                "ol",
+                "p",
+                "pre",
+                "q",
+                "s",
+                "small",
+                "span",
+                "strong",
+                "sub",
+                "sup",
+                "table",
+                "tbody",
+                "td",
+                "tfoot",
+                "th",
+                "thead",
+                "tr",
+                "u",
+                "ul",
+            ])
+            .allow_attributes("*", &["title", "lang", "dir"])
+            .allow_attributes("a", &["href"])
+            .allow_attributes("img", &["src", "alt", "width", "height"])
+            .allow_attributes("td", &["colspan", "rowspan"])
+            .allow_attributes("th", &["colspan", "rowspan"])
+            .allow_url_schemes(&["http", "https", "mailto"])
+    }
+}
+
+impl SanitizePolicy {
+    /// A policy which removes all tags (keeping only text)
+    pub fn empty() -> Self {
+        Self {
+            allowed_tags: HashSet::new(),
+            allowed_attributes: HashMap::new(),
+            allowed_url_schemes: HashSet::new(),
+            removed_content_tags: [
+                "script", "style", "template", "iframe", "object", "embed", "noscript", "noembed",
+                "noframes", "xmp", "title", "textarea", "select", "svg", "math",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            allow_comments: false,
+        }
+    }
+
+    pub fn allow_tags(mut self, tags: &[&str]) -> Self {
+        self.allowed_tags
+            .extend(tags.iter().map(|tag| tag.to_ascii_lowercase()));
+        self
+    }
+
+    /// Allow attributes on the specified tag (or on all tags if `tag` is `"*"`)
+    pub fn allow_attributes(mut self, tag: &str, attributes: &[&str]) -> Self {
+        self.allowed_attributes
+            .entry(tag.to_ascii_lowercase())
+            .or_default()
+            .extend(attributes.iter().map(|attr| attr.to_ascii_lowercase()));
+        self
+    }
+
+    pub fn allow_url_schemes(mut self, schemes: &[&str]) -> Self {
+        self.allowed_url_schemes
+            .extend(schemes.iter().map(|scheme| scheme.to_ascii_lowercase()));
+        self
+    }
+
+    pub fn allow_comments(mut self, allow: bool) -> Self {
+        self.allow_comments = allow;
+        self
+    }
+
+    fn is_tag_allowed(&self, tag: &str) -> bool {
+        self.allowed_tags.contains(tag) && !self.removed_content_tags.contains(tag)
+    }
+
+    fn is_attribute_allowed(&self, tag: &str, attr: &str) -> bool {
+        // Event handler attributes are never allowed
+        if attr.starts_with("on") {
+            return false;
+        }
+        let allowed_for = |key: &str| {
+            self.allowed_attributes
+                .get(key)
+                .is_some_and(|attrs| attrs.contains(attr))
+        };
+        allowed_for(tag) || allowed_for("*")
+    }
+
+    fn is_url_allowed(&self, url: &str) -> bool {
+        // Browsers ignore whitespace and control characters within URL schemes (e.g. "java\tscript:")
+        let url: String = url
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+            .collect();
+        match url.find([':', '/', '?', '#']) {
+            Some(idx) if url[idx..].starts_with(':') => self
+                .allowed_url_schemes
+                .contains(&url[..idx].to_ascii_lowercase()),
+            // No scheme: a relative URL
+            _ => true,
+        }
+    }
+}
+
+fn escape_text(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\u{a0}' => out.push_str("&nbsp;"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn escape_attribute(out: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\u{a0}' => out.push_str("&nbsp;"),
+            c => out.push(c),
+        }
+    }
+}
+
+struct SanitizerState {
+    out: String,
+    /// The allowed elements which are currently open
+    open_elements: Vec<String>,
+    /// The element whose content is currently being removed (if any)
+    removing: Option<String>,
+}
+
+struct SanitizerSink<'p> {
+    policy: &'p SanitizePolicy,
+    state: RefCell<SanitizerState>,
+}
+
+impl TokenSink for SanitizerSink<'_> {
+    type Handle = ();
+
+    fn process_token(&self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        let policy = self.policy;
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+
+        // Skip everything until the end tag of the element being removed
+        if let Some(removing) = &state.removing {
+            if let Token::TagToken(tag) = &token {
+                if tag.kind == TagKind::EndTag && *tag.name == **removing {
+                    state.removing = None;
+                }
+            }
+            return TokenSinkResult::Continue;
+        }
+
+        match token {
+            Token::TagToken(tag) => {
+                let name = tag.name.to_string();
+                match tag.kind {
+                    TagKind::StartTag => {
+                        if policy.removed_content_tags.contains(&name) {
+                            if !tag.self_closing && !VOID_ELEMENTS.contains(&name.as_str()) {
+                                state.removing = Some(name.clone());
+                            }
+                            // Ensure that the tokenizer doesn't parse the content of raw text elements as markup
+                            return match name.as_str() {
+                                "script" => TokenSinkResult::RawData(RawKind::ScriptData),
+                                "style" | "xmp" | "iframe" | "noembed" | "noframes" => {
+                                    TokenSinkResult::RawData(RawKind::Rawtext)
+                                }
+                                "title" | "textarea" => TokenSinkResult::RawData(RawKind::Rcdata),
+                                _ => TokenSinkResult::Continue,
+                            };
+                        }
+
+                        if !policy.is_tag_allowed(&name) {
+                            return TokenSinkResult::Continue;
+                        }
+
+                        state.out.push('<');
+                        state.out.push_str(&name);
+                        for attr in tag.attrs.iter() {
+                            let attr_name = attr.name.local.to_string();
+                            if !attr.name.ns.is_empty()
+                                || !policy.is_attribute_allowed(&name, &attr_name)
+                            {
+                                continue;
+                            }
+                            if URL_ATTRIBUTES.contains(&attr_name.as_str())
+                                && !policy.is_url_allowed(&attr.value)
+                            {
+                                continue;
+                            }
+                            state.out.push(' ');
+                            state.out.push_str(&attr_name);
+                            state.out.push_str("=\"");
+                            escape_attribute(&mut state.out, &attr.value);
+                            state.out.push('"');
+                        }
+                        state.out.push('>');
+
+                        if !VOID_ELEMENTS.contains(&name.as_str()) {
+                            state.open_elements.push(name);
+                        }
+                    }
+                    TagKind::EndTag => {
+                        // Only close elements which are open, closing any unclosed elements within them
+                        if let Some(idx) = state.open_elements.iter().rposition(|el| *el == name) {
+                            for el in state.open_elements.drain(idx..).rev() {
+                                state.out.push_str("</");
+                                state.out.push_str(&el);
+                                state.out.push('>');
+                            }
+                        }
+                    }
+                }
+            }
+            Token::CharacterTokens(text) => escape_text(&mut state.out, &text),
+            Token::CommentToken(text) => {
+                if policy.allow_comments {
+                    state.out.push_str("<!--");
+                    // Prevent the comment from being terminated early
+                    state.out.push_str(&text.replace("--", "- -"));
+                    state.out.push_str("-->");
+                }
+            }
+            Token::DoctypeToken(_)
+            | Token::NullCharacterToken
+            | Token::EOFToken
+            | Token::ParseError(_) => {}
+        }
+
+        TokenSinkResult::Continue
+    }
+}
+
+/// Sanitize untrusted `html` according to `policy`, producing HTML which is safe to render.
+///
+/// Disallowed tags are removed (keeping their content, except for tags in
+/// [`removed_content_tags`](SanitizePolicy::removed_content_tags)), disallowed attributes are removed,
+/// URL attributes with disallowed schemes are removed, and unclosed elements are closed.
+pub fn sanitize(html: &str, policy: &SanitizePolicy) -> String {
+    let sink = SanitizerSink {
+        policy,
+        state: RefCell::new(SanitizerState {
+            out: String::with_capacity(html.len()),
+            open_elements: Vec::new(),
+            removing: None,
+        }),
+    };
+
+    let tokenizer = Tokenizer::new(sink, TokenizerOpts::default());
+    let input = BufferQueue::default();
+    input.push_back(StrTendril::from(html));
+    let _ = tokenizer.feed(&input);
+    tokenizer.end();
+
+    let mut state = tokenizer.sink.state.into_inner();
+    while let Some(el) = state.open_elements.pop() {
+        state.out.push_str("</");
+        state.out.push_str(&el);
+        state.out.push('>');
+    }
+    state.out
+}
+
+#[test]
+fn sanitizes_untrusted_html() {
+    let policy = SanitizePolicy::default();
+
+    assert_eq!(
+        sanitize(
+            r#"<p onclick="steal()">Hi <b>there</b><script>alert(1)</script></p>"#,
+            &policy
+        ),
+        "<p>Hi <b>there</b></p>"
+    );
+    assert_eq!(
+        sanitize(r#"<a href="java&#9;script:alert(1)">x</a>"#, &policy),
+        "<a>x</a>"
+    );
+    assert_eq!(
+        sanitize(r#"<a href="/profile?id=1&x=2">me</a>"#, &policy),
+        r#"<a href="/profile?id=1&amp;x=2">me</a>"#
+    );
+    assert_eq!(sanitize("<div><em>unclosed", &policy), "<em>unclosed</em>");
+}
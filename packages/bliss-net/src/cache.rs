@@ -0,0 +1,64 @@
+//! Persistent on-disk HTTP cache
+//!
+//! Responses are stored using [`cacache`](https://docs.rs/cacache) (content-addressed bodies plus a metadata index),
+//! and are reused or revalidated according to standard HTTP caching semantics.
+
+use http::Extensions;
+use http_cache_reqwest::CacheMode;
+use reqwest_middleware::{Middleware, Next};
+use std::path::PathBuf;
+
+use crate::ProviderConfig;
+
+pub(crate) fn get_cache_path(config: &ProviderConfig) -> PathBuf {
+    if let Some(path) = &config.cache_dir {
+        return path.clone();
+    }
+
+    use directories::ProjectDirs;
+    let path = ProjectDirs::from("com", "DioxusLabs", "Bliss")
+        .expect("Failed to find cache directory")
+        .cache_dir()
+        .to_owned();
+    println!("Using cache dir {}", path.display());
+    path
+}
+
+/// Middleware which serves (possibly stale) cached responses to `GET` requests which fail
+/// due to a network error, so that previously visited pages still load while offline
+pub(crate) struct OfflineFallback;
+
+fn is_network_error(err: &reqwest_middleware::Error) -> bool {
+    match err {
+        reqwest_middleware::Error::Reqwest(err) => err.is_connect() || err.is_timeout(),
+        reqwest_middleware::Error::Middleware(_) => false,
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for OfflineFallback {
+    async fn handle(
+        &self,
+        req: reqwest::Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        let retry = match req.method() == reqwest::Method::GET {
+            true => req.try_clone(),
+            false => None,
+        };
+
+        let result = next.clone().run(req, extensions).await;
+        match (result, retry) {
+            (Err(err), Some(retry)) if is_network_error(&err) => {
+                extensions.insert(CacheMode::OnlyIfCached);
+                match next.run(retry, extensions).await {
+                    // A 504 response indicates that the resource was not cached
+                    Ok(res) if res.status() != reqwest::StatusCode::GATEWAY_TIMEOUT => Ok(res),
+                    _ => Err(err),
+                }
+            }
+            (result, _) => result,
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Configuration for the [`Provider`](crate::Provider)
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Options used when constructing a [`Provider`](crate::Provider)
+#[derive(Debug, Clone)]
+pub struct ProviderConfig {
+    /// The maximum number of idle keep-alive connections kept open per origin
+    pub pool_max_idle_per_origin: usize,
+    /// How long an idle connection is kept open before being closed. `None` keeps idle connections open indefinitely.
+    pub pool_idle_timeout: Option<Duration>,
+    /// Use HTTP/2 for all requests without negotiating it via ALPN. Only useful for servers which are known
+    /// to support HTTP/2. Has no effect unless the `http2` feature is enabled.
+    pub http2_prior_knowledge: bool,
+    /// The directory in which the HTTP cache is stored. Defaults to the platform's cache directory.
+    /// Has no effect unless the `cache` feature is enabled.
+    pub cache_dir: Option<PathBuf>,
+    /// Serve cached responses (even if stale) when a request fails due to a network error.
+    /// Has no effect unless the `cache` feature is enabled.
+    pub offline_fallback: bool,
+    /// The file in which cookies are persisted. `None` keeps cookies in memory only.
+    /// Has no effect unless the `cookies` feature is enabled.
+    pub cookie_file: Option<PathBuf>,
+    /// Make requests to sites other than that of the top-level document without cookies. Documents register
+    /// their URL with the provider when they are created. Has no effect unless the `cookies` feature is enabled.
+    pub block_third_party_cookies: bool,
+    /// The maximum number of requests made to an origin at once. Further requests are queued and started
+    /// in order of their [`priority`](bliss_traits::net::Request::priority).
+    pub max_concurrent_requests_per_origin: usize,
+    /// TLS options (additional root certificates, client certificates)
+    pub tls: TlsConfig,
+}
+
+/// TLS options for a [`Provider`](crate::Provider)
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// Additional PEM encoded root certificates (or bundles of certificates) to trust,
+    /// in addition to the built-in roots
+    pub root_certificates_pem: Vec<Vec<u8>>,
+    /// A PEM encoded client certificate and private key, presented to servers which request
+    /// client authentication (mutual TLS)
+    pub client_identity_pem: Option<Vec<u8>>,
+    /// Hosts for which invalid certificates (self-signed, expired, or for another host) are accepted.
+    ///
+    /// **This is dangerous**, as it allows connections to these hosts to be intercepted.
+    /// It is intended for internal and development deployments only.
+    pub accept_invalid_certs_for_hosts: Vec<String>,
+}
+
+impl TlsConfig {
+    /// Trust the PEM encoded root certificate(s) in `pem`
+    pub fn add_root_certificate_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates_pem.push(pem.into());
+        self
+    }
+
+    /// Present the PEM encoded client certificate and private key in `pem` to servers
+    pub fn set_client_identity_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.client_identity_pem = Some(pem.into());
+        self
+    }
+
+    /// Accept invalid certificates for `host`. **This is dangerous**: see
+    /// [`accept_invalid_certs_for_hosts`](Self::accept_invalid_certs_for_hosts).
+    pub fn danger_accept_invalid_certs_for_host(mut self, host: impl Into<String>) -> Self {
+        self.accept_invalid_certs_for_hosts.push(host.into());
+        self
+    }
+}
+
+impl Default for ProviderConfig {
+    fn default() -> Self {
+        Self {
+            pool_max_idle_per_origin: 32,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            http2_prior_knowledge: false,
+            cache_dir: None,
+            offline_fallback: true,
+            cookie_file: None,
+            block_third_party_cookies: false,
+            max_concurrent_requests_per_origin: 6,
+            tls: TlsConfig::default(),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Cookie storage, with optional persistence to disk and third-party cookie blocking
+
+use cookie_store::{CookieStore, RawCookie};
+use reqwest::Url;
+use reqwest::header::HeaderValue;
+use std::convert::Infallible;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// A cookie store shared by all requests made by a [`Provider`](crate::Provider)
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    store: RwLock<CookieStore>,
+    /// The file cookies are persisted to (if any)
+    path: Option<PathBuf>,
+}
+
+impl CookieJar {
+    /// Create an empty, in-memory cookie jar
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a cookie jar which is persisted to the file at `path`.
+    /// Cookies are loaded from the file (if it exists), and are saved to it when the jar is dropped.
+    pub fn persistent(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let store = match File::open(&path) {
+            Ok(file) => {
+                cookie_store::serde::json::load(BufReader::new(file)).unwrap_or_else(|err| {
+                    eprintln!("Failed to load cookies from {}: {err}", path.display());
+                    CookieStore::default()
+                })
+            }
+            Err(_) => CookieStore::default(),
+        };
+        Self {
+            store: RwLock::new(store),
+            path: Some(path),
+        }
+    }
+
+    /// Save persistent (non-session, unexpired) cookies to disk. Does nothing if the jar is not persistent.
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        let store = self.store.read().unwrap();
+        cookie_store::serde::json::save(&store, &mut writer).map_err(std::io::Error::other)
+    }
+
+    /// Remove all cookies
+    pub fn clear(&self) {
+        self.store.write().unwrap().clear();
+    }
+
+    /// Remove all cookies which would be sent to the origin of `url`
+    pub fn clear_origin(&self, url: &Url) {
+        let mut store = self.store.write().unwrap();
+        let retained: Vec<_> = store
+            .iter_unexpired()
+            .filter(|cookie| !cookie.domain.matches(url))
+            .cloned()
+            .collect();
+        *store = CookieStore::from_cookies(retained.into_iter().map(Ok::<_, Infallible>), false)
+            .unwrap();
+    }
+}
+
+impl Drop for CookieJar {
+    fn drop(&mut self) {
+        if let Err(err) = self.save() {
+            eprintln!("Failed to save cookies: {err}");
+        }
+    }
+}
+
+impl reqwest::cookie::CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let cookies = cookie_headers
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| RawCookie::parse(value.to_owned()).ok());
+        self.store
+            .write()
+            .unwrap()
+            .store_response_cookies(cookies, url);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let store = self.store.read().unwrap();
+        let value = store
+            .get_request_values(url)
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if value.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&value).ok()
+    }
+}
+
+/// The site of a URL: its registrable domain according to the Public Suffix List (or the whole host for
+/// IP addresses and hosts which are themselves public suffixes)
+fn site(url: &Url) -> Option<String> {
+    let Some(domain) = url.domain() else {
+        return url.host_str().map(str::to_string);
+    };
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+    Some(match psl::domain_str(&domain) {
+        Some(registrable) => registrable.to_string(),
+        None => domain,
+    })
+}
+
+/// Whether a request to `url` made by a document at `top_level_url` is same-site (and may therefore use cookies
+/// when third-party cookies are blocked)
+pub(crate) fn is_same_site(top_level_url: &Url, url: &Url) -> bool {
+    site(top_level_url) == site(url)
+}
+
+#[test]
+fn determines_same_site_requests() {
+    let top_level = Url::parse("https://www.example.com/page").unwrap();
+    assert!(is_same_site(
+        &top_level,
+        &Url::parse("https://static.example.com/app.css").unwrap()
+    ));
+    assert!(!is_same_site(
+        &top_level,
+        &Url::parse("https://tracker.example.net/pixel.gif").unwrap()
+    ));
+}
+
+#[test]
+fn uses_public_suffixes_to_determine_sites() {
+    let url = |url: &str| Url::parse(url).unwrap();
+    assert!(is_same_site(
+        &url("https://www.example.co.uk/"),
+        &url("https://static.example.co.uk/app.css")
+    ));
+    assert!(!is_same_site(
+        &url("https://a.co.uk/"),
+        &url("https://b.co.uk/pixel.gif")
+    ));
+    assert!(!is_same_site(
+        &url("https://x.github.io/"),
+        &url("https://y.github.io/app.js")
+    ));
+    assert!(is_same_site(
+        &url("https://x.github.io/"),
+        &url("https://x.github.io/app.js")
+    ));
+}
@@ -1,9 +1,27 @@
 //! Networking (HTTP, filesystem, Data URIs) for Bliss
 //!
 //! Provides an implementation of the [`bliss_traits::net::NetProvider`] trait.
+//!
+//! Connections are kept alive and pooled per origin. With the `http2` feature enabled, HTTP/2 is negotiated
+//! with servers which support it, allowing requests to the same origin to be multiplexed over a single connection.
+
+#[cfg(feature = "cache")]
+mod cache;
+mod config;
+#[cfg(feature = "cookies")]
+mod cookies;
+mod scheduler;
+mod throttle;
+
+pub use config::{ProviderConfig, TlsConfig};
+#[cfg(feature = "cookies")]
+pub use cookies::CookieJar;
+pub use throttle::{NetworkConditions, ThrottledNetProvider};
 
 // use bliss_traits::net::{Body, Bytes, NetHandler, NetProvider, NetWaker, Request};
-use bliss_traits::net::{AbortSignal, Body, Bytes, NetHandler, NetProvider, NetWaker, Request};
+use bliss_traits::net::{
+    AbortSignal, Body, Bytes, HeaderMap, NetHandler, NetProvider, NetWaker, Request,
+};
 use data_url::DataUrl;
 use std::{marker::PhantomData, pin::Pin, sync::Arc, task::Poll};
 use tokio::runtime::Handle;
@@ -23,44 +41,144 @@ type RequestBuilder = reqwest_middleware::RequestBuilder;
 #[cfg(not(feature = "cache"))]
 type RequestBuilder = reqwest::RequestBuilder;
 
-#[cfg(feature = "cache")]
-fn get_cache_path() -> std::path::PathBuf {
-    use directories::ProjectDirs;
-    let path = ProjectDirs::from("com", "DioxusLabs", "Bliss")
-        .expect("Failed to find cache directory")
-        .cache_dir()
-        .to_owned();
-    println!("Using cache dir {}", path.display());
-    path
-}
-
 pub struct Provider {
     rt: Handle,
     client: Client,
+    /// Client without cookies, used for third-party requests when third-party cookies are blocked
+    #[cfg(feature = "cookies")]
+    third_party_client: Option<Client>,
+    /// Client without cookies which accepts invalid certificates, used for third-party requests to `insecure_hosts`
+    /// when third-party cookies are blocked
+    #[cfg(feature = "cookies")]
+    insecure_third_party_client: Option<Client>,
+    #[cfg(feature = "cookies")]
+    cookie_jar: Arc<CookieJar>,
+    /// The URL of the top-level document for each document id, used to determine whether requests are third-party
+    #[cfg(feature = "cookies")]
+    top_level_urls: std::sync::Mutex<std::collections::HashMap<usize, reqwest::Url>>,
+    /// Client which accepts invalid certificates, used for requests to `insecure_hosts`
+    insecure_client: Option<Client>,
+    insecure_hosts: Vec<String>,
+    scheduler: Arc<scheduler::Scheduler>,
     waker: Arc<dyn NetWaker>,
 }
 impl Provider {
     pub fn new(waker: Option<Arc<dyn NetWaker>>) -> Self {
-        let builder = reqwest::Client::builder();
+        Self::with_config(waker, ProviderConfig::default())
+    }
+    /// Create a provider using the options in `config`.
+    ///
+    /// Panics if the TLS configuration contains invalid certificates (see [`try_with_config`](Self::try_with_config)).
+    pub fn with_config(waker: Option<Arc<dyn NetWaker>>, config: ProviderConfig) -> Self {
+        Self::try_with_config(waker, config).expect("Failed to create HTTP client")
+    }
+    /// Create a provider using the options in `config`, failing if the TLS configuration contains invalid certificates
+    pub fn try_with_config(
+        waker: Option<Arc<dyn NetWaker>>,
+        config: ProviderConfig,
+    ) -> Result<Self, ProviderError> {
         #[cfg(feature = "cookies")]
-        let builder = builder.cookie_store(true);
-        let client = builder.build().unwrap();
+        let cookie_jar = Arc::new(match &config.cookie_file {
+            Some(path) => CookieJar::persistent(path),
+            None => CookieJar::new(),
+        });
 
-        #[cfg(feature = "cache")]
-        let client = reqwest_middleware::ClientBuilder::new(client)
-            .with(Cache(HttpCache {
-                mode: CacheMode::Default,
-                manager: CACacheManager::new(get_cache_path(), true),
-                options: HttpCacheOptions::default(),
-            }))
-            .build();
+        let client = Self::build_client(
+            &config,
+            false,
+            #[cfg(feature = "cookies")]
+            Some(cookie_jar.clone()),
+        )?;
+        let insecure_hosts = config.tls.accept_invalid_certs_for_hosts.clone();
+        #[cfg(feature = "cookies")]
+        let third_party_client = match config.block_third_party_cookies {
+            true => Some(Self::build_client(&config, false, None)?),
+            false => None,
+        };
+        #[cfg(feature = "cookies")]
+        let insecure_third_party_client =
+            match config.block_third_party_cookies && !insecure_hosts.is_empty() {
+                true => Some(Self::build_client(&config, true, None)?),
+                false => None,
+            };
+        let insecure_client = match insecure_hosts.is_empty() {
+            true => None,
+            false => Some(Self::build_client(
+                &config,
+                true,
+                #[cfg(feature = "cookies")]
+                Some(cookie_jar.clone()),
+            )?),
+        };
 
         let waker = waker.unwrap_or(Arc::new(DummyNetWaker));
-        Self {
+        Ok(Self {
             rt: Handle::current(),
             client,
+            #[cfg(feature = "cookies")]
+            third_party_client,
+            #[cfg(feature = "cookies")]
+            insecure_third_party_client,
+            #[cfg(feature = "cookies")]
+            cookie_jar,
+            #[cfg(feature = "cookies")]
+            top_level_urls: Default::default(),
+            insecure_client,
+            insecure_hosts,
+            scheduler: Arc::new(scheduler::Scheduler::new(
+                config.max_concurrent_requests_per_origin,
+            )),
             waker,
+        })
+    }
+
+    fn build_client(
+        config: &ProviderConfig,
+        accept_invalid_certs: bool,
+        #[cfg(feature = "cookies")] cookie_jar: Option<Arc<CookieJar>>,
+    ) -> Result<Client, reqwest::Error> {
+        let mut builder = reqwest::Client::builder()
+            .pool_max_idle_per_host(config.pool_max_idle_per_origin)
+            .pool_idle_timeout(config.pool_idle_timeout)
+            .danger_accept_invalid_certs(accept_invalid_certs);
+        for pem in &config.tls.root_certificates_pem {
+            for certificate in reqwest::Certificate::from_pem_bundle(pem)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        if let Some(pem) = &config.tls.client_identity_pem {
+            builder = builder.identity(reqwest::Identity::from_pem(pem)?);
+        }
+        #[cfg(feature = "http2")]
+        let builder = match config.http2_prior_knowledge {
+            true => builder.http2_prior_knowledge(),
+            false => builder,
         }
+        .http2_adaptive_window(true);
+        #[cfg(feature = "cookies")]
+        let builder = match cookie_jar {
+            Some(cookie_jar) => builder.cookie_provider(cookie_jar),
+            None => builder,
+        };
+        let client = builder.build()?;
+
+        #[cfg(feature = "cache")]
+        let client = {
+            let builder = reqwest_middleware::ClientBuilder::new(client);
+            let builder = match config.offline_fallback {
+                true => builder.with(cache::OfflineFallback),
+                false => builder,
+            };
+            builder
+                .with(Cache(HttpCache {
+                    mode: CacheMode::Default,
+                    manager: CACacheManager::new(cache::get_cache_path(config), true),
+                    options: HttpCacheOptions::default(),
+                }))
+                .build()
+        };
+
+        Ok(client)
     }
     pub fn shared(waker: Option<Arc<dyn NetWaker>>) -> Arc<dyn NetProvider> {
         Arc::new(Self::new(waker))
@@ -71,21 +189,118 @@ impl Provider {
     pub fn count(&self) -> usize {
         Arc::strong_count(&self.waker) - 1
     }
+
+    /// The cookie jar used by this provider. Can be used to clear cookies (for all origins or a specific origin).
+    #[cfg(feature = "cookies")]
+    pub fn cookie_jar(&self) -> &Arc<CookieJar> {
+        &self.cookie_jar
+    }
+
+    /// Set the URL of the top-level document for the document `doc_id`. When third-party cookies are blocked,
+    /// requests made by that document to other sites are made without cookies.
+    ///
+    /// Documents register their URL through [`NetProvider::set_document_url`], so this only needs to be called
+    /// for documents whose top-level document is a different document (e.g. iframes).
+    #[cfg(feature = "cookies")]
+    pub fn set_top_level_url(&self, doc_id: usize, url: reqwest::Url) {
+        self.top_level_urls.lock().unwrap().insert(doc_id, url);
+    }
+
+    /// Whether invalid certificates are accepted for requests to `url`
+    fn is_insecure_host(&self, url: &reqwest::Url) -> bool {
+        let host = url.host_str().unwrap_or_default();
+        self.insecure_hosts
+            .iter()
+            .any(|h| h.eq_ignore_ascii_case(host))
+    }
+
+    /// The client to use for a request to `url`
+    fn client_for_url(&self, url: &reqwest::Url) -> Client {
+        if let Some(insecure_client) = &self.insecure_client {
+            if self.is_insecure_host(url) {
+                return insecure_client.clone();
+            }
+        }
+        self.client.clone()
+    }
+
+    /// The client to use for a request to `url` made by the document `doc_id`
+    #[cfg_attr(not(feature = "cookies"), allow(unused_variables))]
+    fn client_for(&self, doc_id: usize, url: &reqwest::Url) -> Client {
+        #[cfg(feature = "cookies")]
+        if let Some(third_party_client) = &self.third_party_client {
+            let top_level_urls = self.top_level_urls.lock().unwrap();
+            if let Some(top_level_url) = top_level_urls.get(&doc_id) {
+                if !cookies::is_same_site(top_level_url, url) {
+                    // Whether to accept invalid certificates is decided independently of cookies
+                    if let Some(insecure_client) = &self.insecure_third_party_client {
+                        if self.is_insecure_host(url) {
+                            return insecure_client.clone();
+                        }
+                    }
+                    return third_party_client.clone();
+                }
+            }
+        }
+        self.client_for_url(url)
+    }
 }
 impl Provider {
     async fn fetch_inner(
         client: Client,
         request: Request,
     ) -> Result<(String, Bytes), ProviderError> {
+        let (url, _headers, bytes) = Self::fetch_inner_with_headers(client, request).await?;
+        Ok((url, bytes))
+    }
+
+    /// Fetch a resource once the scheduler allows a request to its origin to start
+    async fn fetch_scheduled(
+        scheduler: Arc<scheduler::Scheduler>,
+        client: Client,
+        request: Request,
+    ) -> Result<(String, HeaderMap, Bytes), ProviderError> {
+        let _permit = match request.url.scheme() {
+            "http" | "https" => {
+                let origin = request.url.origin().ascii_serialization();
+                Some(scheduler.acquire(origin, request.priority).await)
+            }
+            _ => None,
+        };
+        Self::fetch_inner_with_headers(client, request).await
+    }
+
+    async fn fetch_inner_with_content_type(
+        client: Client,
+        request: Request,
+    ) -> Result<(String, Option<String>, Bytes), ProviderError> {
+        let (url, headers, bytes) = Self::fetch_inner_with_headers(client, request).await?;
+        let content_type = headers
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        Ok((url, content_type, bytes))
+    }
+
+    async fn fetch_inner_with_headers(
+        client: Client,
+        request: Request,
+    ) -> Result<(String, HeaderMap, Bytes), ProviderError> {
         Ok(match request.url.scheme() {
             "data" => {
                 let data_url = DataUrl::process(request.url.as_str())?;
+                let mut headers = HeaderMap::new();
+                if let Ok(content_type) = data_url.mime_type().to_string().parse() {
+                    headers.insert(reqwest::header::CONTENT_TYPE, content_type);
+                }
                 let decoded = data_url.decode_to_vec()?;
-                (request.url.to_string(), Bytes::from(decoded.0))
+                (request.url.to_string(), headers, Bytes::from(decoded.0))
             }
             "file" => {
                 let file_content = std::fs::read(request.url.path())?;
-                (request.url.to_string(), Bytes::from(file_content))
+                let mut headers = HeaderMap::new();
+                headers.insert(reqwest::header::CONTENT_LENGTH, file_content.len().into());
+                (request.url.to_string(), headers, Bytes::from(file_content))
             }
             _ => {
                 let response = client
@@ -98,7 +313,9 @@ impl Provider {
                     .send()
                     .await?;
 
-                (response.url().to_string(), response.bytes().await?)
+                let url = response.url().to_string();
+                let headers = response.headers().clone();
+                (url, headers, response.bytes().await?)
             }
         })
     }
@@ -112,7 +329,7 @@ impl Provider {
         #[cfg(feature = "debug_log")]
         let url = request.url.to_string();
 
-        let client = self.client.clone();
+        let client = self.client_for_url(&request.url);
         self.rt.spawn(async move {
             let result = Self::fetch_inner(client, request).await;
 
@@ -131,7 +348,7 @@ impl Provider {
         #[cfg(feature = "debug_log")]
         let url = request.url.to_string();
 
-        let client = self.client.clone();
+        let client = self.client_for_url(&request.url);
         let result = Self::fetch_inner(client, request).await;
 
         #[cfg(feature = "debug_log")]
@@ -143,16 +360,35 @@ impl Provider {
 
         result
     }
+
+    /// Same as [`fetch_async`](Self::fetch_async), but also returns the `Content-Type` of the response (if known)
+    pub async fn fetch_async_with_content_type(
+        &self,
+        request: Request,
+    ) -> Result<(String, Option<String>, Bytes), ProviderError> {
+        let client = self.client_for_url(&request.url);
+        Self::fetch_inner_with_content_type(client, request).await
+    }
+
+    /// Same as [`fetch_async`](Self::fetch_async), but also returns the headers of the response
+    pub async fn fetch_async_with_headers(
+        &self,
+        request: Request,
+    ) -> Result<(String, HeaderMap, Bytes), ProviderError> {
+        let client = self.client_for_url(&request.url);
+        Self::fetch_inner_with_headers(client, request).await
+    }
 }
 
 impl NetProvider for Provider {
     fn fetch(&self, doc_id: usize, mut request: Request, handler: Box<dyn NetHandler>) {
-        let client = self.client.clone();
+        let client = self.client_for(doc_id, &request.url);
 
         #[cfg(feature = "debug_log")]
         println!("Fetching {}", &request.url);
 
         let waker = self.waker.clone();
+        let scheduler = self.scheduler.clone();
         self.rt.spawn(async move {
             #[cfg(feature = "debug_log")]
             let url = request.url.to_string();
@@ -161,18 +397,21 @@ impl NetProvider for Provider {
             let result = if let Some(signal) = signal {
                 AbortFetch::new(
                     signal,
-                    Box::pin(async move { Self::fetch_inner(client, request).await }),
+                    Box::pin(
+                        async move { Self::fetch_scheduled(scheduler, client, request).await },
+                    ),
                 )
                 .await
             } else {
-                Self::fetch_inner(client, request).await
+                Self::fetch_scheduled(scheduler, client, request).await
             };
 
             // Call the waker to notify of completed network request
             waker.wake(doc_id);
 
             match result {
-                Ok((response_url, bytes)) => {
+                Ok((response_url, headers, bytes)) => {
+                    handler.headers(&headers);
                     handler.bytes(response_url, bytes);
                     #[cfg(feature = "debug_log")]
                     println!("Success {url}");
@@ -180,12 +419,23 @@ impl NetProvider for Provider {
                 Err(e) => {
                     #[cfg(feature = "debug_log")]
                     eprintln!("Error fetching {url}: {e:?}");
-                    #[cfg(not(feature = "debug_log"))]
-                    let _ = e;
+                    handler.error(format!("{e:?}"));
                 }
             };
         });
     }
+
+    #[cfg_attr(not(feature = "cookies"), allow(unused_variables))]
+    fn set_document_url(&self, doc_id: usize, url: Option<&reqwest::Url>) {
+        #[cfg(feature = "cookies")]
+        {
+            let mut top_level_urls = self.top_level_urls.lock().unwrap();
+            match url {
+                Some(url) => top_level_urls.insert(doc_id, url.clone()),
+                None => top_level_urls.remove(&doc_id),
+            };
+        }
+    }
 }
 
 /// A future that is cancellable using an AbortSignal
@@ -0,0 +1,141 @@
+//! Scheduling of requests: limits the number of concurrent requests per origin, starting queued requests
+//! in priority order (documents and render-blocking resources before images and prefetches)
+
+use bliss_traits::net::RequestPriority;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+pub(crate) struct Scheduler {
+    max_concurrent_per_origin: usize,
+    origins: Mutex<HashMap<String, OriginQueue>>,
+}
+
+#[derive(Default)]
+struct OriginQueue {
+    /// The number of requests currently in flight
+    active: usize,
+    pending: BinaryHeap<PendingRequest>,
+    /// Used to start requests of the same priority in the order they were made
+    next_seq: u64,
+}
+
+struct PendingRequest {
+    priority: RequestPriority,
+    seq: u64,
+    start: oneshot::Sender<()>,
+}
+
+impl PartialEq for PendingRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for PendingRequest {}
+impl PartialOrd for PendingRequest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for PendingRequest {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Higher priority first, then earlier requests first
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// Permission to make a request. The slot is passed on to the next queued request when dropped.
+pub(crate) struct RequestPermit {
+    scheduler: Arc<Scheduler>,
+    origin: String,
+}
+
+impl Scheduler {
+    pub(crate) fn new(max_concurrent_per_origin: usize) -> Self {
+        Self {
+            max_concurrent_per_origin: max_concurrent_per_origin.max(1),
+            origins: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait until a request to `origin` with the specified `priority` may be started
+    pub(crate) async fn acquire(
+        self: &Arc<Self>,
+        origin: String,
+        priority: RequestPriority,
+    ) -> RequestPermit {
+        let receiver = {
+            let mut origins = self.origins.lock().unwrap();
+            let queue = origins.entry(origin.clone()).or_default();
+            if queue.active < self.max_concurrent_per_origin {
+                queue.active += 1;
+                None
+            } else {
+                let (start, receiver) = oneshot::channel();
+                let seq = queue.next_seq;
+                queue.next_seq += 1;
+                queue.pending.push(PendingRequest {
+                    priority,
+                    seq,
+                    start,
+                });
+                Some(receiver)
+            }
+        };
+
+        if let Some(receiver) = receiver {
+            // The sender is only dropped after sending (which transfers the slot to this request)
+            let _ = receiver.await;
+        }
+
+        RequestPermit {
+            scheduler: self.clone(),
+            origin,
+        }
+    }
+
+    fn release(&self, origin: &str) {
+        let mut origins = self.origins.lock().unwrap();
+        let Some(queue) = origins.get_mut(origin) else {
+            return;
+        };
+
+        // Pass the slot on to the highest priority request which is still waiting
+        while let Some(next) = queue.pending.pop() {
+            if next.start.send(()).is_ok() {
+                return;
+            }
+        }
+
+        queue.active -= 1;
+        if queue.active == 0 {
+            origins.remove(origin);
+        }
+    }
+}
+
+impl Drop for RequestPermit {
+    fn drop(&mut self) {
+        self.scheduler.release(&self.origin);
+    }
+}
+
+#[test]
+fn orders_pending_requests() {
+    let pending = |priority, seq| PendingRequest {
+        priority,
+        seq,
+        start: oneshot::channel().0,
+    };
+    let mut heap = BinaryHeap::new();
+    heap.push(pending(RequestPriority::Low, 0));
+    heap.push(pending(RequestPriority::High, 1));
+    heap.push(pending(RequestPriority::High, 2));
+    heap.push(pending(RequestPriority::Lowest, 3));
+
+    let order: Vec<u64> = std::iter::from_fn(|| heap.pop().map(|req| req.seq)).collect();
+    assert_eq!(order, vec![1, 2, 0, 3]);
+}
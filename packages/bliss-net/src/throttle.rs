@@ -0,0 +1,163 @@
+//! Network conditioning: simulated latency, limited throughput and packet loss
+//!
+//! Useful for testing how applications behave on slow or unreliable connections.
+
+use bliss_traits::net::{Bytes, HeaderMap, NetHandler, NetProvider, Request, Url};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::runtime::Handle;
+
+/// Simulated network conditions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NetworkConditions {
+    /// Delay added before each request is started
+    pub latency: Duration,
+    /// Maximum download throughput in bytes per second. `None` leaves throughput unlimited.
+    pub download_throughput: Option<u64>,
+    /// Probability (from `0.0` to `1.0`) that a request fails as if the connection was lost
+    pub packet_loss: f32,
+}
+
+impl NetworkConditions {
+    /// No conditioning
+    pub const NONE: Self = Self {
+        latency: Duration::ZERO,
+        download_throughput: None,
+        packet_loss: 0.0,
+    };
+
+    /// Approximates a slow 3G mobile connection
+    pub const SLOW_3G: Self = Self {
+        latency: Duration::from_millis(2000),
+        download_throughput: Some(50_000),
+        packet_loss: 0.0,
+    };
+
+    /// Approximates a fast 3G mobile connection
+    pub const FAST_3G: Self = Self {
+        latency: Duration::from_millis(560),
+        download_throughput: Some(180_000),
+        packet_loss: 0.0,
+    };
+
+    /// The time taken to download `len` bytes at the configured throughput
+    fn transfer_time(&self, len: usize) -> Duration {
+        match self.download_throughput {
+            Some(throughput) if throughput > 0 => {
+                Duration::from_secs_f64(len as f64 / throughput as f64)
+            }
+            _ => Duration::ZERO,
+        }
+    }
+}
+
+impl Default for NetworkConditions {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// A [`NetProvider`] which wraps another provider, simulating the configured [`NetworkConditions`].
+///
+/// The conditions can be changed at any time (e.g. from devtools) using [`set_conditions`](Self::set_conditions).
+pub struct ThrottledNetProvider<P: NetProvider> {
+    inner: Arc<P>,
+    rt: Handle,
+    conditions: RwLock<NetworkConditions>,
+}
+
+impl<P: NetProvider> ThrottledNetProvider<P> {
+    /// Wrap `inner`. Must be called from within a tokio runtime.
+    pub fn new(inner: Arc<P>, conditions: NetworkConditions) -> Self {
+        Self {
+            inner,
+            rt: Handle::current(),
+            conditions: RwLock::new(conditions),
+        }
+    }
+
+    pub fn conditions(&self) -> NetworkConditions {
+        *self.conditions.read().unwrap()
+    }
+
+    pub fn set_conditions(&self, conditions: NetworkConditions) {
+        *self.conditions.write().unwrap() = conditions;
+    }
+}
+
+impl<P: NetProvider> NetProvider for ThrottledNetProvider<P> {
+    fn fetch(&self, doc_id: usize, request: Request, handler: Box<dyn NetHandler>) {
+        let conditions = self.conditions();
+        if conditions == NetworkConditions::NONE {
+            return self.inner.fetch(doc_id, request, handler);
+        }
+
+        let inner = self.inner.clone();
+        let rt = self.rt.clone();
+        self.rt.spawn(async move {
+            tokio::time::sleep(conditions.latency).await;
+
+            if conditions.packet_loss > 0.0 && fastrand::f32() < conditions.packet_loss {
+                handler.error(String::from("Connection lost (simulated packet loss)"));
+                return;
+            }
+
+            let handler = ThrottledHandler {
+                inner: handler,
+                rt,
+                conditions,
+            };
+            inner.fetch(doc_id, request, Box::new(handler));
+        });
+    }
+
+    fn set_document_url(&self, doc_id: usize, url: Option<&Url>) {
+        self.inner.set_document_url(doc_id, url);
+    }
+}
+
+/// Delays delivery of the response according to the simulated throughput
+struct ThrottledHandler {
+    inner: Box<dyn NetHandler>,
+    rt: Handle,
+    conditions: NetworkConditions,
+}
+
+impl NetHandler for ThrottledHandler {
+    fn headers(&self, headers: &HeaderMap) {
+        self.inner.headers(headers);
+    }
+    fn bytes(self: Box<Self>, resolved_url: String, bytes: Bytes) {
+        let delay = self.conditions.transfer_time(bytes.len());
+        if delay.is_zero() {
+            return self.inner.bytes(resolved_url, bytes);
+        }
+        let inner = self.inner;
+        self.rt.spawn(async move {
+            tokio::time::sleep(delay).await;
+            inner.bytes(resolved_url, bytes);
+        });
+    }
+
+    fn chunk(&self, seq: u64, bytes: Bytes) {
+        self.inner.chunk(seq, bytes)
+    }
+
+    fn end(&self) {
+        self.inner.end()
+    }
+
+    fn error(&self, message: String) {
+        self.inner.error(message)
+    }
+}
+
+#[test]
+fn computes_transfer_time() {
+    let conditions = NetworkConditions {
+        download_throughput: Some(1000),
+        ..NetworkConditions::NONE
+    };
+    assert_eq!(conditions.transfer_time(500), Duration::from_millis(500));
+    assert_eq!(NetworkConditions::NONE.transfer_time(500), Duration::ZERO);
+}
@@ -81,7 +81,12 @@ pub(crate) fn to_peniko_gradient(
             items,
             flags,
             ..
-        } => conic_gradient((angle, position, items, *flags), origin_rect, current_color),
+        } => conic_gradient(
+            (angle, position, items, *flags),
+            origin_rect,
+            scale,
+            current_color,
+        ),
     }
 }
 
@@ -267,9 +272,12 @@ fn radial_gradient(
     (gradient, gradient_transform)
 }
 
+/// A conic gradient is rendered as a sweep gradient, rotated by the `from <angle>` (CSS angles start at the top
+/// rather than the right) and centered on the `at <position>`.
 fn conic_gradient(
     gradient: ConicGradient,
     rect: Rect,
+    scale: f64,
     current_color: &AbsoluteColor,
 ) -> (peniko::Gradient, Option<Affine>) {
     let (angle, position, items, flags) = gradient;
@@ -297,9 +305,11 @@ fn conic_gradient(
         });
     }
 
+    // Resolve the position in CSS pixels (so that absolute lengths are scaled correctly on HiDPI screens)
+    let css_rect = rect.scale_from_origin(1.0 / scale);
+    let center = get_translation(position, css_rect) * scale;
     let gradient_transform = Some(
-        Affine::rotate(angle.radians() as f64 - std::f64::consts::PI / 2.0)
-            .then_translate(get_translation(position, rect)),
+        Affine::rotate(angle.radians() as f64 - std::f64::consts::PI / 2.0).then_translate(center),
     );
 
     (gradient, gradient_transform)
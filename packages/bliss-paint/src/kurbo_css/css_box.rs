@@ -38,6 +38,7 @@ pub struct CssBox {
     pub padding_width: Insets,
     pub border_width: Insets,
     pub outline_width: f64,
+    pub outline_offset: f64,
 
     pub border_radii: NonUniformRoundedRectRadii,
 }
@@ -48,11 +49,12 @@ impl CssBox {
         border: Insets,
         padding: Insets,
         outline_width: f64,
+        outline_offset: f64,
         mut border_radii: NonUniformRoundedRectRadii,
     ) -> Self {
         let padding_box = border_box - border;
         let content_box = padding_box - padding;
-        let outline_box = border_box.inset(outline_width);
+        let outline_box = border_box.inset(outline_width + outline_offset);
 
         // Correct the border radii if they are too big if two border radii would intersect, then we need to shrink
         // ALL border radii by the same factor such that they do not
@@ -80,6 +82,7 @@ impl CssBox {
             content_box,
             outline_box,
             outline_width,
+            outline_offset,
             padding_width: padding,
             border_width: border,
             border_radii,
@@ -139,18 +142,65 @@ impl CssBox {
         path
     }
 
-    /// Construct a bezpath drawing the outline
-    pub fn outline(&self) -> BezPath {
-        let mut path = BezPath::new();
+    /// Construct a box whose border is the band of this box's border between `start` and `end`. These are
+    /// fractions of the border width, measured from the outer edge (`0.0`) to the inner edge (`1.0`).
+    ///
+    /// Used to paint border styles which split the border into several parts (`double`, `groove` and `ridge`)
+    pub fn border_band(&self, start: f64, end: f64) -> CssBox {
+        let scale_border = |factor: f64| Insets {
+            x0: self.border_width.x0 * factor,
+            y0: self.border_width.y0 * factor,
+            x1: self.border_width.x1 * factor,
+            y1: self.border_width.y1 * factor,
+        };
+        let outer = scale_border(start);
+        let shrink = |radius: Vec2, x: f64, y: f64| {
+            Vec2::new((radius.x - x).max(0.0), (radius.y - y).max(0.0))
+        };
+        let radii = NonUniformRoundedRectRadii {
+            top_left: shrink(self.border_radii.top_left, outer.x0, outer.y0),
+            top_right: shrink(self.border_radii.top_right, outer.x1, outer.y0),
+            bottom_right: shrink(self.border_radii.bottom_right, outer.x1, outer.y1),
+            bottom_left: shrink(self.border_radii.bottom_left, outer.x0, outer.y1),
+        };
 
-        // TODO: this has been known to produce quirky outputs with hugely rounded edges
-        self.shape(&mut path, CssBoxKind::OutlineBox, Direction::Clockwise);
-        path.move_to(self.corner(Corner::TopLeft, CssBoxKind::BorderBox));
+        CssBox::new(
+            self.border_box - outer,
+            scale_border(end - start),
+            self.padding_width,
+            self.outline_width,
+            self.outline_offset,
+            radii,
+        )
+    }
 
-        self.shape(&mut path, CssBoxKind::BorderBox, Direction::Anticlockwise);
-        path.move_to(self.corner(Corner::TopLeft, CssBoxKind::BorderBox));
+    /// Construct a box whose border is this box's outline, so that the outline can be painted using
+    /// the same code as borders. The outline is `outline_offset` outside of the border box and its
+    /// corners follow the border radius.
+    pub fn outline_frame(&self) -> CssBox {
+        let expand = self.outline_width + self.outline_offset;
+        let grow = |radius: Vec2| {
+            if radius.x == 0.0 || radius.y == 0.0 {
+                Vec2::ZERO
+            } else {
+                Vec2::new((radius.x + expand).max(0.0), (radius.y + expand).max(0.0))
+            }
+        };
+        let radii = NonUniformRoundedRectRadii {
+            top_left: grow(self.border_radii.top_left),
+            top_right: grow(self.border_radii.top_right),
+            bottom_right: grow(self.border_radii.bottom_right),
+            bottom_left: grow(self.border_radii.bottom_left),
+        };
 
-        path
+        CssBox::new(
+            self.outline_box,
+            Insets::uniform(self.outline_width),
+            Insets::ZERO,
+            0.0,
+            0.0,
+            radii,
+        )
     }
 
     /// Construct a bezpath drawing the frame border
@@ -229,7 +279,6 @@ impl CssBox {
 
     fn corner(&self, corner: Corner, css_box: CssBoxKind) -> Point {
         let Rect { x0, y0, x1, y1 } = match css_box {
-            CssBoxKind::OutlineBox => self.outline_box,
             CssBoxKind::BorderBox => self.border_box,
             CssBoxKind::PaddingBox => self.padding_box,
             CssBoxKind::ContentBox => self.content_box,
@@ -411,7 +460,6 @@ impl CssBox {
         }
 
         let css_box: Insets = match side {
-            OutlineBox => return false,
             BorderBox => return false,
             PaddingBox => self.border_width,
             ContentBox => add_insets(self.border_width, self.padding_width),
@@ -459,7 +507,6 @@ impl CssBox {
 
         let radii: Vec2 = match side {
             BorderBox => corner_radii,
-            OutlineBox => corner_radii + Vec2::new(self.outline_width, self.outline_width),
             PaddingBox => corner_radii - get_corner_insets(*border_width, corner),
             ContentBox => {
                 corner_radii - get_corner_insets(add_insets(*border_width, *padding_width), corner)
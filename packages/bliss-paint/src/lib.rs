@@ -8,6 +8,9 @@ mod debug_overlay;
 mod gradient;
 mod kurbo_css;
 mod layers;
+#[cfg(feature = "raster-pdf")]
+mod pdf;
+mod print;
 mod render;
 mod sizing;
 mod text;
@@ -16,6 +19,11 @@ use anyrender::PaintScene;
 use bliss_dom::{BaseDocument, util::Color};
 use render::BlissDomPainter;
 
+#[cfg(feature = "raster-pdf")]
+pub use pdf::render_raster_pdf;
+pub use print::{PageSetup, paginate, paint_page};
+pub use text::TextRenderingOptions;
+
 /// Paint a [`bliss_dom::BaseDocument`] by pushing drawing commands into
 /// an impl [`anyrender::PaintScene`].
 ///
@@ -34,8 +42,39 @@ pub fn paint_scene(
     x_offset: u32,
     y_offset: u32,
 ) {
-    let generator =
-        BlissDomPainter::new(dom, scale, width, height, x_offset as f64, y_offset as f64);
+    paint_scene_with_options(
+        scene,
+        dom,
+        scale,
+        width,
+        height,
+        x_offset,
+        y_offset,
+        TextRenderingOptions::default(),
+    );
+}
+
+/// Like [`paint_scene`], but rasterizing text with the given [`TextRenderingOptions`]
+#[allow(clippy::too_many_arguments)]
+pub fn paint_scene_with_options(
+    scene: &mut impl PaintScene,
+    dom: &BaseDocument,
+    scale: f64,
+    width: u32,
+    height: u32,
+    x_offset: u32,
+    y_offset: u32,
+    text_rendering: TextRenderingOptions,
+) {
+    let generator = BlissDomPainter::new(
+        dom,
+        scale,
+        width,
+        height,
+        x_offset as f64,
+        y_offset as f64,
+        text_rendering,
+    );
     generator.paint_scene(scene);
 
     // println!(
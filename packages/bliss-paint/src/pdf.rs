@@ -0,0 +1,204 @@
+//! Export of documents to (multi-page) raster-only PDF files
+
+use std::io::Write as _;
+
+use anyrender::{ImageRenderer, PaintScene as _, render_to_buffer};
+use bliss_dom::BaseDocument;
+use kurbo::{Affine, Rect};
+use peniko::{Color, Fill};
+
+use crate::print::{PageSetup, paginate, paint_page};
+use crate::text::TextRenderingOptions;
+
+/// The number of PDF points (1/72in) per CSS pixel (1/96in)
+const POINTS_PER_PX: f64 = 0.75;
+
+/// Render a document into a raster-only PDF with one page per page of content.
+///
+/// The document is laid out against the content width of the page (and restored to its original
+/// viewport afterwards). Each page is rasterized at [`PageSetup::scale`] using the image renderer `R`
+/// (e.g. `anyrender_vello_cpu::VelloCpuImageRenderer`) and embedded as an image: there is no vector
+/// backend, so text in the PDF can't be selected or searched and the file size grows with the scale.
+///
+/// Page size and margins come from the [`PageSetup`] rather than from `@page` rules, and pages are
+/// split by [`paginate`] without honouring `break-*` properties (see [`PageSetup`]).
+pub fn render_raster_pdf<R: ImageRenderer>(
+    dom: &mut BaseDocument,
+    setup: &PageSetup,
+    text_rendering: TextRenderingOptions,
+) -> Vec<u8> {
+    // Lay the document out against the page
+    let original_viewport = dom.viewport().clone();
+    let mut viewport = original_viewport.clone();
+    viewport.hidpi_scale = setup.scale as f32;
+    viewport.zoom = 1.0;
+    viewport.window_size = (
+        (setup.content_width() * setup.scale).round() as u32,
+        (setup.content_height() * setup.scale).round() as u32,
+    );
+    dom.set_viewport(viewport);
+    dom.resolve(0.0);
+
+    let width = (setup.width * setup.scale).round() as u32;
+    let height = (setup.height * setup.scale).round() as u32;
+    let page_starts = paginate(dom, setup);
+
+    let mut pdf = PdfWriter::new();
+    for (idx, &page_start) in page_starts.iter().enumerate() {
+        let page_end = page_starts
+            .get(idx + 1)
+            .copied()
+            .unwrap_or(page_start + setup.content_height());
+
+        let rgba = render_to_buffer::<R, _>(
+            |scene| {
+                // Paper is white
+                let page = Rect::new(0.0, 0.0, width as f64, height as f64);
+                scene.fill(Fill::NonZero, Affine::IDENTITY, Color::WHITE, None, &page);
+                paint_page(scene, dom, setup, page_start, page_end, text_rendering);
+            },
+            width,
+            height,
+        );
+        pdf.add_image_page(
+            &rgba,
+            width,
+            height,
+            setup.width * POINTS_PER_PX,
+            setup.height * POINTS_PER_PX,
+        );
+    }
+
+    dom.set_viewport(original_viewport);
+    dom.resolve(0.0);
+
+    pdf.finish()
+}
+
+/// A minimal PDF writer which produces documents whose pages each consist of a single image
+struct PdfWriter {
+    buf: Vec<u8>,
+    /// The byte offset of each object (indexed by object number - 1)
+    offsets: Vec<usize>,
+    /// The object numbers of the pages
+    pages: Vec<usize>,
+}
+
+// Object numbers of the objects which are always present
+const CATALOG: usize = 1;
+const PAGES: usize = 2;
+
+impl PdfWriter {
+    fn new() -> Self {
+        let mut buf = Vec::new();
+        // The binary comment marks the file as containing binary data
+        buf.extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+        Self {
+            buf,
+            offsets: vec![0; 2],
+            pages: Vec::new(),
+        }
+    }
+
+    fn start_object(&mut self, id: usize) {
+        if self.offsets.len() < id {
+            self.offsets.resize(id, 0);
+        }
+        self.offsets[id - 1] = self.buf.len();
+        writeln!(self.buf, "{id} 0 obj").unwrap();
+    }
+
+    fn write_object(&mut self, id: usize, dict: &str) {
+        self.start_object(id);
+        writeln!(self.buf, "{dict}\nendobj").unwrap();
+    }
+
+    fn write_stream(&mut self, id: usize, dict: &str, data: &[u8]) {
+        self.start_object(id);
+        write!(self.buf, "<< {dict} /Length {} >>\nstream\n", data.len()).unwrap();
+        self.buf.extend_from_slice(data);
+        self.buf.extend_from_slice(b"\nendstream\nendobj\n");
+    }
+
+    /// Add a page (of `page_width` x `page_height` points) which is filled by an RGBA image.
+    /// The alpha channel is discarded.
+    fn add_image_page(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+        page_width: f64,
+        page_height: f64,
+    ) {
+        let page = self.offsets.len() + 1;
+        let contents = page + 1;
+        let image = page + 2;
+
+        let rgb: Vec<u8> = rgba
+            .chunks_exact(4)
+            .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+            .collect();
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&rgb, 6);
+        let content = format!("q {page_width:.2} 0 0 {page_height:.2} 0 0 cm /Im0 Do Q");
+
+        self.write_object(
+            page,
+            &format!(
+                "<< /Type /Page /Parent {PAGES} 0 R /MediaBox [0 0 {page_width:.2} {page_height:.2}] \
+                 /Resources << /XObject << /Im0 {image} 0 R >> >> /Contents {contents} 0 R >>"
+            ),
+        );
+        self.write_stream(contents, "", content.as_bytes());
+        self.write_stream(
+            image,
+            &format!(
+                "/Type /XObject /Subtype /Image /Width {width} /Height {height} \
+                 /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /FlateDecode"
+            ),
+            &compressed,
+        );
+        self.pages.push(page);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.write_object(CATALOG, &format!("<< /Type /Catalog /Pages {PAGES} 0 R >>"));
+        let kids: Vec<String> = self.pages.iter().map(|id| format!("{id} 0 R")).collect();
+        self.write_object(
+            PAGES,
+            &format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                self.pages.len()
+            ),
+        );
+
+        let xref_offset = self.buf.len();
+        let size = self.offsets.len() + 1;
+        writeln!(self.buf, "xref\n0 {size}\n0000000000 65535 f ").unwrap();
+        for offset in &self.offsets {
+            writeln!(self.buf, "{offset:010} 00000 n ").unwrap();
+        }
+        writeln!(
+            self.buf,
+            "trailer\n<< /Size {size} /Root {CATALOG} 0 R >>\nstartxref\n{xref_offset}\n%%EOF"
+        )
+        .unwrap();
+
+        self.buf
+    }
+}
+
+#[test]
+fn writes_image_pages() {
+    let mut pdf = PdfWriter::new();
+    let pixels = [255u8; 2 * 2 * 4];
+    pdf.add_image_page(&pixels, 2, 2, 595.28, 841.89);
+    pdf.add_image_page(&pixels, 2, 2, 595.28, 841.89);
+    let bytes = pdf.finish();
+    let text = String::from_utf8_lossy(&bytes);
+
+    assert!(bytes.starts_with(b"%PDF-1.4"));
+    assert!(text.contains("/Kids [3 0 R 6 0 R] /Count 2"));
+    assert!(text.contains("xref\n0 9\n"));
+    assert!(text.trim_end().ends_with("%%EOF"));
+}
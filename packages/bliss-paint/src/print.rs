@@ -0,0 +1,180 @@
+//! Paginated rendering, for printing documents or exporting them to PDF
+
+use anyrender::PaintScene;
+use bliss_dom::BaseDocument;
+use kurbo::{Affine, Insets, Rect};
+
+use crate::render::BlissDomPainter;
+use crate::text::TextRenderingOptions;
+
+/// The size and margins of the pages which a document is split into (in CSS pixels)
+///
+/// Stylo doesn't parse `@page` rules or the `break-*` properties, so page margins are set here
+/// rather than by the document's stylesheets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageSetup {
+    pub width: f64,
+    pub height: f64,
+    /// The space between the edges of each page and its content
+    pub margins: Insets,
+    /// The number of device pixels per CSS pixel which pages are rendered at
+    pub scale: f64,
+}
+
+impl PageSetup {
+    /// ISO A4 paper (210mm x 297mm) with half-inch margins
+    pub const A4: Self = Self {
+        width: 793.7,
+        height: 1122.5,
+        margins: Insets::uniform(48.0),
+        scale: 2.0,
+    };
+
+    /// US Letter paper (8.5in x 11in) with half-inch margins
+    pub const LETTER: Self = Self {
+        width: 816.0,
+        height: 1056.0,
+        margins: Insets::uniform(48.0),
+        scale: 2.0,
+    };
+
+    pub fn with_margins(mut self, margins: Insets) -> Self {
+        self.margins = margins;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Swap the width and height of the page
+    pub fn landscape(mut self) -> Self {
+        std::mem::swap(&mut self.width, &mut self.height);
+        self
+    }
+
+    /// The width of the area of each page which the document is laid out into
+    pub fn content_width(&self) -> f64 {
+        (self.width - self.margins.x0 - self.margins.x1).max(1.0)
+    }
+
+    /// The height of the area of each page which the document is laid out into
+    pub fn content_height(&self) -> f64 {
+        (self.height - self.margins.y0 - self.margins.y1).max(1.0)
+    }
+}
+
+/// Collect the vertical extents (in CSS px) of content which shouldn't be split across pages:
+/// lines of text and replaced elements (images, canvases, etc)
+fn unbreakable_spans(dom: &BaseDocument) -> Vec<(f64, f64)> {
+    let scale = dom.viewport().scale_f64();
+    let mut spans = Vec::new();
+
+    for (_, node) in dom.tree().iter() {
+        let Some(element) = node.element_data() else {
+            continue;
+        };
+        let layout = &node.final_layout;
+        if layout.size.height <= 0.0 {
+            continue;
+        }
+        let top = node.absolute_position(0.0, 0.0).y as f64;
+
+        if node.flags.is_inline_root() {
+            if let Some(inline_layout) = &element.inline_layout_data {
+                let content_top = top + (layout.border.top + layout.padding.top) as f64;
+                for line in inline_layout.layout.lines() {
+                    let metrics = line.metrics();
+                    spans.push((
+                        content_top + metrics.block_min_coord as f64 / scale,
+                        content_top + metrics.block_max_coord as f64 / scale,
+                    ));
+                }
+            }
+        }
+
+        if element.raster_image_data().is_some() || element.canvas_data().is_some() {
+            spans.push((top, top + layout.size.height as f64));
+        }
+    }
+
+    spans
+}
+
+/// Choose where to split a document into pages. The document must already be laid out at the
+/// [`content_width`](PageSetup::content_width) of the page.
+///
+/// Returns the offset (in CSS px) at which each page starts. Page breaks are moved up to avoid
+/// splitting lines of text and images across pages (unless they are taller than a page). Forced and
+/// avoided breaks (`break-before`, `break-after` and `break-inside`) aren't supported.
+pub fn paginate(dom: &BaseDocument, setup: &PageSetup) -> Vec<f64> {
+    let page_height = setup.content_height();
+    let root_layout = &dom.root_element().final_layout;
+    let doc_height = root_layout.size.height.max(root_layout.content_size.height) as f64;
+    let spans = unbreakable_spans(dom);
+
+    let mut page_starts = vec![0.0];
+    let mut start = 0.0;
+    while start + page_height < doc_height {
+        let mut end = start + page_height;
+
+        // Moving the break may cause it to split other content, so repeat until nothing is split
+        while let Some(top) = spans
+            .iter()
+            .filter(|&&(top, bottom)| top > start && top < end && bottom > end)
+            .filter(|&&(top, bottom)| bottom - top <= page_height)
+            .map(|&(top, _)| top)
+            .reduce(f64::min)
+        {
+            end = top;
+        }
+
+        page_starts.push(end);
+        start = end;
+    }
+
+    page_starts
+}
+
+/// Paint the page of a document which spans from `page_start` to `page_end` (as returned by [`paginate`]).
+///
+/// The page is painted at [`PageSetup::scale`] with its top-left corner at the origin, and content is
+/// clipped to the area inside the page's margins. Like [`paint_scene`](crate::paint_scene), this assumes
+/// that styles and layout are already resolved.
+pub fn paint_page(
+    scene: &mut impl PaintScene,
+    dom: &BaseDocument,
+    setup: &PageSetup,
+    page_start: f64,
+    page_end: f64,
+    text_rendering: TextRenderingOptions,
+) {
+    let scale = setup.scale;
+    let scroll = dom.viewport_scroll();
+    let margins = setup.margins;
+
+    let content_area = Rect::new(
+        margins.x0,
+        margins.y0,
+        margins.x0 + setup.content_width(),
+        margins.y0 + (page_end - page_start),
+    )
+    .scale_from_origin(scale);
+
+    // Offset the document so that `page_start` is at the top of the content area. Elements below
+    // `page_end` are not painted.
+    let painter = BlissDomPainter::new(
+        dom,
+        scale,
+        (setup.width * scale).ceil() as u32,
+        ((page_end - scroll.y).max(0.0) * scale).ceil() as u32,
+        margins.x0 + scroll.x,
+        margins.y0 - page_start + scroll.y,
+        text_rendering,
+    );
+
+    scene.push_clip_layer(Affine::IDENTITY, &content_area);
+    painter.paint_scene(scene);
+    scene.pop_layer();
+}
@@ -12,12 +12,13 @@ use crate::debug_overlay::render_debug_overlay;
 use crate::kurbo_css::NonUniformRoundedRectRadii;
 use crate::layers::LayerManager;
 use crate::sizing::compute_object_fit;
+use crate::text::TextRenderingOptions;
 use anyrender::{CustomPaint, Paint, PaintScene};
 use bliss_dom::node::{
     ListItemLayout, ListItemLayoutPosition, Marker, NodeData, RasterImageData, SpecialElementData,
     TextInputData, TextNodeData,
 };
-use bliss_dom::{BaseDocument, ElementData, Node, local_name};
+use bliss_dom::{BaseDocument, CaretShape, ElementData, Node, local_name};
 use bliss_traits::devtools::DevtoolSettings;
 
 use euclid::Transform3D;
@@ -35,9 +36,9 @@ use style::{
     },
 };
 
-use kurbo::{self, Affine, Insets, Point, Rect, Stroke, Vec2};
+use kurbo::{self, Affine, BezPath, Cap, Insets, Point, Rect, Shape as _, Stroke, Vec2};
 use peniko::{self, Fill, ImageData, ImageSampler};
-use style::values::generics::color::GenericColor;
+use style::values::generics::color::{GenericColor, GenericColorOrAuto};
 use taffy::Layout;
 
 /// A short-lived struct which holds a bunch of parameters for rendering a scene so
@@ -51,6 +52,7 @@ pub struct BlissDomPainter<'dom> {
     pub(crate) initial_x: f64,
     pub(crate) initial_y: f64,
     pub(crate) layer_manager: LayerManager,
+    pub(crate) text_rendering: TextRenderingOptions,
     /// Cached selection ranges for O(1) lookup: node_id -> (start_offset, end_offset)
     pub(crate) selection_ranges: HashMap<usize, (usize, usize)>,
 }
@@ -64,6 +66,7 @@ impl<'dom> BlissDomPainter<'dom> {
         height: u32,
         initial_x: f64,
         initial_y: f64,
+        text_rendering: TextRenderingOptions,
     ) -> Self {
         let selection_ranges: HashMap<usize, (usize, usize)> = dom
             .get_text_selection_ranges()
@@ -81,6 +84,7 @@ impl<'dom> BlissDomPainter<'dom> {
             initial_x,
             initial_y,
             layer_manager,
+            text_rendering,
             selection_ranges,
         }
     }
@@ -449,11 +453,21 @@ impl<'dom> BlissDomPainter<'dom> {
     }
 }
 
-fn to_image_quality(image_rendering: ImageRendering) -> peniko::ImageQuality {
+/// Select the sampling used to draw an image with the given `image-rendering` when it is scaled by
+/// `x_scale` and `y_scale` (in device pixels per image pixel)
+///
+/// `crisp-edges` always uses nearest-neighbor sampling. `pixelated` only uses nearest-neighbor
+/// sampling when the image is scaled up, and is otherwise treated as `auto` (bilinear sampling).
+fn to_image_quality(
+    image_rendering: ImageRendering,
+    x_scale: f64,
+    y_scale: f64,
+) -> peniko::ImageQuality {
+    let is_upscaled = x_scale > 1.0 || y_scale > 1.0;
     match image_rendering {
-        ImageRendering::Auto => peniko::ImageQuality::Medium,
         ImageRendering::CrispEdges => peniko::ImageQuality::Low,
-        ImageRendering::Pixelated => peniko::ImageQuality::Low,
+        ImageRendering::Pixelated if is_upscaled => peniko::ImageQuality::Low,
+        ImageRendering::Auto | ImageRendering::Pixelated => peniko::ImageQuality::Medium,
     }
 }
 
@@ -512,15 +526,16 @@ impl ElementCx<'_> {
                 Affine::translate((pos.x * self.scale, pos.y * self.scale)) * self.transform;
 
             // Render text selection highlight (if any) using cached selection ranges
-            if let Some(&(sel_start, sel_end)) = self.context.selection_ranges.get(&self.node.id) {
-                crate::text::draw_text_selection(
-                    scene,
-                    &text_layout.layout,
-                    transform,
-                    sel_start,
-                    sel_end,
-                );
-            }
+            let selection_path = self
+                .context
+                .selection_ranges
+                .get(&self.node.id)
+                .map(|&(sel_start, sel_end)| {
+                    crate::text::text_selection_path(&text_layout.layout, sel_start, sel_end)
+                });
+            let selection_text_color = selection_path
+                .as_ref()
+                .and_then(|path| self.draw_selection_highlight(scene, transform, path));
 
             // Render text
             crate::text::stroke_text(
@@ -528,10 +543,90 @@ impl ElementCx<'_> {
                 text_layout.layout.lines(),
                 self.context.dom,
                 transform,
+                self.scale,
+                self.context.text_rendering,
+                None,
             );
+
+            // Repaint the selected text in the `::selection` color
+            if let (Some(path), Some(color)) = (&selection_path, selection_text_color) {
+                scene.push_clip_layer(transform, path);
+                crate::text::stroke_text(
+                    scene,
+                    text_layout.layout.lines(),
+                    self.context.dom,
+                    transform,
+                    self.scale,
+                    self.context.text_rendering,
+                    Some(color),
+                );
+                scene.pop_layer();
+            }
         }
     }
 
+    /// Fill the text selection highlight using the element's `::selection` background color (or the default
+    /// highlight color). Returns the `::selection` text color if it differs from the element's text color.
+    fn draw_selection_highlight(
+        &self,
+        scene: &mut impl PaintScene,
+        transform: Affine,
+        path: &BezPath,
+    ) -> Option<Color> {
+        let selection_styles = self.node.selection_styles();
+
+        let background = selection_styles
+            .as_ref()
+            .map(|styles| {
+                styles
+                    .get_background()
+                    .background_color
+                    .resolve_to_absolute(&styles.clone_color())
+                    .as_srgb_color()
+            })
+            .filter(|color| color.components[3] != 0.0)
+            .unwrap_or(SELECTION_COLOR);
+        scene.fill(Fill::NonZero, transform, background, None, path);
+
+        let color = selection_styles?.clone_color();
+        (color != self.style.clone_color()).then(|| color.as_srgb_color())
+    }
+
+    fn draw_caret(
+        &self,
+        scene: &mut impl PaintScene,
+        input_data: &TextInputData,
+        transform: Affine,
+    ) {
+        if !self.context.dom.caret_visible() {
+            return;
+        }
+
+        let Some(cursor) = input_data.editor.cursor_geometry(1.5) else {
+            return;
+        };
+        let caret_shape = self.context.dom.caret_shape();
+        let rect = convert_rect(&cursor);
+        let rect = match caret_shape {
+            CaretShape::Bar => rect,
+            // Roughly the width of a character
+            CaretShape::Block => rect.with_size((rect.height() * 0.5, rect.height())),
+        };
+
+        let current_color = self.style.clone_color();
+        let color = match &self.style.get_inherited_ui().caret_color.0 {
+            GenericColorOrAuto::Color(color) => color.resolve_to_absolute(&current_color),
+            GenericColorOrAuto::Auto => current_color,
+        };
+        let mut color = color.as_srgb_color();
+        if caret_shape == CaretShape::Block {
+            // Keep the text under the caret legible
+            color = color.multiply_alpha(0.5);
+        }
+
+        scene.fill(Fill::NonZero, transform, color, None, &rect);
+    }
+
     fn draw_text_input_text(&self, scene: &mut impl PaintScene, pos: Point) {
         // Render the text in text inputs
         if let Some(input_data) = self.text_input {
@@ -546,38 +641,46 @@ impl ElementCx<'_> {
             let transform =
                 Affine::translate((pos.x * self.scale, pos.y * self.scale)) * self.transform;
 
+            let mut selection = None;
             if self.node.is_focussed() {
                 // Render selection/caret
+                let mut selection_path = BezPath::new();
                 for (rect, _line_idx) in input_data.editor.selection_geometry().iter() {
-                    scene.fill(
-                        Fill::NonZero,
-                        transform,
-                        SELECTION_COLOR,
-                        None,
-                        &convert_rect(rect),
-                    );
+                    selection_path.extend(convert_rect(rect).path_elements(0.1));
                 }
-                if let Some(cursor) = input_data.editor.cursor_geometry(1.5) {
-                    // TODO: Use the `caret-color` attribute here if present.
-                    let color = self.style.get_inherited_text().color;
-
-                    scene.fill(
-                        Fill::NonZero,
-                        transform,
-                        color.as_srgb_color(),
-                        None,
-                        &convert_rect(&cursor),
-                    );
-                };
+                if !selection_path.is_empty() {
+                    let color = self.draw_selection_highlight(scene, transform, &selection_path);
+                    selection = color.map(|color| (selection_path, color));
+                }
+                self.draw_caret(scene, input_data, transform);
             }
 
             // Render text
+            let layout = input_data.editor.try_layout().unwrap();
             crate::text::stroke_text(
                 scene,
-                input_data.editor.try_layout().unwrap().lines(),
+                layout.lines(),
                 self.context.dom,
                 transform,
+                self.scale,
+                self.context.text_rendering,
+                None,
             );
+
+            // Repaint the selected text in the `::selection` color
+            if let Some((path, color)) = selection {
+                scene.push_clip_layer(transform, &path);
+                crate::text::stroke_text(
+                    scene,
+                    layout.lines(),
+                    self.context.dom,
+                    transform,
+                    self.scale,
+                    self.context.text_rendering,
+                    Some(color),
+                );
+                scene.pop_layer();
+            }
         }
     }
 
@@ -616,7 +719,15 @@ impl ElementCx<'_> {
             let transform =
                 Affine::translate((pos.x * self.scale, pos.y * self.scale)) * self.transform;
 
-            crate::text::stroke_text(scene, layout.lines(), self.context.dom, transform);
+            crate::text::stroke_text(
+                scene,
+                layout.lines(),
+                self.context.dom,
+                transform,
+                self.scale,
+                self.context.text_rendering,
+                None,
+            );
         }
     }
 
@@ -711,7 +822,6 @@ impl ElementCx<'_> {
             let object_fit = self.style.clone_object_fit();
             let object_position = self.style.clone_object_position();
             let image_rendering = self.style.clone_image_rendering();
-            let quality = to_image_quality(image_rendering);
 
             // Apply object-fit algorithm
             let container_size = taffy::Size {
@@ -736,6 +846,7 @@ impl ElementCx<'_> {
 
             let x_scale = paint_size.width as f64 / object_size.width as f64;
             let y_scale = paint_size.height as f64 / object_size.height as f64;
+            let quality = to_image_quality(image_rendering, x_scale, y_scale);
             let transform = self
                 .transform
                 .pre_translate(Vec2 { x, y })
@@ -779,8 +890,15 @@ impl ElementCx<'_> {
             let initial_y = self.pos.y + self.frame.content_box.origin().y;
             // let transform = self.transform.then_translate(Vec2 { x, y });
 
-            let painter =
-                BlissDomPainter::new(&sub_doc, scale, width, height, initial_x, initial_y);
+            let painter = BlissDomPainter::new(
+                &sub_doc,
+                scale,
+                width,
+                height,
+                initial_x,
+                initial_y,
+                self.context.text_rendering,
+            );
             painter.paint_scene(scene);
         }
     }
@@ -901,44 +1019,108 @@ impl ElementCx<'_> {
     fn draw_border_edge(&self, scene: &mut impl PaintScene, edge: Edge) {
         let style = &*self.style;
         let border = style.get_border();
-        let path = self.frame.border_edge_shape(edge);
 
         let current_color = style.clone_color();
-        let color = match edge {
-            Edge::Top => border
-                .border_top_color
-                .resolve_to_absolute(&current_color)
-                .as_srgb_color(),
-            Edge::Right => border
-                .border_right_color
-                .resolve_to_absolute(&current_color)
-                .as_srgb_color(),
-            Edge::Bottom => border
-                .border_bottom_color
-                .resolve_to_absolute(&current_color)
-                .as_srgb_color(),
-            Edge::Left => border
-                .border_left_color
-                .resolve_to_absolute(&current_color)
-                .as_srgb_color(),
+        let (color, border_style, width) = match edge {
+            Edge::Top => (
+                border
+                    .border_top_color
+                    .resolve_to_absolute(&current_color)
+                    .as_srgb_color(),
+                border.border_top_style,
+                self.frame.border_width.y0,
+            ),
+            Edge::Right => (
+                border
+                    .border_right_color
+                    .resolve_to_absolute(&current_color)
+                    .as_srgb_color(),
+                border.border_right_style,
+                self.frame.border_width.x1,
+            ),
+            Edge::Bottom => (
+                border
+                    .border_bottom_color
+                    .resolve_to_absolute(&current_color)
+                    .as_srgb_color(),
+                border.border_bottom_style,
+                self.frame.border_width.y1,
+            ),
+            Edge::Left => (
+                border
+                    .border_left_color
+                    .resolve_to_absolute(&current_color)
+                    .as_srgb_color(),
+                border.border_left_style,
+                self.frame.border_width.x0,
+            ),
         };
 
+        self.draw_styled_edge(scene, &self.frame, edge, border_style, color, width);
+    }
+
+    /// Draw one edge of the border of `frame` (which may be the element's border or its outline)
+    fn draw_styled_edge(
+        &self,
+        scene: &mut impl PaintScene,
+        frame: &CssBox,
+        edge: Edge,
+        border_style: BorderStyle,
+        color: Color,
+        width: f64,
+    ) {
         let alpha = color.components[3];
-        if alpha != 0.0 {
+        if alpha == 0.0 || width <= 0.0 {
+            return;
+        }
+
+        // Whether the edge is shaded darker for `inset` borders (and lighter for `outset` borders)
+        let is_top_left = matches!(edge, Edge::Top | Edge::Left);
+        let fill_band = |scene: &mut _, start: f64, end: f64, color: Color| {
+            let path = frame.border_band(start, end).border_edge_shape(edge);
             scene.fill(Fill::NonZero, self.transform, color, None, &path);
+        };
+
+        match border_style {
+            BorderStyle::None | BorderStyle::Hidden => {}
+            BorderStyle::Solid => fill_band(scene, 0.0, 1.0, color),
+            // There isn't enough room to draw two lines with a gap between them
+            BorderStyle::Double if width < 3.0 => fill_band(scene, 0.0, 1.0, color),
+            BorderStyle::Double => {
+                fill_band(scene, 0.0, 1.0 / 3.0, color);
+                fill_band(scene, 2.0 / 3.0, 1.0, color);
+            }
+            BorderStyle::Inset => fill_band(scene, 0.0, 1.0, shade(color, is_top_left)),
+            BorderStyle::Outset => fill_band(scene, 0.0, 1.0, shade(color, !is_top_left)),
+            BorderStyle::Groove => {
+                fill_band(scene, 0.0, 0.5, shade(color, is_top_left));
+                fill_band(scene, 0.5, 1.0, shade(color, !is_top_left));
+            }
+            BorderStyle::Ridge => {
+                fill_band(scene, 0.0, 0.5, shade(color, !is_top_left));
+                fill_band(scene, 0.5, 1.0, shade(color, is_top_left));
+            }
+            BorderStyle::Dotted | BorderStyle::Dashed => {
+                // Stroke the centerline of the border, clipped to this edge
+                let path = frame.border_edge_shape(edge);
+                let centerline = frame.border_band(0.5, 1.0).border_box_path();
+                let stroke = if border_style == BorderStyle::Dotted {
+                    Stroke::new(width)
+                        .with_caps(Cap::Round)
+                        .with_dashes(0.0, [0.0, width * 2.0])
+                } else {
+                    Stroke::new(width).with_dashes(0.0, [width * 3.0, width * 2.0])
+                };
+
+                scene.push_clip_layer(self.transform, &path);
+                scene.stroke(&stroke, self.transform, color, None, &centerline);
+                scene.pop_layer();
+            }
         }
     }
 
-    /// ❌ dotted - Defines a dotted border
-    /// ❌ dashed - Defines a dashed border
-    /// ✅ solid - Defines a solid border
-    /// ❌ double - Defines a double border
-    /// ❌ groove - Defines a 3D grooved border. The effect depends on the border-color value
-    /// ❌ ridge - Defines a 3D ridged border. The effect depends on the border-color value
-    /// ❌ inset - Defines a 3D inset border. The effect depends on the border-color value
-    /// ❌ outset - Defines a 3D outset border. The effect depends on the border-color value
-    /// ✅ none - Defines no border
-    /// ✅ hidden - Defines a hidden border
+    /// Draw the outline (including the focus ring for `outline-style: auto`) outside of the border box.
+    /// Outlines don't take up space, so this doesn't affect layout.
     fn draw_outline(&self, scene: &mut impl PaintScene) {
         let outline = self.style.get_outline();
 
@@ -949,25 +1131,15 @@ impl ElementCx<'_> {
             .as_srgb_color();
 
         let style = match outline.outline_style {
-            OutlineStyle::Auto => return,
+            // The focus ring is drawn as a solid line in the outline color
+            OutlineStyle::Auto => BorderStyle::Solid,
             OutlineStyle::BorderStyle(style) => style,
         };
 
-        let path = match style {
-            BorderStyle::None | BorderStyle::Hidden => return,
-            BorderStyle::Solid => self.frame.outline(),
-
-            // TODO: Implement other border styles
-            BorderStyle::Inset
-            | BorderStyle::Groove
-            | BorderStyle::Outset
-            | BorderStyle::Ridge
-            | BorderStyle::Dotted
-            | BorderStyle::Dashed
-            | BorderStyle::Double => self.frame.outline(),
-        };
-
-        scene.fill(Fill::NonZero, self.transform, color, None, &path);
+        let frame = self.frame.outline_frame();
+        for edge in [Edge::Top, Edge::Right, Edge::Bottom, Edge::Left] {
+            self.draw_styled_edge(scene, &frame, edge, style, color, self.frame.outline_width);
+        }
     }
 }
 impl<'a> std::ops::Deref for ElementCx<'a> {
@@ -977,6 +1149,15 @@ impl<'a> std::ops::Deref for ElementCx<'a> {
     }
 }
 
+/// Darken a border color for the shaded sides of `inset`, `outset`, `groove` and `ridge` borders
+fn shade(color: Color, darken: bool) -> Color {
+    if !darken {
+        return color;
+    }
+    let [r, g, b, a] = color.components;
+    Color::new([r * 0.5, g * 0.5, b * 0.5, a])
+}
+
 fn insets_from_taffy_rect(input: taffy::Rect<f64>) -> Insets {
     Insets {
         x0: input.left,
@@ -996,6 +1177,7 @@ fn create_css_rect(style: &ComputedValues, layout: &Layout, scale: f64) -> CssBo
     let border = insets_from_taffy_rect(layout.border.map(|p| p as f64 * scale));
     let padding = insets_from_taffy_rect(layout.padding.map(|p| p as f64 * scale));
     let outline_width = style.get_outline().outline_width.0.to_f64_px() * scale;
+    let outline_offset = style.get_outline().outline_offset.px() as f64 * scale;
 
     // Resolve the radii to a length. need to downscale since the radii are in document pixels
     let resolve_w = CSSPixelLength::new(width as _);
@@ -1014,5 +1196,12 @@ fn create_css_rect(style: &ComputedValues, layout: &Layout, scale: f64) -> CssBo
         bottom_left: resolve_radii(&s_border.border_bottom_left_radius),
     };
 
-    CssBox::new(border_box, border, padding, outline_width, border_radii)
+    CssBox::new(
+        border_box,
+        border,
+        padding,
+        outline_width,
+        outline_offset,
+        border_radii,
+    )
 }
@@ -8,6 +8,7 @@ use peniko::{self, Fill};
 use style::{
     properties::{
         generated::longhands::{
+            background_attachment::single_value::computed_value::T as StyloBackgroundAttachment,
             background_clip::single_value::computed_value::T as StyloBackgroundClip,
             background_origin::single_value::computed_value::T as StyloBackgroundOrigin,
         },
@@ -135,6 +136,31 @@ impl ElementCx<'_> {
         }
     }
 
+    /// The rect (in the element's coordinate space) relative to which the background layer `idx` is positioned
+    /// and sized. This is the box specified by `background-origin`, unless the layer has
+    /// `background-attachment: fixed` in which case it is the viewport.
+    fn background_positioning_area(&self, idx: usize) -> Rect {
+        let bg_styles = &self.style.get_background();
+
+        let background_attachment = get_cyclic(&bg_styles.background_attachment.0, idx);
+        if *background_attachment == StyloBackgroundAttachment::Fixed {
+            let viewport = Rect::from_origin_size(
+                (
+                    self.context.initial_x * self.scale,
+                    self.context.initial_y * self.scale,
+                ),
+                (self.context.width as f64, self.context.height as f64),
+            );
+            return self.transform.inverse().transform_rect_bbox(viewport);
+        }
+
+        match get_cyclic(&bg_styles.background_origin.0, idx) {
+            StyloBackgroundOrigin::BorderBox => self.frame.border_box,
+            StyloBackgroundOrigin::PaddingBox => self.frame.padding_box,
+            StyloBackgroundOrigin::ContentBox => self.frame.content_box,
+        }
+    }
+
     fn draw_solid_bg(&self, scene: &mut impl PaintScene, shape: &BezPath) {
         let current_color = self.style.clone_color();
         let background_color = &self.style.get_background().background_color;
@@ -205,16 +231,10 @@ impl ElementCx<'_> {
         };
 
         let image_rendering = self.style.clone_image_rendering();
-        let quality = to_image_quality(image_rendering);
 
         let bg_styles = &self.style.get_background();
 
-        let background_origin = get_cyclic(&bg_styles.background_origin.0, idx);
-        let origin_rect = match background_origin {
-            StyloBackgroundOrigin::BorderBox => self.frame.border_box,
-            StyloBackgroundOrigin::PaddingBox => self.frame.padding_box,
-            StyloBackgroundOrigin::ContentBox => self.frame.content_box,
-        };
+        let origin_rect = self.background_positioning_area(idx);
 
         let image_width = image_data.width as f64;
         let image_height = image_data.height as f64;
@@ -233,6 +253,7 @@ impl ElementCx<'_> {
 
         let x_ratio = bg_size.width / image_width;
         let y_ratio = bg_size.height / image_height;
+        let quality = to_image_quality(image_rendering, x_ratio, y_ratio);
 
         let BackgroundRepeat(repeat_x, repeat_y) = get_cyclic(&bg_styles.background_repeat.0, idx);
 
@@ -387,11 +408,15 @@ impl ElementCx<'_> {
 
         let bg_styles = &self.style.get_background();
 
-        let background_origin = *get_cyclic(&bg_styles.background_origin.0, idx);
-        let origin_rect = match background_origin {
-            StyloBackgroundOrigin::BorderBox => self.frame.border_box,
-            StyloBackgroundOrigin::PaddingBox => self.frame.padding_box,
-            StyloBackgroundOrigin::ContentBox => self.frame.content_box,
+        let origin_rect = self.background_positioning_area(idx);
+        let background_origin = match get_cyclic(&bg_styles.background_attachment.0, idx) {
+            // Fixed backgrounds tile across the whole viewport, so the tiles never need extending to the clip box
+            StyloBackgroundAttachment::Fixed => match background_clip {
+                StyloBackgroundClip::BorderBox => StyloBackgroundOrigin::BorderBox,
+                StyloBackgroundClip::PaddingBox => StyloBackgroundOrigin::PaddingBox,
+                StyloBackgroundClip::ContentBox => StyloBackgroundOrigin::ContentBox,
+            },
+            _ => *get_cyclic(&bg_styles.background_origin.0, idx),
         };
 
         let (bg_pos, bg_size) = compute_background_position_and_background_size(
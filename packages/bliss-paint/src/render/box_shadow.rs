@@ -78,22 +78,28 @@ impl ElementCx<'_> {
 
         let padding_box = self.frame.padding_box_path();
 
-        for shadow in box_shadow.iter().filter(|s| s.inset) {
+        // Shadows are painted back-to-front, so the first shadow in the list ends up on top
+        for shadow in box_shadow.iter().filter(|s| s.inset).rev() {
             let shadow_color = shadow
                 .base
                 .color
                 .resolve_to_absolute(&current_color)
                 .as_srgb_color();
-            if shadow_color == Color::TRANSPARENT {
-                return;
+            if shadow_color.components[3] == 0.0 {
+                continue;
             }
 
+            let x = shadow.base.horizontal.px() as f64 * self.scale;
+            let y = shadow.base.vertical.px() as f64 * self.scale;
+            let blur = shadow.base.blur.px() as f64 * self.scale;
+            let spread = shadow.spread.px() as f64 * self.scale;
+
+            // The shadow is cast by the edges of a "hole" which is the padding box shrunk by the spread
+            // distance and moved by the shadow offset. A positive spread grows the shadow inwards.
+            let hole = self.frame.padding_box.inflate(-spread, -spread) + Vec2::new(x, y);
+
             // TODO draw shadows with matching individual radii instead of averaging
-            let radius = self.frame.border_radii.average();
-            let transform = self.transform.then_translate(Vec2 {
-                x: shadow.base.horizontal.px() as f64,
-                y: shadow.base.vertical.px() as f64,
-            });
+            let radius = (self.frame.border_radii.average() - spread).max(0.0);
 
             scene.push_layer(Mix::Normal, 1.0, self.transform, &padding_box);
             scene.fill(
@@ -104,17 +110,14 @@ impl ElementCx<'_> {
                 &padding_box,
             );
 
-            scene.push_layer(Compose::DestOut, 1.0, self.transform, &padding_box);
-            scene.draw_box_shadow(
-                transform,
-                self.frame.border_box,
-                Color::WHITE,
-                radius,
-                shadow.base.blur.px() as f64 * self.scale,
-            );
+            // If the spread is larger than the box then there is no hole and the whole padding box is shadowed
+            if hole.width() > 0.0 && hole.height() > 0.0 {
+                scene.push_layer(Compose::DestOut, 1.0, self.transform, &padding_box);
+                scene.draw_box_shadow(self.transform, hole, Color::WHITE, radius, blur);
+                scene.pop_layer();
+            }
 
             scene.pop_layer();
-            scene.pop_layer();
         }
     }
 }
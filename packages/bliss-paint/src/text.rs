@@ -1,17 +1,85 @@
 use anyrender::PaintScene;
 use bliss_dom::{BaseDocument, node::TextBrush, util::ToColorColor};
-use kurbo::{Affine, Stroke};
+use kurbo::{Affine, BezPath, Cap, Shape as _, Stroke, Vec2};
 use parley::{Affinity, Cursor, Layout, Line, PositionedLayoutItem, Selection};
 use peniko::Fill;
-use style::values::computed::TextDecorationLine;
+use style::properties::longhands::text_decoration_style::computed_value::T as TextDecorationStyle;
+use style::values::computed::{CSSPixelLength, TextDecorationLength, TextDecorationLine};
+use style::values::generics::text::GenericTextDecorationLength;
 
-use crate::SELECTION_COLOR;
+use crate::color::Color;
+
+/// The maximum number of rings of copies drawn to approximate a blurred text shadow
+const MAX_BLUR_RINGS: usize = 3;
+/// The maximum number of copies of the glyphs drawn in each ring of a blurred text shadow
+const MAX_BLUR_RING_SAMPLES: usize = 12;
+/// The distance (in device pixels) between neighbouring copies of the glyphs in a blurred text shadow
+const BLUR_SAMPLE_SPACING: f64 = 2.0;
+
+/// Options controlling how text is rasterized
+///
+/// Only options which can be expressed through [`PaintScene`] are supported. The anti-aliasing
+/// mode (grayscale vs subpixel) and gamma/contrast are out of scope: `anyrender` has no way to
+/// request them per draw call, so they are determined by the renderer which the scene is painted
+/// into (all current renderers use grayscale anti-aliasing).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextRenderingOptions {
+    /// Whether glyph outlines are hinted (aligned to the pixel grid). Hinting makes small text
+    /// crisper on low-DPI displays at the cost of slightly distorting glyph shapes.
+    pub hinting: bool,
+    /// The amount (in device pixels) by which glyph stems are thickened, to increase the contrast of
+    /// text which looks thin or washed out. `0.0` draws glyphs exactly as the font specifies.
+    pub stem_darkening: f32,
+}
+
+impl Default for TextRenderingOptions {
+    fn default() -> Self {
+        Self {
+            hinting: true,
+            stem_darkening: 0.0,
+        }
+    }
+}
+
+/// The offsets and opacities of the translucent copies of the glyphs which are drawn to approximate
+/// a text shadow blurred by `blur` device pixels.
+///
+/// `PaintScene` has no blur filter for glyphs, so copies are spread around the shadow's offset in
+/// concentric rings. The number of rings and the number of copies in each ring grow with the blur
+/// radius (keeping neighbouring copies about [`BLUR_SAMPLE_SPACING`] apart), so small blurs stay
+/// cheap while large blurs remain smooth. Outer rings are fainter to approximate a Gaussian falloff.
+fn blur_samples(blur: f64) -> Vec<(Vec2, f32)> {
+    if blur < 0.5 {
+        return vec![(Vec2::ZERO, 1.0)];
+    }
+
+    let rings = ((blur / BLUR_SAMPLE_SPACING).ceil() as usize).clamp(1, MAX_BLUR_RINGS);
+    let mut samples = vec![(Vec2::ZERO, 0.4)];
+    for ring in 1..=rings {
+        let t = ring as f64 / rings as f64;
+        let radius = blur * t;
+        let count = ((std::f64::consts::TAU * radius / BLUR_SAMPLE_SPACING).ceil() as usize)
+            .clamp(4, MAX_BLUR_RING_SAMPLES);
+        // The total opacity of a ring falls off linearly from the centre to the edge of the blur
+        let alpha = ((2.4 - 1.6 * t) / count as f64) as f32;
+        // Stagger alternate rings so that their copies don't line up into streaks
+        let rotation = if ring % 2 == 0 { 0.5 } else { 0.0 };
+        samples.extend((0..count).map(|i| {
+            let angle = std::f64::consts::TAU * (i as f64 + rotation) / count as f64;
+            (Vec2::new(angle.cos(), angle.sin()) * radius, alpha)
+        }));
+    }
+    samples
+}
 
 pub(crate) fn stroke_text<'a>(
     scene: &mut impl PaintScene,
     lines: impl Iterator<Item = Line<'a, TextBrush>>,
     doc: &BaseDocument,
     transform: Affine,
+    scale: f64,
+    options: TextRenderingOptions,
+    color_override: Option<Color>,
 ) {
     for line in lines {
         for item in line.items() {
@@ -34,77 +102,255 @@ pub(crate) fn stroke_text<'a>(
                     .unwrap();
                 let itext_styles = styles.get_inherited_text();
                 let text_styles = styles.get_text();
-                let text_color = itext_styles.color.as_color_color();
-                let text_decoration_color = text_styles
-                    .text_decoration_color
-                    .as_absolute()
-                    .map(ToColorColor::as_color_color)
-                    .unwrap_or(text_color);
+                let text_color = color_override.unwrap_or(itext_styles.color.as_color_color());
+                let text_decoration_color = color_override.unwrap_or_else(|| {
+                    text_styles
+                        .text_decoration_color
+                        .as_absolute()
+                        .map(ToColorColor::as_color_color)
+                        .unwrap_or(text_color)
+                });
                 let text_decoration_brush = anyrender::Paint::from(text_decoration_color);
                 let text_decoration_line = text_styles.text_decoration_line;
+                let text_decoration_style = text_styles.text_decoration_style;
                 let has_underline = text_decoration_line.contains(TextDecorationLine::UNDERLINE);
+                let has_overline = text_decoration_line.contains(TextDecorationLine::OVERLINE);
                 let has_strikethrough =
                     text_decoration_line.contains(TextDecorationLine::LINE_THROUGH);
 
+                // Resolve a text-decoration-thickness or text-underline-offset (percentages are relative to 1em)
+                let resolve_decoration_length = |length: &TextDecorationLength| match length {
+                    GenericTextDecorationLength::Length(length) => Some(
+                        length
+                            .resolve(CSSPixelLength::new(font_size / scale as f32))
+                            .px()
+                            * scale as f32,
+                    ),
+                    GenericTextDecorationLength::Auto | GenericTextDecorationLength::FromFont => {
+                        None
+                    }
+                };
+                let thickness = resolve_decoration_length(&text_styles.text_decoration_thickness);
+                let underline_offset =
+                    resolve_decoration_length(&itext_styles.text_underline_offset).unwrap_or(0.0);
+
+                let glyphs = || {
+                    glyph_run.positioned_glyphs().map(|glyph| anyrender::Glyph {
+                        id: glyph.id as _,
+                        x: glyph.x,
+                        y: glyph.y,
+                    })
+                };
+
+                // Draw text shadows beneath the text. The first shadow is painted on top.
+                // Shadows aren't redrawn when repainting the text in a different color (e.g. for selected text).
+                let shadows = match color_override {
+                    Some(_) => &[][..],
+                    None => &itext_styles.text_shadow.0[..],
+                };
+                for shadow in shadows.iter().rev() {
+                    let shadow_color = shadow
+                        .color
+                        .resolve_to_absolute(&itext_styles.color)
+                        .as_color_color();
+                    if shadow_color.components[3] == 0.0 {
+                        continue;
+                    }
+                    let shadow_brush = anyrender::Paint::from(shadow_color);
+                    let offset = Vec2::new(
+                        shadow.horizontal.px() as f64 * scale,
+                        shadow.vertical.px() as f64 * scale,
+                    );
+                    let blur = shadow.blur.px() as f64 * scale;
+
+                    for (sample_offset, alpha) in blur_samples(blur) {
+                        scene.draw_glyphs(
+                            font,
+                            font_size,
+                            options.hinting,
+                            run.normalized_coords(),
+                            Fill::NonZero,
+                            &shadow_brush,
+                            alpha,
+                            transform * Affine::translate(offset + sample_offset),
+                            glyph_xform,
+                            glyphs(),
+                        );
+                    }
+                }
+
                 scene.draw_glyphs(
                     font,
                     font_size,
-                    true, // hint
+                    options.hinting,
                     run.normalized_coords(),
                     Fill::NonZero,
                     &anyrender::Paint::from(text_color),
                     1.0, // alpha
                     transform,
                     glyph_xform,
-                    glyph_run.positioned_glyphs().map(|glyph| anyrender::Glyph {
-                        id: glyph.id as _,
-                        x: glyph.x,
-                        y: glyph.y,
-                    }),
+                    glyphs(),
                 );
 
-                let mut draw_decoration_line =
-                    |offset: f32, size: f32, brush: &anyrender::Paint| {
-                        let x = glyph_run.offset() as f64;
-                        let w = glyph_run.advance() as f64;
-                        let y = (glyph_run.baseline() - offset + size / 2.0) as f64;
-                        let line = kurbo::Line::new((x, y), (x + w, y));
-                        scene.stroke(&Stroke::new(size as f64), transform, brush, None, &line)
-                    };
+                // Thicken the glyphs by additionally stroking their outlines
+                if options.stem_darkening > 0.0 {
+                    scene.draw_glyphs(
+                        font,
+                        font_size,
+                        options.hinting,
+                        run.normalized_coords(),
+                        &Stroke::new(options.stem_darkening as f64),
+                        &anyrender::Paint::from(text_color),
+                        1.0, // alpha
+                        transform,
+                        glyph_xform,
+                        glyphs(),
+                    );
+                }
+
+                let mut draw_decoration_line = |offset: f32, size: f32| {
+                    let size = thickness.unwrap_or(size).max(0.0);
+                    let x = glyph_run.offset() as f64;
+                    let w = glyph_run.advance() as f64;
+                    let y = (glyph_run.baseline() - offset + size / 2.0) as f64;
+                    draw_decoration(
+                        scene,
+                        transform,
+                        (x, x + w),
+                        y,
+                        size as f64,
+                        text_decoration_style,
+                        &text_decoration_brush,
+                    );
+                };
 
                 if has_underline {
-                    let offset = metrics.underline_offset;
+                    let offset = metrics.underline_offset - underline_offset;
                     let size = metrics.underline_size;
 
                     // TODO: intercept line when crossing an descending character like "gqy"
-                    draw_decoration_line(offset, size, &text_decoration_brush);
+                    draw_decoration_line(offset, size);
+                }
+                if has_overline {
+                    let size = metrics.underline_size;
+                    let offset = metrics.ascent + thickness.unwrap_or(size);
+
+                    draw_decoration_line(offset, size);
                 }
                 if has_strikethrough {
                     let offset = metrics.strikethrough_offset;
                     let size = metrics.strikethrough_size;
 
-                    draw_decoration_line(offset, size, &text_decoration_brush);
+                    draw_decoration_line(offset, size);
                 }
             }
         }
     }
 }
 
-/// Draw selection highlight rectangles for the given byte range in a layout.
-/// Uses Parley's Selection type for accurate geometry calculation.
-pub(crate) fn draw_text_selection(
+/// Draw a text decoration line of the given `thickness` centered on `y`, spanning from `x.0` to `x.1`
+fn draw_decoration(
     scene: &mut impl PaintScene,
-    layout: &Layout<TextBrush>,
     transform: Affine,
+    x: (f64, f64),
+    y: f64,
+    thickness: f64,
+    style: TextDecorationStyle,
+    brush: &anyrender::Paint,
+) {
+    if thickness <= 0.0 || x.1 <= x.0 {
+        return;
+    }
+
+    let (x0, x1) = x;
+    let line = |y: f64| kurbo::Line::new((x0, y), (x1, y));
+    match style {
+        TextDecorationStyle::Double => {
+            // Two lines separated by a gap of the same thickness
+            let stroke = Stroke::new(thickness);
+            scene.stroke(&stroke, transform, brush, None, &line(y - thickness));
+            scene.stroke(&stroke, transform, brush, None, &line(y + thickness));
+        }
+        TextDecorationStyle::Dotted => {
+            let stroke = Stroke::new(thickness)
+                .with_caps(Cap::Round)
+                .with_dashes(0.0, [0.0, thickness * 2.0]);
+            scene.stroke(&stroke, transform, brush, None, &line(y));
+        }
+        TextDecorationStyle::Dashed => {
+            let stroke =
+                Stroke::new(thickness).with_dashes(0.0, [thickness * 3.0, thickness * 2.0]);
+            scene.stroke(&stroke, transform, brush, None, &line(y));
+        }
+        TextDecorationStyle::Wavy => {
+            let amplitude = thickness * 1.5;
+            let half_wavelength = thickness * 3.0;
+            let mut path = BezPath::new();
+            path.move_to((x0, y));
+            let mut x = x0;
+            let mut direction = -1.0;
+            while x < x1 {
+                let next_x = x + half_wavelength;
+                path.quad_to(
+                    (x + half_wavelength / 2.0, y + amplitude * 2.0 * direction),
+                    (next_x, y),
+                );
+                x = next_x;
+                direction = -direction;
+            }
+            scene.stroke(&Stroke::new(thickness), transform, brush, None, &path);
+        }
+        // solid (and any other style)
+        _ => {
+            scene.stroke(&Stroke::new(thickness), transform, brush, None, &line(y));
+        }
+    }
+}
+
+/// Build the selection highlight shape for the given byte range in a layout.
+/// Uses Parley's Selection type for accurate geometry calculation.
+///
+/// The rectangles for each line are merged into a single path, so that they can be filled
+/// without overlapping edges being painted twice.
+pub(crate) fn text_selection_path(
+    layout: &Layout<TextBrush>,
     selection_start: usize,
     selection_end: usize,
-) {
+) -> BezPath {
     let anchor = Cursor::from_byte_index(layout, selection_start, Affinity::Downstream);
     let focus = Cursor::from_byte_index(layout, selection_end, Affinity::Downstream);
     let selection = Selection::new(anchor, focus);
 
+    let mut path = BezPath::new();
     selection.geometry_with(layout, |rect, _line_idx| {
         let rect = kurbo::Rect::new(rect.x0, rect.y0, rect.x1, rect.y1);
-        scene.fill(Fill::NonZero, transform, SELECTION_COLOR, None, &rect);
+        path.extend(rect.path_elements(0.1));
     });
+    path
+}
+
+#[test]
+fn blur_samples_scale_with_the_blur_radius() {
+    assert_eq!(blur_samples(0.0), [(Vec2::ZERO, 1.0)]);
+
+    let small = blur_samples(1.0);
+    let medium = blur_samples(6.0);
+    let large = blur_samples(100.0);
+    assert!(small.len() < medium.len());
+    assert!(medium.len() < large.len());
+    assert!(small.len() <= 5);
+    assert_eq!(large.len(), 1 + MAX_BLUR_RINGS * MAX_BLUR_RING_SAMPLES);
+
+    for (blur, samples) in [(1.0, small), (6.0, medium), (100.0, large)] {
+        assert!(
+            samples
+                .iter()
+                .all(|(offset, _)| offset.length() <= blur + 1e-9)
+        );
+        assert!(
+            samples
+                .iter()
+                .all(|(_, alpha)| *alpha > 0.0 && *alpha <= 1.0)
+        );
+    }
 }
@@ -1,23 +1,38 @@
 use crate::event::{BlissShellEvent, BlissShellProxy};
 
-use anyrender::WindowRenderer;
+use anyrender::{ImageRenderer, WindowRenderer};
+use bliss_traits::shell::EditCommand;
 use std::collections::HashMap;
 use std::sync::mpsc::Receiver;
+use std::time::Instant;
 use winit::application::ApplicationHandler;
 use winit::event::WindowEvent;
-use winit::event_loop::ActiveEventLoop;
+use winit::event_loop::{ActiveEventLoop, ControlFlow};
 use winit::window::WindowId;
 
 #[cfg(target_os = "macos")]
 use winit::platform::macos::ApplicationHandlerExtMacOS;
 
-use crate::{View, WindowConfig};
+use crate::screenshot::{ScreenshotError, ScreenshotRenderer, render_screenshot};
+#[cfg(feature = "tray")]
+use crate::tray::TrayState;
+#[cfg(feature = "tray")]
+use crate::{TrayConfig, TrayEvent};
+use crate::{View, WindowConfig, ZoomLevels};
+#[cfg(feature = "tray")]
+use bliss_traits::events::MouseEventButton;
 
 pub struct BlissApplication<Rend: WindowRenderer> {
     pub windows: HashMap<WindowId, View<Rend>>,
     pub pending_windows: Vec<WindowConfig<Rend>>,
     pub proxy: BlissShellProxy,
     pub event_queue: Receiver<BlissShellEvent>,
+    /// The zoom level of each origin, shared by all windows
+    pub zoom_levels: ZoomLevels,
+    screenshot_renderer: Option<ScreenshotRenderer>,
+    renderer_failed: bool,
+    #[cfg(feature = "tray")]
+    tray: TrayState,
 }
 
 impl<Rend: WindowRenderer> BlissApplication<Rend> {
@@ -27,6 +42,11 @@ impl<Rend: WindowRenderer> BlissApplication<Rend> {
             pending_windows: Vec::new(),
             proxy,
             event_queue,
+            zoom_levels: ZoomLevels::new(),
+            screenshot_renderer: None,
+            renderer_failed: false,
+            #[cfg(feature = "tray")]
+            tray: TrayState::new(),
         }
     }
 
@@ -34,6 +54,42 @@ impl<Rend: WindowRenderer> BlissApplication<Rend> {
         self.pending_windows.push(window_config);
     }
 
+    /// Whether a window couldn't be shown because its renderer failed to initialise (e.g. because
+    /// no suitable GPU was found). The event loop exits if this leaves the application without windows.
+    pub fn renderer_failed(&self) -> bool {
+        self.renderer_failed
+    }
+
+    /// Allow windows to be captured with [`BlissShellProxy::capture_screenshot`].
+    ///
+    /// Window renderers can't read back what they have presented, so screenshots are rendered
+    /// separately (from the window's current state) with the image renderer `R`
+    /// (e.g. `anyrender_vello_cpu::VelloCpuImageRenderer`).
+    pub fn enable_screenshots<R: ImageRenderer>(&mut self) {
+        self.screenshot_renderer = Some(render_screenshot::<R>);
+    }
+
+    /// Show (or remove) the system tray icon. Tray icons are only supported on Windows and macOS
+    /// (this does nothing on other platforms).
+    #[cfg(feature = "tray")]
+    pub fn set_tray(&mut self, config: Option<TrayConfig>) {
+        self.tray.set_config(config);
+
+        // The icon can only be created once the event loop has started
+        if !self.windows.is_empty() {
+            self.tray.ensure_created(&self.proxy);
+        }
+    }
+
+    /// Show windows which were hidden to the tray
+    #[cfg(feature = "tray")]
+    fn restore_from_tray(&mut self) {
+        for view in self.windows.values() {
+            view.window.set_visible(true);
+            view.window.focus_window();
+        }
+    }
+
     fn window_mut_by_doc_id(&mut self, doc_id: usize) -> Option<&mut View<Rend>> {
         self.windows.values_mut().find(|w| w.doc.id() == doc_id)
     }
@@ -72,12 +128,70 @@ impl<Rend: WindowRenderer> BlissApplication<Rend> {
                     }
                 }
             }
-            BlissShellEvent::Embedder(_) => {
+            BlissShellEvent::ContextMenuItemSelected { window_id, item_id } => {
+                // Items other than the default edit commands should be handled by embedders (if required)
+                if let Some(command) = EditCommand::from_menu_item_id(&item_id)
+                    && let Some(window) = self.windows.get_mut(&window_id)
+                {
+                    window.apply_edit_command(command);
+                }
+            }
+            BlissShellEvent::CaptureScreenshot { window_id, sender } => {
+                let window = self.windows.get_mut(&window_id);
+                let screenshot = match (window, self.screenshot_renderer) {
+                    (None, _) => Err(ScreenshotError::WindowNotFound),
+                    (Some(_), None) => Err(ScreenshotError::NotEnabled),
+                    (Some(window), Some(renderer)) => Ok(window.capture_screenshot(renderer)),
+                };
+                let _ = sender.send(screenshot);
+            }
+            BlissShellEvent::SetFullscreen { window_id, mode } => {
+                if let Some(window) = self.windows.get_mut(&window_id) {
+                    window.set_fullscreen(mode);
+                }
+            }
+            #[cfg(feature = "tray")]
+            BlissShellEvent::Tray(event) => {
+                // Other tray events should be handled by embedders (if required)
+                let is_left_click = matches!(
+                    event,
+                    TrayEvent::Click {
+                        button: MouseEventButton::Main
+                    }
+                );
+                if is_left_click && self.tray.hide_to_tray() {
+                    self.restore_from_tray();
+                }
+            }
+            BlissShellEvent::SystemPreferences {
+                window_id,
+                preferences,
+            } => {
+                if let Some(window) = self.windows.get_mut(&window_id) {
+                    window.set_system_preferences(preferences);
+                }
+            }
+            BlissShellEvent::ZoomChanged { .. } => {
                 // Do nothing. Should be handled by embedders (if required).
             }
-            BlissShellEvent::Navigate(_opts) => {
+            BlissShellEvent::Embedder(_) => {
                 // Do nothing. Should be handled by embedders (if required).
             }
+            BlissShellEvent::Navigate(options) => {
+                let source = self
+                    .windows
+                    .values()
+                    .find(|window| window.doc.id() == options.source_document);
+                if let Some(window) = source {
+                    let proxy = self.proxy.clone();
+                    window
+                        .doc
+                        .inner()
+                        .fetch_navigation(*options, move |response| {
+                            proxy.send_event(BlissShellEvent::navigation_load(response));
+                        });
+                }
+            }
             BlissShellEvent::NavigationLoad { .. } => {
                 // Do nothing. Should be handled by embedders (if required).
             }
@@ -95,12 +209,21 @@ impl<Rend: WindowRenderer> ApplicationHandler for BlissApplication<Rend> {
         // Initialise pending windows
         for window_config in self.pending_windows.drain(..) {
             let mut view = View::init(window_config, event_loop, &self.proxy);
+            view.set_zoom_levels(self.zoom_levels.clone());
             view.resume();
             if !view.renderer.is_active() {
+                self.renderer_failed = true;
                 continue;
             }
             self.windows.insert(view.window_id(), view);
         }
+        if self.renderer_failed && self.windows.is_empty() {
+            event_loop.exit();
+            return;
+        }
+
+        #[cfg(feature = "tray")]
+        self.tray.ensure_created(&self.proxy);
     }
 
     fn destroy_surfaces(&mut self, _event_loop: &dyn ActiveEventLoop) {
@@ -125,6 +248,19 @@ impl<Rend: WindowRenderer> ApplicationHandler for BlissApplication<Rend> {
     ) {
         // Exit the app when window close is requested.
         if matches!(event, WindowEvent::CloseRequested) {
+            if let Some(view) = self.windows.get(&window_id) {
+                view.save_geometry();
+            }
+
+            // Keep the window (hidden) if it can be restored from the tray
+            #[cfg(feature = "tray")]
+            if self.tray.hide_to_tray() {
+                if let Some(view) = self.windows.get(&window_id) {
+                    view.window.set_visible(false);
+                }
+                return;
+            }
+
             // Drop window before exiting event loop
             // See https://github.com/rust-windowing/winit/issues/4135
             let window = self.windows.remove(&window_id);
@@ -153,13 +289,24 @@ impl<Rend: WindowRenderer> ApplicationHandler for BlissApplication<Rend> {
         Some(self)
     }
 
-    #[cfg(target_os = "ios")]
-    fn about_to_wait(&mut self, _event_loop: &dyn ActiveEventLoop) {
+    fn about_to_wait(&mut self, event_loop: &dyn ActiveEventLoop) {
+        #[cfg(target_os = "ios")]
         for view in self.windows.values_mut() {
             if view.ios_request_redraw.get() {
                 view.window.request_redraw();
             }
         }
+
+        // Sleep until a window next needs to be redrawn (e.g. to blink the caret)
+        let now = Instant::now();
+        for view in self.windows.values_mut() {
+            view.wake_up_if_due(now);
+        }
+        let next_wake_up = self.windows.values().filter_map(View::next_wake_up).min();
+        event_loop.set_control_flow(match next_wake_up {
+            Some(wake_up) => ControlFlow::WaitUntil(wake_up),
+            None => ControlFlow::Wait,
+        });
     }
 }
 
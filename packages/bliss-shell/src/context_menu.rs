@@ -0,0 +1,114 @@
+//! Native context menus using [`muda`]
+
+use bliss_traits::shell::{ContextMenu, ContextMenuItem};
+use muda::dpi::{LogicalPosition, Position};
+use muda::{
+    CheckMenuItem, ContextMenu as _, IsMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem,
+    Submenu,
+};
+use winit::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use winit::window::{Window, WindowId};
+
+use crate::event::{BlissShellEvent, BlissShellProxy};
+#[cfg(feature = "tray")]
+use crate::tray::TrayEvent;
+
+/// Prefix for the ids of items in the tray icon's menu, which distinguishes them from context menu items
+#[cfg(feature = "tray")]
+pub(crate) const TRAY_MENU_ID_PREFIX: &str = "bliss-tray:";
+
+/// Route selected menu items to the event loop. Items which aren't in the tray icon's menu are reported
+/// as belonging to the context menu of `context_menu_window`.
+pub(crate) fn set_menu_event_handler(
+    proxy: BlissShellProxy,
+    context_menu_window: Option<WindowId>,
+) {
+    MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+        #[cfg(feature = "tray")]
+        if let Some(item_id) = event.id.0.strip_prefix(TRAY_MENU_ID_PREFIX) {
+            proxy.send_event(BlissShellEvent::Tray(TrayEvent::MenuItemSelected {
+                item_id: item_id.to_string(),
+            }));
+            return;
+        }
+
+        if let Some(window_id) = context_menu_window {
+            proxy.send_event(BlissShellEvent::ContextMenuItemSelected {
+                window_id,
+                item_id: event.id.0,
+            });
+        }
+    }));
+}
+
+/// Build a native menu, prefixing the id of each item with `id_prefix`
+pub(crate) fn build_menu(menu: &ContextMenu, id_prefix: &str) -> Menu {
+    let native_menu = Menu::new();
+    for item in &menu.items {
+        let _ = native_menu.append(&*build_item(item, id_prefix));
+    }
+    native_menu
+}
+
+fn build_item(item: &ContextMenuItem, id_prefix: &str) -> Box<dyn IsMenuItem> {
+    match item {
+        ContextMenuItem::Action {
+            id,
+            label,
+            enabled,
+            checked: None,
+        } => Box::new(MenuItem::with_id(
+            format!("{id_prefix}{id}"),
+            label,
+            *enabled,
+            None,
+        )),
+        ContextMenuItem::Action {
+            id,
+            label,
+            enabled,
+            checked: Some(checked),
+        } => Box::new(CheckMenuItem::with_id(
+            format!("{id_prefix}{id}"),
+            label,
+            *enabled,
+            *checked,
+            None,
+        )),
+        ContextMenuItem::Separator => Box::new(PredefinedMenuItem::separator()),
+        ContextMenuItem::Submenu {
+            label,
+            enabled,
+            items,
+        } => {
+            let submenu = Submenu::new(label, *enabled);
+            for item in items {
+                let _ = submenu.append(&*build_item(item, id_prefix));
+            }
+            Box::new(submenu)
+        }
+    }
+}
+
+/// Show a context menu at (`x`, `y`) in logical pixels relative to the window's content area.
+/// Blocks until the menu is closed.
+pub(crate) fn show_context_menu(window: &dyn Window, menu: &ContextMenu, x: f32, y: f32) {
+    let native_menu = build_menu(menu, "");
+    let position = Some(Position::Logical(LogicalPosition::new(x as f64, y as f64)));
+    let Ok(handle) = window.window_handle() else {
+        return;
+    };
+
+    // SAFETY: the handle refers to the live window which the menu is shown for
+    match handle.as_raw() {
+        #[cfg(target_os = "windows")]
+        RawWindowHandle::Win32(handle) => unsafe {
+            native_menu.show_context_menu_for_hwnd(handle.hwnd.get(), position);
+        },
+        #[cfg(target_os = "macos")]
+        RawWindowHandle::AppKit(handle) => unsafe {
+            native_menu.show_context_menu_for_nsview(handle.ns_view.as_ptr() as _, position);
+        },
+        _ => {}
+    }
+}
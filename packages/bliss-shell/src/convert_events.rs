@@ -1,7 +1,7 @@
 use bliss_traits::events::{
     BlissImeEvent, BlissKeyEvent, BlissPointerId, KeyState, PointerDetails,
 };
-use bliss_traits::shell::ColorScheme;
+use bliss_traits::shell::{ColorScheme, FullscreenMode};
 use keyboard_types::{Code, Key, Location, Modifiers};
 use winit::event::KeyEvent as WinitKeyEvent;
 use winit::event::{ButtonSource, ElementState};
@@ -12,7 +12,8 @@ use winit::keyboard::KeyLocation as WinitKeyLocation;
 use winit::keyboard::ModifiersState as WinitModifiers;
 use winit::keyboard::NamedKey as WinitNamedKey;
 use winit::keyboard::PhysicalKey as WinitPhysicalKey;
-use winit::window::Theme;
+use winit::monitor::Fullscreen;
+use winit::window::{Theme, Window};
 
 pub(crate) fn theme_to_color_scheme(theme: Theme) -> ColorScheme {
     match theme {
@@ -28,6 +29,38 @@ pub(crate) fn color_scheme_to_theme(scheme: ColorScheme) -> Theme {
     }
 }
 
+pub(crate) fn winit_fullscreen_to_mode(fullscreen: Option<Fullscreen>) -> FullscreenMode {
+    match fullscreen {
+        None => FullscreenMode::Windowed,
+        Some(Fullscreen::Borderless(_)) => FullscreenMode::Borderless,
+        Some(Fullscreen::Exclusive(..)) => FullscreenMode::Exclusive,
+    }
+}
+
+/// Exclusive fullscreen uses the current video mode of the window's monitor, falling back to
+/// borderless fullscreen if that isn't known
+pub(crate) fn fullscreen_mode_to_winit(
+    window: &dyn Window,
+    mode: FullscreenMode,
+) -> Option<Fullscreen> {
+    match mode {
+        FullscreenMode::Windowed => None,
+        FullscreenMode::Borderless => Some(Fullscreen::Borderless(None)),
+        FullscreenMode::Exclusive => {
+            let monitor = window.current_monitor();
+            let video_mode = monitor
+                .as_ref()
+                .and_then(|monitor| monitor.current_video_mode());
+            match (monitor, video_mode) {
+                (Some(monitor), Some(video_mode)) => {
+                    Some(Fullscreen::Exclusive(monitor, video_mode))
+                }
+                _ => Some(Fullscreen::Borderless(None)),
+            }
+        }
+    }
+}
+
 pub(crate) fn winit_ime_to_bliss(event: Ime) -> BlissImeEvent {
     match event {
         Ime::Enabled => BlissImeEvent::Enabled,
@@ -1,5 +1,6 @@
-use bliss_traits::navigation::NavigationOptions;
-use bliss_traits::net::NetWaker;
+use bliss_traits::navigation::{NavigationOptions, NavigationProvider, NavigationResponse};
+use bliss_traits::net::{NetWaker, http::header};
+use bliss_traits::shell::{FullscreenMode, SystemPreferences};
 use futures_util::task::ArcWake;
 use std::sync::mpsc::{Receiver, Sender, channel};
 use std::{any::Any, sync::Arc};
@@ -8,6 +9,10 @@ use winit::{event_loop::EventLoopProxy, window::WindowId};
 #[cfg(feature = "accessibility")]
 use accesskit_xplat::WindowEvent as AccessKitEvent;
 
+use crate::screenshot::{Screenshot, ScreenshotError};
+#[cfg(feature = "tray")]
+use crate::tray::TrayEvent;
+
 #[derive(Debug, Clone)]
 pub enum BlissShellEvent {
     Poll {
@@ -25,10 +30,48 @@ pub enum BlissShellEvent {
         data: Arc<AccessKitEvent>,
     },
 
+    /// An item in a context menu shown by [`ShellProvider::show_context_menu`](bliss_traits::shell::ShellProvider::show_context_menu)
+    /// was selected
+    ContextMenuItemSelected {
+        window_id: WindowId,
+        item_id: String,
+    },
+
+    /// Capture the content of a window (see [`BlissShellProxy::capture_screenshot`])
+    CaptureScreenshot {
+        window_id: WindowId,
+        sender: Sender<Result<Screenshot, ScreenshotError>>,
+    },
+
+    /// Enter or leave fullscreen (e.g. from the embedder's UI)
+    SetFullscreen {
+        window_id: WindowId,
+        mode: FullscreenMode,
+    },
+
+    /// The operating system's accessibility settings were read for a window. They are read in the
+    /// background when the window is created and whenever it is focused.
+    SystemPreferences {
+        window_id: WindowId,
+        preferences: SystemPreferences,
+    },
+
+    /// The zoom level of a window changed (e.g. using Ctrl+= / Ctrl+- or Ctrl+wheel)
+    ZoomChanged {
+        window_id: WindowId,
+        zoom: f32,
+    },
+
+    /// An interaction with the system tray icon
+    #[cfg(feature = "tray")]
+    Tray(TrayEvent),
+
     /// An arbitary event from the Bliss embedder
     Embedder(Arc<dyn Any + Send + Sync>),
 
-    /// Navigate to another URL (triggered by e.g. clicking a link)
+    /// Navigate to another URL (triggered by e.g. clicking a link). The target is fetched by the
+    /// source document: responses which should be downloaded are handed to its download handler,
+    /// and other responses are sent back as [`NavigationLoad`](Self::NavigationLoad) events.
     Navigate(Box<NavigationOptions>),
 
     /// Navigate to another URL (triggered by e.g. clicking a link)
@@ -44,17 +87,42 @@ impl BlissShellEvent {
         let boxed = Arc::new(value) as Arc<dyn Any + Send + Sync>;
         Self::Embedder(boxed)
     }
+
+    /// A [`NavigationLoad`](Self::NavigationLoad) event for a fetched navigation response
+    pub(crate) fn navigation_load(response: NavigationResponse) -> Self {
+        let is_md = response
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|content_type| content_type.starts_with("text/markdown"));
+        Self::NavigationLoad {
+            url: response.url.to_string(),
+            contents: String::from_utf8_lossy(&response.body).into_owned(),
+            retain_scroll_position: false,
+            is_md,
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct BlissShellProxy(Arc<BlissShellProxyInner>);
 pub struct BlissShellProxyInner {
-    winit_proxy: EventLoopProxy,
+    /// `None` for proxies of a [`HeadlessDocumentLoop`](crate::HeadlessDocumentLoop), which is driven by the embedder
+    winit_proxy: Option<EventLoopProxy>,
     sender: Sender<BlissShellEvent>,
 }
 
 impl BlissShellProxy {
     pub fn new(winit_proxy: EventLoopProxy) -> (Self, Receiver<BlissShellEvent>) {
+        Self::with_winit_proxy(Some(winit_proxy))
+    }
+
+    /// Create a proxy which isn't connected to a winit event loop
+    pub fn new_headless() -> (Self, Receiver<BlissShellEvent>) {
+        Self::with_winit_proxy(None)
+    }
+
+    fn with_winit_proxy(winit_proxy: Option<EventLoopProxy>) -> (Self, Receiver<BlissShellEvent>) {
         let (sender, receiver) = channel();
         let proxy = Self(Arc::new(BlissShellProxyInner {
             winit_proxy,
@@ -64,8 +132,24 @@ impl BlissShellProxy {
     }
 
     pub fn wake_up(&self) {
-        self.0.winit_proxy.wake_up();
+        if let Some(winit_proxy) = &self.0.winit_proxy {
+            winit_proxy.wake_up();
+        }
+    }
+    /// Capture the current content of a window. The screenshot (or the reason it couldn't be taken)
+    /// is sent to the returned receiver once the event loop has handled the request.
+    ///
+    /// The window is re-rendered on the CPU rather than read back from the GPU, so the screenshot
+    /// may differ slightly from what is on screen (see [`Screenshot`]).
+    pub fn capture_screenshot(
+        &self,
+        window_id: WindowId,
+    ) -> Receiver<Result<Screenshot, ScreenshotError>> {
+        let (sender, receiver) = channel();
+        self.send_event(BlissShellEvent::CaptureScreenshot { window_id, sender });
+        receiver
     }
+
     pub fn send_event(&self, event: impl Into<BlissShellEvent>) {
         self.send_event_impl(event.into());
     }
@@ -75,6 +159,12 @@ impl BlissShellProxy {
     }
 }
 
+impl NavigationProvider for BlissShellProxy {
+    fn navigate_to(&self, options: NavigationOptions) {
+        self.send_event_impl(BlissShellEvent::Navigate(Box::new(options)))
+    }
+}
+
 impl NetWaker for BlissShellProxy {
     fn wake(&self, client_id: usize) {
         self.send_event_impl(BlissShellEvent::RequestRedraw { doc_id: client_id })
@@ -0,0 +1,121 @@
+//! Per-frame timing statistics
+
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::time::{Duration, Instant};
+
+use winit::window::Window;
+
+/// Timing information about a frame rendered by a [`View`](crate::View)
+#[derive(Debug, Clone, Copy)]
+pub struct FrameStats {
+    /// The number of frames the window had rendered before this one
+    pub frame_number: u64,
+    /// When work on the frame started
+    pub frame_start: Instant,
+    /// Time spent resolving styles and layout
+    pub resolve_time: Duration,
+    /// Time spent painting the document into a scene (on the CPU)
+    pub scene_build_time: Duration,
+    /// Time spent by the renderer rasterizing and presenting the scene. For GPU renderers this is
+    /// the time taken to submit work to the GPU (and wait for a surface to present to), which may
+    /// be less than the time the GPU spends rendering.
+    pub render_time: Duration,
+    /// When the frame was handed to the platform for presentation
+    pub present_time: Instant,
+    /// The interval between vsyncs of the window's monitor (if known)
+    pub refresh_interval: Option<Duration>,
+    /// The number of vsyncs which were missed between this frame and the previous frame while
+    /// the document was animating. Always zero if the refresh rate isn't known.
+    pub dropped_frames: u32,
+}
+
+impl FrameStats {
+    /// The total time taken to produce the frame
+    pub fn frame_time(&self) -> Duration {
+        self.present_time.duration_since(self.frame_start)
+    }
+
+    /// Whether the frame took longer than one refresh interval to produce
+    pub fn is_janky(&self) -> bool {
+        self.dropped_frames > 0
+            || self
+                .refresh_interval
+                .is_some_and(|interval| self.frame_time() > interval)
+    }
+}
+
+/// Collects [`FrameStats`] for a window and sends them to subscribers
+#[derive(Default)]
+pub(crate) struct FrameStatsRecorder {
+    subscribers: Vec<Sender<FrameStats>>,
+    frame_count: u64,
+    /// When the previous frame was presented, if the document was animating at the time
+    last_animating_present: Option<Instant>,
+}
+
+impl FrameStatsRecorder {
+    pub(crate) fn subscribe(&mut self) -> Receiver<FrameStats> {
+        let (sender, receiver) = channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// Whether anyone is listening for stats (so timings need to be measured)
+    pub(crate) fn is_active(&self) -> bool {
+        !self.subscribers.is_empty()
+    }
+
+    /// Record a frame, sending its stats to each subscriber
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn record(
+        &mut self,
+        window: &dyn Window,
+        frame_start: Instant,
+        resolve_time: Duration,
+        scene_build_time: Duration,
+        render_time: Duration,
+        present_time: Instant,
+        is_animating: bool,
+    ) {
+        let frame_number = self.frame_count;
+        self.frame_count += 1;
+
+        if !self.is_active() {
+            return;
+        }
+
+        let refresh_interval = refresh_interval(window);
+        let dropped_frames = match (self.last_animating_present, refresh_interval) {
+            (Some(last_present), Some(interval)) => {
+                let vsyncs = present_time.duration_since(last_present).as_secs_f64()
+                    / interval.as_secs_f64();
+                (vsyncs.round() as u32).saturating_sub(1)
+            }
+            _ => 0,
+        };
+        self.last_animating_present = is_animating.then_some(present_time);
+
+        let stats = FrameStats {
+            frame_number,
+            frame_start,
+            resolve_time,
+            scene_build_time,
+            render_time,
+            present_time,
+            refresh_interval,
+            dropped_frames,
+        };
+
+        // Drop subscribers whose receiver has been dropped
+        self.subscribers
+            .retain(|subscriber| subscriber.send(stats).is_ok());
+    }
+}
+
+fn refresh_interval(window: &dyn Window) -> Option<Duration> {
+    let millihertz = window
+        .current_monitor()?
+        .current_video_mode()?
+        .refresh_rate_millihertz()?;
+    Some(Duration::from_secs_f64(1000.0 / millihertz.get() as f64))
+}
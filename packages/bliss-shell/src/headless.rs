@@ -0,0 +1,357 @@
+use crate::TextRenderingOptions;
+use crate::event::{BlissShellEvent, BlissShellProxy};
+use crate::screenshot::ScreenshotError;
+
+use anyrender::{ImageRenderer, render_to_buffer};
+use bliss_dom::Document;
+use bliss_paint::paint_scene_with_options;
+use bliss_traits::events::UiEvent;
+use bliss_traits::shell::{ColorScheme, Viewport};
+use futures_util::task::ArcWake;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use std::task::Waker;
+use std::time::Duration;
+
+/// The maximum number of rounds of polling which [`HeadlessDocumentLoop::run_until_idle`] will run
+/// before giving up on documents which never become idle (e.g. scripts which schedule work forever).
+const MAX_IDLE_ROUNDS: usize = 1000;
+
+/// A document hosted by a [`HeadlessDocumentLoop`] in place of a window
+pub struct HeadlessView {
+    pub doc: Box<dyn Document>,
+    pub text_rendering: TextRenderingOptions,
+    /// Whether the document has changed since it was last rendered
+    pub needs_redraw: bool,
+    waker: Waker,
+}
+
+/// A loop which runs documents without a display server, for use in CI and on servers.
+///
+/// This hosts [`Document`]s directly rather than running a
+/// [`BlissApplication`](crate::BlissApplication): there are no windows, so [`View`](crate::View)
+/// and the winit event loop aren't involved, and window-level behaviour (keyboard shortcuts, zoom,
+/// menus, accessibility, system preferences and screenshots) isn't available. Navigations are
+/// fetched by their source document, and events which only an embedder can handle (navigation
+/// loads, embedder events and tray events) are queued for
+/// [`take_unhandled_events`](Self::take_unhandled_events).
+///
+/// Rather than blocking on OS events, the loop is driven by the embedder: synthetic [`UiEvent`]s are
+/// delivered with [`send_ui_event`](Self::send_ui_event), time is advanced explicitly with
+/// [`advance_time`](Self::advance_time) (so that animations and timers are deterministic), and each
+/// document can be rendered to an offscreen buffer with [`render`](Self::render).
+pub struct HeadlessDocumentLoop {
+    pub views: HashMap<usize, HeadlessView>,
+    proxy: BlissShellProxy,
+    event_queue: Receiver<BlissShellEvent>,
+    /// The current time on the loop's clock
+    now: Duration,
+    /// Events waiting to be delivered at a time on the loop's clock
+    timers: Vec<(Duration, BlissShellEvent)>,
+    /// Events which the loop doesn't handle itself, waiting to be taken by the embedder
+    unhandled_events: Vec<BlissShellEvent>,
+}
+
+impl Default for HeadlessDocumentLoop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeadlessDocumentLoop {
+    pub fn new() -> Self {
+        let (proxy, event_queue) = BlissShellProxy::new_headless();
+        Self {
+            views: HashMap::new(),
+            proxy,
+            event_queue,
+            now: Duration::ZERO,
+            timers: Vec::new(),
+            unhandled_events: Vec::new(),
+        }
+    }
+
+    /// A proxy for sending events to this event loop (e.g. to use as a document's `NetWaker`)
+    pub fn proxy(&self) -> BlissShellProxy {
+        self.proxy.clone()
+    }
+
+    /// The current time on the loop's clock (starting from zero)
+    pub fn current_time(&self) -> Duration {
+        self.now
+    }
+
+    /// Add a document with a viewport of `width` x `height` physical pixels.
+    /// Returns the id of the document, which identifies the view in other methods.
+    pub fn add_document(
+        &mut self,
+        doc: Box<dyn Document>,
+        width: u32,
+        height: u32,
+        scale: f32,
+    ) -> usize {
+        let doc_id = doc.id();
+        let mut view = HeadlessView {
+            doc,
+            text_rendering: TextRenderingOptions::default(),
+            needs_redraw: true,
+            waker: create_headless_waker(&self.proxy, doc_id),
+        };
+
+        let viewport = Viewport::new(width, height, scale, ColorScheme::Light);
+        view.doc.inner_mut().set_viewport(viewport);
+
+        self.views.insert(doc_id, view);
+        doc_id
+    }
+
+    pub fn remove_document(&mut self, doc_id: usize) -> Option<Box<dyn Document>> {
+        self.views.remove(&doc_id).map(|view| view.doc)
+    }
+
+    /// Change the size (in physical pixels) of a document's viewport
+    pub fn resize(&mut self, doc_id: usize, width: u32, height: u32) {
+        if let Some(view) = self.views.get_mut(&doc_id) {
+            view.doc.inner_mut().viewport_mut().window_size = (width, height);
+            view.needs_redraw = true;
+        }
+    }
+
+    /// Deliver a synthetic input event to a document
+    pub fn send_ui_event(&mut self, doc_id: usize, event: UiEvent) {
+        if let Some(view) = self.views.get_mut(&doc_id) {
+            let animation_time = self.now.as_secs_f64();
+            view.doc.inner_mut().resolve(animation_time);
+            view.doc.handle_ui_event(event);
+            view.needs_redraw = true;
+        }
+    }
+
+    /// Deliver `event` to this loop once its clock has advanced by `delay`
+    pub fn schedule_event(&mut self, delay: Duration, event: BlissShellEvent) {
+        self.timers.push((self.now + delay, event));
+    }
+
+    /// Advance the loop's clock, delivering any scheduled events which become due (in order)
+    /// and running the loop until it's idle.
+    pub fn advance_time(&mut self, duration: Duration) {
+        let target = self.now + duration;
+        loop {
+            let next_due = self
+                .timers
+                .iter()
+                .enumerate()
+                .filter(|(_, (due, _))| *due <= target)
+                .min_by_key(|(_, (due, _))| *due)
+                .map(|(idx, _)| idx);
+            let Some(idx) = next_due else {
+                break;
+            };
+
+            let (due, event) = self.timers.remove(idx);
+            self.now = self.now.max(due);
+            self.handle_bliss_shell_event(event);
+            self.run_until_idle();
+        }
+
+        self.now = target;
+        self.run_until_idle();
+    }
+
+    /// Process pending events and poll documents until there is no more work to do.
+    /// Returns `true` if any document changed.
+    pub fn run_until_idle(&mut self) -> bool {
+        let mut changed = false;
+        for _ in 0..MAX_IDLE_ROUNDS {
+            let mut did_work = false;
+            while let Ok(event) = self.event_queue.try_recv() {
+                self.handle_bliss_shell_event(event);
+                did_work = true;
+            }
+
+            for view in self.views.values_mut() {
+                if view.poll() {
+                    did_work = true;
+                }
+            }
+
+            if !did_work {
+                break;
+            }
+            changed = true;
+        }
+
+        changed
+    }
+
+    fn handle_bliss_shell_event(&mut self, event: BlissShellEvent) {
+        match event {
+            BlissShellEvent::RequestRedraw { doc_id } => {
+                if let Some(view) = self.views.get_mut(&doc_id) {
+                    // Resolving processes any resources which have finished loading
+                    view.doc.inner_mut().resolve(self.now.as_secs_f64());
+                    view.poll();
+                    view.needs_redraw = true;
+                }
+            }
+            BlissShellEvent::Poll { .. } => {
+                // Headless documents are woken by doc id (see `create_headless_waker`)
+            }
+            #[cfg(feature = "accessibility")]
+            BlissShellEvent::Accessibility { .. } => {}
+            BlissShellEvent::CaptureScreenshot { sender, .. } => {
+                // Headless documents don't have a window. They can be captured with `render`.
+                let _ = sender.send(Err(ScreenshotError::Headless));
+            }
+            BlissShellEvent::SetFullscreen { .. } | BlissShellEvent::SystemPreferences { .. } => {
+                // Headless documents don't have a window
+            }
+            #[cfg(feature = "tray")]
+            BlissShellEvent::Tray(_) => {
+                // Should be handled by embedders (if required)
+                self.unhandled_events.push(event);
+            }
+            BlissShellEvent::Navigate(options) => {
+                if let Some(view) = self.views.get(&options.source_document) {
+                    let proxy = self.proxy.clone();
+                    view.doc
+                        .inner()
+                        .fetch_navigation(*options, move |response| {
+                            proxy.send_event(BlissShellEvent::navigation_load(response));
+                        });
+                }
+            }
+            BlissShellEvent::ContextMenuItemSelected { .. }
+            | BlissShellEvent::ZoomChanged { .. }
+            | BlissShellEvent::Embedder(_)
+            | BlissShellEvent::NavigationLoad { .. } => {
+                // Should be handled by embedders (if required)
+                self.unhandled_events.push(event);
+            }
+        }
+    }
+
+    /// Take the events (embedder events and navigation loads) which have been delivered to the loop
+    /// but which it doesn't handle itself
+    pub fn take_unhandled_events(&mut self) -> Vec<BlissShellEvent> {
+        std::mem::take(&mut self.unhandled_events)
+    }
+
+    /// Resolve and render a document into an RGBA8 buffer the size of its viewport
+    /// using the image renderer `R` (e.g. `anyrender_vello_cpu::VelloCpuImageRenderer`)
+    pub fn render<R: ImageRenderer>(&mut self, doc_id: usize) -> Option<Vec<u8>> {
+        let view = self.views.get_mut(&doc_id)?;
+        let animation_time = self.now.as_secs_f64();
+
+        let mut inner = view.doc.inner_mut();
+        inner.resolve(animation_time);
+
+        let (width, height) = inner.viewport().window_size;
+        let scale = inner.viewport().scale_f64();
+        let text_rendering = view.text_rendering;
+        let buffer = render_to_buffer::<R, _>(
+            |scene| {
+                paint_scene_with_options(scene, &inner, scale, width, height, 0, 0, text_rendering)
+            },
+            width,
+            height,
+        );
+        drop(inner);
+
+        view.needs_redraw = false;
+        Some(buffer)
+    }
+}
+
+impl HeadlessView {
+    /// Poll the document and its script engine. Returns `true` if there was any work to do.
+    fn poll(&mut self) -> bool {
+        let cx = std::task::Context::from_waker(&self.waker);
+        let mut did_work = self.doc.poll(Some(cx));
+
+        let mut inner = self.doc.inner_mut();
+        match inner.poll_script_engine() {
+            Ok(true) => did_work = true,
+            Ok(false) => {}
+            Err(e) => {
+                // Report error
+                eprintln!("Script engine error: {:?}", e);
+            }
+        }
+        drop(inner);
+
+        if did_work {
+            self.needs_redraw = true;
+        }
+        did_work
+    }
+}
+
+/// Create a waker that will send a redraw request for a headless document to the event loop
+fn create_headless_waker(proxy: &BlissShellProxy, doc_id: usize) -> Waker {
+    struct DocHandle {
+        proxy: BlissShellProxy,
+        doc_id: usize,
+    }
+    impl ArcWake for DocHandle {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            let event = BlissShellEvent::RequestRedraw {
+                doc_id: arc_self.doc_id,
+            };
+            arc_self.proxy.send_event(event)
+        }
+    }
+
+    let proxy = proxy.clone();
+    futures_util::task::waker(Arc::new(DocHandle { proxy, doc_id }))
+}
+
+#[test]
+fn advance_time_delivers_scheduled_events_in_order() {
+    let mut event_loop = HeadlessDocumentLoop::new();
+    event_loop.schedule_event(
+        Duration::from_millis(30),
+        BlissShellEvent::embedder_event(3u32),
+    );
+    event_loop.schedule_event(
+        Duration::from_millis(10),
+        BlissShellEvent::embedder_event(1u32),
+    );
+    event_loop.schedule_event(
+        Duration::from_millis(20),
+        BlissShellEvent::embedder_event(2u32),
+    );
+    event_loop.schedule_event(
+        Duration::from_millis(50),
+        BlissShellEvent::embedder_event(5u32),
+    );
+
+    event_loop.advance_time(Duration::from_millis(40));
+    assert_eq!(event_loop.current_time(), Duration::from_millis(40));
+    let delivered: Vec<u32> = event_loop
+        .take_unhandled_events()
+        .into_iter()
+        .map(|event| match event {
+            BlissShellEvent::Embedder(value) => *value.downcast_ref::<u32>().unwrap(),
+            _ => panic!("unexpected event"),
+        })
+        .collect();
+    assert_eq!(delivered, [1, 2, 3]);
+
+    event_loop.advance_time(Duration::from_millis(10));
+    assert_eq!(event_loop.take_unhandled_events().len(), 1);
+}
+
+#[test]
+fn run_until_idle_drains_the_event_queue() {
+    let mut event_loop = HeadlessDocumentLoop::new();
+    assert!(!event_loop.run_until_idle());
+
+    let proxy = event_loop.proxy();
+    proxy.send_event(BlissShellEvent::embedder_event(()));
+    proxy.send_event(BlissShellEvent::embedder_event(()));
+    assert!(event_loop.run_until_idle());
+    assert_eq!(event_loop.take_unhandled_events().len(), 2);
+    assert!(!event_loop.run_until_idle());
+}
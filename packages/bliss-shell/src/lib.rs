@@ -7,19 +7,43 @@
 //!  - `accessibility`: Enables [`accesskit`] accessibility support.
 //!  - `hot-reload`: Enables hot-reloading of Dioxus RSX.
 //!  - `tracing`: Enables tracing support.
+//!  - `context_menu`: Enables native context menus (on Windows and macOS).
+//!  - `tray`: Enables a system tray icon (on Windows and macOS).
+//!  - `taskbar_progress`: Enables progress indication on the window's taskbar button (on Windows).
 
 mod application;
+#[cfg(all(feature = "context_menu", any(target_os = "windows", target_os = "macos")))]
+mod context_menu;
 mod convert_events;
 mod event;
+mod frame_stats;
+mod headless;
 mod net;
+mod screenshot;
+mod system_preferences;
+#[cfg(all(feature = "taskbar_progress", target_os = "windows"))]
+mod taskbar;
+#[cfg(feature = "tray")]
+mod tray;
 mod window;
+mod window_geometry;
+mod zoom;
 
 #[cfg(feature = "accessibility")]
 mod accessibility;
 
 pub use crate::application::BlissApplication;
 pub use crate::event::{BlissShellEvent, BlissShellProxy};
+pub use crate::frame_stats::FrameStats;
+pub use crate::headless::{HeadlessDocumentLoop, HeadlessView};
+pub use crate::screenshot::{Screenshot, ScreenshotError};
+pub use crate::system_preferences::detect_system_preferences;
+#[cfg(feature = "tray")]
+pub use crate::tray::{TrayConfig, TrayEvent};
 pub use crate::window::{View, WindowConfig};
+pub use crate::window_geometry::{WindowGeometry, WindowGeometryStore};
+pub use crate::zoom::ZoomLevels;
+pub use bliss_paint::TextRenderingOptions;
 
 #[cfg(feature = "data-uri")]
 pub use crate::net::DataUriNetProvider;
@@ -37,10 +61,13 @@ pub use crate::net::DataUriNetProvider;
     )
 ))]
 use bliss_traits::shell::FileDialogFilter;
-use bliss_traits::shell::ShellProvider;
+#[cfg(all(feature = "context_menu", any(target_os = "windows", target_os = "macos")))]
+use bliss_traits::shell::ContextMenu;
+use bliss_traits::shell::{FullscreenMode, ShellProvider, WindowIcon};
 use std::sync::Arc;
 use winit::cursor::{Cursor, CursorIcon};
 use winit::dpi::{LogicalPosition, LogicalSize};
+use winit::error::EventLoopError;
 pub use winit::event_loop::{ControlFlow, EventLoop, EventLoopProxy};
 pub use winit::window::Window;
 use winit::window::{ImeCapabilities, ImeEnableRequest, ImeRequest, ImeRequestData};
@@ -49,10 +76,18 @@ use winit::window::{ImeCapabilities, ImeEnableRequest, ImeRequest, ImeRequestDat
 pub struct Config {
     pub stylesheets: Vec<String>,
     pub base_url: Option<String>,
+    /// Where to save and restore the window's position and size (see [`WindowConfig::with_saved_geometry`])
+    pub window_geometry: Option<WindowGeometryStore>,
 }
 
 /// Build an event loop for the application
 pub fn create_default_event_loop() -> EventLoop {
+    try_create_default_event_loop().unwrap()
+}
+
+/// Build an event loop for the application, returning an error if the platform's event loop
+/// couldn't be created (e.g. because there is no display server to connect to)
+pub fn try_create_default_event_loop() -> Result<EventLoop, EventLoopError> {
     let mut ev_builder = EventLoop::builder();
     #[cfg(target_os = "android")]
     {
@@ -60,10 +95,16 @@ pub fn create_default_event_loop() -> EventLoop {
         ev_builder.with_android_app(current_android_app());
     }
 
-    let event_loop = ev_builder.build().unwrap();
+    let event_loop = ev_builder.build()?;
     event_loop.set_control_flow(ControlFlow::Wait);
 
-    event_loop
+    Ok(event_loop)
+}
+
+/// Build a loop which runs documents without a display server (e.g. for CI or server-side
+/// rendering). See [`HeadlessDocumentLoop`].
+pub fn create_headless_document_loop() -> HeadlessDocumentLoop {
+    HeadlessDocumentLoop::new()
 }
 
 #[cfg(target_os = "android")]
@@ -86,10 +127,15 @@ pub fn current_android_app() -> android_activity::AndroidApp {
 
 pub struct BlissShellProvider {
     window: Arc<dyn Window>,
+    #[cfg_attr(
+        not(all(feature = "context_menu", any(target_os = "windows", target_os = "macos"))),
+        allow(dead_code)
+    )]
+    proxy: BlissShellProxy,
 }
 impl BlissShellProvider {
-    pub fn new(window: Arc<dyn Window>) -> Self {
-        Self { window }
+    pub fn new(window: Arc<dyn Window>, proxy: BlissShellProxy) -> Self {
+        Self { window, proxy }
     }
 }
 
@@ -103,6 +149,20 @@ impl ShellProvider for BlissShellProvider {
     fn set_window_title(&self, title: String) {
         self.window.set_title(&title);
     }
+    fn set_window_icon(&self, icon: Option<WindowIcon>) {
+        self.window.set_window_icon(icon.and_then(window::to_winit_icon));
+    }
+    fn set_fullscreen(&self, mode: FullscreenMode) {
+        let fullscreen = convert_events::fullscreen_mode_to_winit(&*self.window, mode);
+        self.window.set_fullscreen(fullscreen);
+    }
+    fn fullscreen_mode(&self) -> FullscreenMode {
+        convert_events::winit_fullscreen_to_mode(self.window.fullscreen())
+    }
+    #[cfg(all(feature = "taskbar_progress", target_os = "windows"))]
+    fn set_progress(&self, progress: Option<f32>) {
+        taskbar::set_taskbar_progress(&*self.window, progress);
+    }
     fn set_ime_enabled(&self, is_enabled: bool) {
         if is_enabled {
             let _ = self.window.request_ime_update(ImeRequest::Enable(
@@ -185,4 +245,10 @@ impl ShellProvider for BlissShellProvider {
         };
         files.unwrap_or_default()
     }
+
+    #[cfg(all(feature = "context_menu", any(target_os = "windows", target_os = "macos")))]
+    fn show_context_menu(&self, menu: ContextMenu, x: f32, y: f32) {
+        context_menu::set_menu_event_handler(self.proxy.clone(), Some(self.window.id()));
+        context_menu::show_context_menu(&*self.window, &menu, x, y);
+    }
 }
@@ -0,0 +1,86 @@
+//! Capturing the contents of windows
+
+use anyrender::{ImageRenderer, render_to_buffer};
+use bliss_dom::BaseDocument;
+use bliss_paint::{TextRenderingOptions, paint_scene_with_options};
+
+/// An image of a window's content
+///
+/// Window renderers present directly to the window's surface and can't read back what they have
+/// drawn, so screenshots aren't a GPU readback of the framebuffer. Instead the document is
+/// re-rendered from its current state on the CPU with the image renderer passed to
+/// [`BlissApplication::enable_screenshots`](crate::BlissApplication::enable_screenshots). As a
+/// result a screenshot may differ from what is on screen:
+///
+/// - anti-aliasing and colour blending follow the CPU renderer rather than the window's renderer
+/// - content which only the window's renderer can draw (e.g. custom paint sources such as WebGPU
+///   canvases) is missing
+/// - window decorations and anything behind a transparent window aren't included
+///
+/// Excluding security-gated regions (e.g. password fields) from screenshots isn't supported.
+#[derive(Debug, Clone)]
+pub struct Screenshot {
+    pub width: u32,
+    pub height: u32,
+    /// The pixels of the image in RGBA8 format
+    pub rgba: Vec<u8>,
+}
+
+impl Screenshot {
+    /// Encode the screenshot as a PNG file
+    pub fn encode_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.rgba)?;
+        writer.finish()?;
+        Ok(out)
+    }
+}
+
+/// Why a window couldn't be captured with
+/// [`BlissShellProxy::capture_screenshot`](crate::BlissShellProxy::capture_screenshot)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenshotError {
+    /// The application hasn't enabled screenshots with
+    /// [`BlissApplication::enable_screenshots`](crate::BlissApplication::enable_screenshots)
+    NotEnabled,
+    /// The request was sent to a [`HeadlessDocumentLoop`](crate::HeadlessDocumentLoop), which has no
+    /// windows (documents can be captured with its `render` method instead)
+    Headless,
+    /// There is no window with the requested id
+    WindowNotFound,
+}
+
+impl std::fmt::Display for ScreenshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotEnabled => write!(f, "screenshots are not enabled"),
+            Self::Headless => write!(f, "headless documents have no windows to capture"),
+            Self::WindowNotFound => write!(f, "window not found"),
+        }
+    }
+}
+
+impl std::error::Error for ScreenshotError {}
+
+/// Renders a document into an RGBA8 buffer of `width` x `height` pixels
+pub(crate) type ScreenshotRenderer =
+    fn(&BaseDocument, u32, u32, f64, TextRenderingOptions) -> Vec<u8>;
+
+/// A [`ScreenshotRenderer`] using the image renderer `R`
+pub(crate) fn render_screenshot<R: ImageRenderer>(
+    doc: &BaseDocument,
+    width: u32,
+    height: u32,
+    scale: f64,
+    text_rendering: TextRenderingOptions,
+) -> Vec<u8> {
+    render_to_buffer::<R, _>(
+        |scene| paint_scene_with_options(scene, doc, scale, width, height, 0, 0, text_rendering),
+        width,
+        height,
+    )
+}
@@ -0,0 +1,144 @@
+//! Detection of the operating system's accessibility settings
+
+use bliss_traits::shell::SystemPreferences;
+use winit::window::WindowId;
+
+use crate::event::{BlissShellEvent, BlissShellProxy};
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+use std::process::Command;
+
+/// Read the operating system's accessibility settings (reduced motion and text scaling).
+/// Settings which can't be read are left at their defaults.
+pub fn detect_system_preferences() -> SystemPreferences {
+    let mut preferences = SystemPreferences::default();
+    detect(&mut preferences);
+    preferences
+}
+
+/// Read the operating system's accessibility settings on a background thread (reading them can involve
+/// running external programs), then send them to the window `window_id` in a
+/// [`BlissShellEvent::SystemPreferences`] event
+pub(crate) fn detect_system_preferences_in_background(window_id: WindowId, proxy: BlissShellProxy) {
+    let result = std::thread::Builder::new()
+        .name(String::from("bliss-system-preferences"))
+        .spawn(move || {
+            let preferences = detect_system_preferences();
+            proxy.send_event(BlissShellEvent::SystemPreferences {
+                window_id,
+                preferences,
+            });
+        });
+    if let Err(err) = result {
+        eprintln!("Failed to read system preferences: {err}");
+    }
+}
+
+/// Run a command and return its trimmed output, if it succeeded
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn read_setting(command: &mut Command) -> Option<String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // Don't flash a console window
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Read the GNOME settings (which most other desktops also follow)
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn detect(preferences: &mut SystemPreferences) {
+    let gsettings = |schema: &str, key: &str| {
+        read_setting(Command::new("gsettings").args(["get", schema, key]))
+    };
+
+    if let Some(enable_animations) = gsettings("org.gnome.desktop.interface", "enable-animations") {
+        preferences.reduced_motion = enable_animations == "false";
+    }
+    if let Some(text_scale) = gsettings("org.gnome.desktop.interface", "text-scaling-factor")
+        .and_then(|factor| factor.parse::<f32>().ok())
+    {
+        preferences.text_scale = text_scale;
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn detect(preferences: &mut SystemPreferences) {
+    let universal_access = |key: &str| {
+        read_setting(Command::new("defaults").args(["read", "com.apple.universalaccess", key]))
+    };
+
+    if let Some(reduce_motion) = universal_access("reduceMotion") {
+        preferences.reduced_motion = reduce_motion == "1";
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn detect(preferences: &mut SystemPreferences) {
+    // Parses the value from `reg query` output such as "    Flags    REG_SZ    126"
+    let registry = |key: &str, value: &str| {
+        let output = read_setting(Command::new("reg").args(["query", key, "/v", value]))?;
+        output
+            .lines()
+            .find(|line| line.trim_start().starts_with(value))
+            .and_then(|line| line.split_whitespace().last())
+            .map(str::to_string)
+    };
+    let parse_int = |value: String| match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse::<u32>().ok(),
+    };
+
+    // "Show animations in Windows"
+    if let Some(min_animate) = registry(r"HKCU\Control Panel\Desktop\WindowMetrics", "MinAnimate") {
+        preferences.reduced_motion = min_animate == "0";
+    }
+    // "Text size" (as a percentage)
+    if let Some(percent) =
+        registry(r"HKCU\Software\Microsoft\Accessibility", "TextScaleFactor").and_then(parse_int)
+    {
+        preferences.text_scale = percent as f32 / 100.0;
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+fn detect(preferences: &mut SystemPreferences) {
+    // Not supported on this platform
+    let _ = preferences;
+}
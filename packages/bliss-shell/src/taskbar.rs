@@ -0,0 +1,86 @@
+//! Progress indication on the window's taskbar button using `ITaskbarList3` (on Windows)
+
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::Com::{
+    CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+    CoUninitialize,
+};
+use windows::Win32::UI::Shell::{ITaskbarList3, TBPF_NOPROGRESS, TBPF_NORMAL, TaskbarList};
+use winit::raw_window_handle::{HasWindowHandle, RawWindowHandle};
+use winit::window::Window;
+
+/// The number of steps which progress is divided into
+const PROGRESS_STEPS: u64 = 1000;
+
+thread_local! {
+    /// COM objects can only be used from the thread (apartment) which created them, so the
+    /// taskbar is created once per thread and shared by all of the thread's windows
+    static TASKBAR: Taskbar = Taskbar::new();
+}
+
+/// An initialised `ITaskbarList3`, along with the COM initialisation it depends on
+struct Taskbar {
+    list: Option<ITaskbarList3>,
+    /// Whether COM was initialised by us, so must be uninitialised when the thread exits
+    uninitialize: bool,
+}
+
+impl Taskbar {
+    fn new() -> Self {
+        // SAFETY: COM is initialised on this thread before the taskbar is created
+        unsafe {
+            // Succeeds with `S_FALSE` if COM was already initialised (e.g. by the windowing
+            // system). Either success must be balanced by a call to `CoUninitialize`.
+            let uninitialize = CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok();
+            let list =
+                CoCreateInstance::<_, ITaskbarList3>(&TaskbarList, None, CLSCTX_INPROC_SERVER)
+                    .ok()
+                    .filter(|list| list.HrInit().is_ok());
+            Self { list, uninitialize }
+        }
+    }
+}
+
+impl Drop for Taskbar {
+    fn drop(&mut self) {
+        // Release the taskbar before COM is uninitialised
+        self.list = None;
+        if self.uninitialize {
+            // SAFETY: balances the successful `CoInitializeEx` in `Taskbar::new` on this thread
+            unsafe { CoUninitialize() };
+        }
+    }
+}
+
+/// Show `progress` (from `0.0` to `1.0`) on the window's taskbar button, or remove the progress
+/// indicator if `progress` is `None`
+pub(crate) fn set_taskbar_progress(window: &dyn Window, progress: Option<f32>) {
+    let Ok(handle) = window.window_handle() else {
+        return;
+    };
+    let RawWindowHandle::Win32(handle) = handle.as_raw() else {
+        return;
+    };
+    let hwnd = HWND(handle.hwnd.get() as _);
+
+    let _ = TASKBAR.try_with(|taskbar| {
+        let Some(taskbar) = &taskbar.list else {
+            return;
+        };
+
+        // SAFETY: the taskbar was created on this thread, and the handle refers to a live window
+        unsafe {
+            match progress {
+                Some(progress) => {
+                    let completed =
+                        (progress.clamp(0.0, 1.0) as f64 * PROGRESS_STEPS as f64) as u64;
+                    let _ = taskbar.SetProgressState(hwnd, TBPF_NORMAL);
+                    let _ = taskbar.SetProgressValue(hwnd, completed, PROGRESS_STEPS);
+                }
+                None => {
+                    let _ = taskbar.SetProgressState(hwnd, TBPF_NOPROGRESS);
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,135 @@
+//! System tray icon using [`tray_icon`] (on Windows and macOS)
+
+use bliss_traits::events::MouseEventButton;
+use bliss_traits::shell::ContextMenu;
+
+use crate::event::BlissShellProxy;
+
+/// Configuration of the application's system tray icon
+/// (see [`BlissApplication::set_tray`](crate::BlissApplication::set_tray))
+#[derive(Debug, Clone, Default)]
+pub struct TrayConfig {
+    /// The icon as RGBA8 pixels
+    pub icon: Vec<u8>,
+    pub icon_width: u32,
+    pub icon_height: u32,
+    pub tooltip: Option<String>,
+    /// The menu shown when the icon is right-clicked
+    pub menu: ContextMenu,
+    /// Hide windows (rather than closing them) when their close button is pressed,
+    /// and show them again when the icon is clicked.
+    ///
+    /// This only applies while the tray icon exists. Tray icons are only supported on Windows and
+    /// macOS, so elsewhere (or if the icon couldn't be created) windows close as usual.
+    pub hide_to_tray: bool,
+}
+
+/// An interaction with the system tray icon
+#[derive(Debug, Clone)]
+pub enum TrayEvent {
+    Click { button: MouseEventButton },
+    DoubleClick { button: MouseEventButton },
+    MenuItemSelected { item_id: String },
+}
+
+pub(crate) struct TrayState {
+    pub(crate) config: Option<TrayConfig>,
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    icon: Option<tray_icon::TrayIcon>,
+}
+
+impl TrayState {
+    pub(crate) fn new() -> Self {
+        Self {
+            config: None,
+            #[cfg(any(target_os = "windows", target_os = "macos"))]
+            icon: None,
+        }
+    }
+
+    /// Whether closed windows should be hidden. Windows are only hidden if the tray icon exists,
+    /// as otherwise there would be no way to show them again.
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    pub(crate) fn hide_to_tray(&self) -> bool {
+        self.icon.is_some()
+            && self
+                .config
+                .as_ref()
+                .is_some_and(|config| config.hide_to_tray)
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    pub(crate) fn hide_to_tray(&self) -> bool {
+        // System tray icons aren't supported on this platform
+        false
+    }
+
+    pub(crate) fn set_config(&mut self, config: Option<TrayConfig>) {
+        self.config = config;
+        #[cfg(any(target_os = "windows", target_os = "macos"))]
+        {
+            self.icon = None;
+        }
+    }
+
+    /// Create the native tray icon if it's configured but doesn't exist yet.
+    /// This must be called after the event loop has started.
+    #[cfg(any(target_os = "windows", target_os = "macos"))]
+    pub(crate) fn ensure_created(&mut self, proxy: &BlissShellProxy) {
+        use crate::BlissShellEvent;
+        use crate::context_menu::{TRAY_MENU_ID_PREFIX, build_menu, set_menu_event_handler};
+        use tray_icon::{Icon, MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+
+        let Some(config) = &self.config else {
+            return;
+        };
+        if self.icon.is_some() {
+            return;
+        }
+
+        let menu = build_menu(&config.menu, TRAY_MENU_ID_PREFIX);
+        let mut builder = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            // Left clicks show hidden windows rather than opening the menu
+            .with_menu_on_left_click(false);
+        if let Ok(icon) =
+            Icon::from_rgba(config.icon.clone(), config.icon_width, config.icon_height)
+        {
+            builder = builder.with_icon(icon);
+        }
+        if let Some(tooltip) = &config.tooltip {
+            builder = builder.with_tooltip(tooltip);
+        }
+        self.icon = builder.build().ok();
+
+        set_menu_event_handler(proxy.clone(), None);
+        let proxy = proxy.clone();
+        TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+            let to_bliss_button = |button: MouseButton| match button {
+                MouseButton::Left => MouseEventButton::Main,
+                MouseButton::Right => MouseEventButton::Secondary,
+                MouseButton::Middle => MouseEventButton::Auxiliary,
+            };
+            let event = match event {
+                TrayIconEvent::Click {
+                    button,
+                    button_state: MouseButtonState::Up,
+                    ..
+                } => TrayEvent::Click {
+                    button: to_bliss_button(button),
+                },
+                TrayIconEvent::DoubleClick { button, .. } => TrayEvent::DoubleClick {
+                    button: to_bliss_button(button),
+                },
+                _ => return,
+            };
+            proxy.send_event(BlissShellEvent::Tray(event));
+        }));
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    pub(crate) fn ensure_created(&mut self, proxy: &BlissShellProxy) {
+        // System tray icons aren't supported on this platform
+        let _ = proxy;
+    }
+}
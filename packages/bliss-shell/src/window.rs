@@ -1,27 +1,37 @@
 use crate::BlissShellProvider;
 use crate::convert_events::{
-    button_source_to_bliss, color_scheme_to_theme, pointer_source_to_bliss,
-    pointer_source_to_bliss_details, theme_to_color_scheme, winit_ime_to_bliss,
-    winit_key_event_to_bliss, winit_modifiers_to_kbt_modifiers,
+    button_source_to_bliss, color_scheme_to_theme, fullscreen_mode_to_winit,
+    pointer_source_to_bliss, pointer_source_to_bliss_details, theme_to_color_scheme,
+    winit_fullscreen_to_mode, winit_ime_to_bliss, winit_key_event_to_bliss,
+    winit_modifiers_to_kbt_modifiers,
 };
-use crate::event::{BlissShellProxy, create_waker};
+use crate::event::{BlissShellEvent, BlissShellProxy, create_waker};
+use crate::frame_stats::{FrameStats, FrameStatsRecorder};
+use crate::screenshot::{Screenshot, ScreenshotRenderer};
+use crate::system_preferences::detect_system_preferences_in_background;
+use crate::window_geometry::{WindowGeometryStore, current_geometry, restore_geometry};
+use crate::zoom::{ZOOM_STEP, ZoomLevels, document_origin, normalize_zoom};
 use anyrender::WindowRenderer;
 use bliss_dom::Document;
-use bliss_paint::paint_scene;
+use bliss_paint::{TextRenderingOptions, paint_scene_with_options};
 use bliss_traits::events::{
-    BlissPointerEvent, BlissPointerId, BlissWheelDelta, BlissWheelEvent, MouseEventButton,
+    BlissDataTransfer, BlissDragEvent, BlissFullscreenEvent, BlissKeyEvent, BlissPointerEvent,
+    BlissPointerId, BlissWheelDelta, BlissWheelEvent, KeyState, MouseEventButton,
     MouseEventButtons, PointerCoords, PointerDetails, UiEvent,
 };
-use bliss_traits::shell::Viewport;
-use winit::dpi::{LogicalPosition, PhysicalInsets, PhysicalPosition};
+use bliss_traits::shell::{EditCommand, FullscreenMode, SystemPreferences, Viewport, WindowIcon};
+use keyboard_types::{Code, Key, Location, Modifiers};
+use winit::dpi::{LogicalPosition, PhysicalInsets, PhysicalPosition, PhysicalSize};
 use winit::keyboard::PhysicalKey;
 
 use std::any::Any;
 use std::sync::Arc;
+use std::sync::mpsc::Receiver;
 use std::task::Waker;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use winit::event::{ButtonSource, ElementState, MouseButton};
 use winit::event_loop::ActiveEventLoop;
+use winit::icon::{Icon, RgbaIcon};
 use winit::window::{Theme, WindowAttributes, WindowId};
 use winit::{event::Modifiers, event::WindowEvent, keyboard::KeyCode, window::Window};
 
@@ -32,6 +42,8 @@ pub struct WindowConfig<Rend: WindowRenderer> {
     doc: Box<dyn Document>,
     attributes: WindowAttributes,
     renderer: Rend,
+    text_rendering: TextRenderingOptions,
+    geometry_store: Option<WindowGeometryStore>,
 }
 
 impl<Rend: WindowRenderer> WindowConfig<Rend> {
@@ -48,8 +60,54 @@ impl<Rend: WindowRenderer> WindowConfig<Rend> {
             doc,
             attributes,
             renderer,
+            text_rendering: TextRenderingOptions::default(),
+            geometry_store: None,
         }
     }
+
+    /// Set the options used to rasterize text in this window
+    pub fn with_text_rendering(mut self, text_rendering: TextRenderingOptions) -> Self {
+        self.text_rendering = text_rendering;
+        self
+    }
+
+    /// Make the window's background transparent so that the desktop (or a compositor effect such
+    /// as a blurred "glass" background) shows through wherever the document doesn't paint a background
+    pub fn with_transparent(mut self, transparent: bool) -> Self {
+        self.attributes = self.attributes.with_transparent(transparent);
+        self
+    }
+
+    /// Show or hide the platform's window decorations (title bar and borders).
+    ///
+    /// Windows without decorations can declare their own title bar by marking elements with a
+    /// `data-bliss-drag-region` attribute. Pressing the mouse on these elements drags the window.
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.attributes = self.attributes.with_decorations(decorations);
+        self
+    }
+
+    /// Set the icon shown in the window's title bar and the taskbar. This is replaced by the
+    /// document's `<link rel="icon">` (if it has one) once that has loaded.
+    pub fn with_window_icon(mut self, icon: WindowIcon) -> Self {
+        self.attributes = self.attributes.with_window_icon(to_winit_icon(icon));
+        self
+    }
+
+    /// Restore the window's position, size and maximized state from `store` when it is created,
+    /// and save them back to `store` when it is closed. This overrides any position or size set
+    /// in the window's attributes (unless nothing has been saved yet).
+    pub fn with_saved_geometry(mut self, store: WindowGeometryStore) -> Self {
+        self.geometry_store = Some(store);
+        self
+    }
+}
+
+/// Convert an icon to a winit icon. Returns `None` if the size of the icon doesn't match its pixel data.
+pub(crate) fn to_winit_icon(icon: WindowIcon) -> Option<Icon> {
+    RgbaIcon::new(icon.rgba, icon.width, icon.height)
+        .ok()
+        .map(Icon::from)
 }
 
 pub struct View<Rend: WindowRenderer> {
@@ -68,8 +126,23 @@ pub struct View<Rend: WindowRenderer> {
     pub buttons: MouseEventButtons,
     pub pointer_pos: PhysicalPosition<f64>,
     pub animation_timer: Option<Instant>,
+    /// When the window next needs to be redrawn (see [`View::next_wake_up`])
+    next_wake_up: Option<Instant>,
+    /// Whether the operating system's accessibility settings are being read in the background
+    reading_system_preferences: bool,
     pub is_visible: bool,
     pub safe_area_insets: PhysicalInsets<u32>,
+    pub text_rendering: TextRenderingOptions,
+    /// The data being dragged into the window (if any)
+    pub drag_data: Option<BlissDataTransfer>,
+    /// The fullscreen mode which the document was last told the window is in
+    pub fullscreen_mode: FullscreenMode,
+    /// The zoom level of each origin (shared with the application's other windows)
+    pub zoom_levels: ZoomLevels,
+    frame_stats: FrameStatsRecorder,
+    geometry_store: Option<WindowGeometryStore>,
+    /// The position and size of the window when it was last neither maximized nor fullscreen
+    normal_geometry: Option<(PhysicalPosition<i32>, PhysicalSize<u32>)>,
 
     #[cfg(feature = "accessibility")]
     /// Accessibility adapter for `accesskit`.
@@ -89,8 +162,16 @@ impl<Rend: WindowRenderer> View<Rend> {
         event_loop: &dyn ActiveEventLoop,
         proxy: &BlissShellProxy,
     ) -> Self {
+        let mut attributes = config.attributes;
+        let saved_geometry = config
+            .geometry_store
+            .as_ref()
+            .and_then(|store| store.load());
+        if let Some(geometry) = &saved_geometry {
+            attributes = restore_geometry(attributes, geometry, event_loop);
+        }
         let winit_window: Arc<dyn Window> =
-            Arc::from(event_loop.create_window(config.attributes).unwrap());
+            Arc::from(event_loop.create_window(attributes).unwrap());
 
         // Create viewport
         // TODO: account for the "safe area"
@@ -100,9 +181,10 @@ impl<Rend: WindowRenderer> View<Rend> {
         let theme = winit_window.theme().unwrap_or(Theme::Light);
         let color_scheme = theme_to_color_scheme(theme);
         let viewport = Viewport::new(size.width, size.height, scale, color_scheme);
+        detect_system_preferences_in_background(winit_window.id(), proxy.clone());
 
         // Create shell provider
-        let shell_provider = BlissShellProvider::new(winit_window.clone());
+        let shell_provider = BlissShellProvider::new(winit_window.clone(), proxy.clone());
 
         let mut doc = config.doc;
         let mut inner = doc.inner_mut();
@@ -123,6 +205,8 @@ impl<Rend: WindowRenderer> View<Rend> {
             renderer: config.renderer,
             waker: None,
             animation_timer: None,
+            next_wake_up: None,
+            reading_system_preferences: true,
             keyboard_modifiers: Default::default(),
             proxy: proxy.clone(),
             window: winit_window.clone(),
@@ -130,6 +214,20 @@ impl<Rend: WindowRenderer> View<Rend> {
             theme_override: None,
             buttons: MouseEventButtons::None,
             safe_area_insets,
+            text_rendering: config.text_rendering,
+            drag_data: None,
+            fullscreen_mode: winit_fullscreen_to_mode(winit_window.fullscreen()),
+            zoom_levels: ZoomLevels::new(),
+            frame_stats: FrameStatsRecorder::default(),
+            geometry_store: config.geometry_store,
+            normal_geometry: saved_geometry.map(|geometry| {
+                let (x, y) = geometry.position;
+                let (width, height) = geometry.size;
+                (
+                    PhysicalPosition::new(x, y),
+                    PhysicalSize::new(width, height),
+                )
+            }),
             pointer_pos: Default::default(),
             is_visible: winit_window.is_visible().unwrap_or(true),
             #[cfg(feature = "accessibility")]
@@ -160,6 +258,151 @@ impl<Rend: WindowRenderer> View<Rend> {
         if retain_scroll_position {
             self.doc.inner_mut().set_viewport_scroll(scroll);
         }
+
+        self.restore_origin_zoom();
+    }
+
+    /// Share zoom levels with other windows, and apply the level of the current document's origin
+    pub fn set_zoom_levels(&mut self, zoom_levels: ZoomLevels) {
+        self.zoom_levels = zoom_levels;
+        self.restore_origin_zoom();
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.doc.inner().viewport().zoom()
+    }
+
+    /// Set the zoom level of the document, and remember it for other documents from the same origin
+    pub fn set_zoom(&mut self, zoom: f32) {
+        let zoom = normalize_zoom(zoom);
+        if let Some(origin) = document_origin(&self.doc.inner()) {
+            self.zoom_levels.set(origin, zoom);
+        }
+        self.apply_zoom(zoom);
+    }
+
+    pub fn zoom_by(&mut self, delta: f32) {
+        self.set_zoom(self.zoom() + delta);
+    }
+
+    /// Apply the zoom level which was last used for the current document's origin
+    fn restore_origin_zoom(&mut self) {
+        let Some(origin) = document_origin(&self.doc.inner()) else {
+            return;
+        };
+        let zoom = self.zoom_levels.get(&origin).unwrap_or(1.0);
+        self.apply_zoom(zoom);
+    }
+
+    /// Change the zoom level of the viewport, notifying the embedder if it changed
+    fn apply_zoom(&mut self, zoom: f32) {
+        if zoom == self.zoom() {
+            return;
+        }
+        self.with_viewport(|v| v.set_zoom(zoom));
+        self.proxy.send_event(BlissShellEvent::ZoomChanged {
+            window_id: self.window_id(),
+            zoom,
+        });
+    }
+
+    /// Apply a clipboard command (e.g. from a text input's context menu) to the focussed element
+    /// by synthesizing the command's keyboard shortcut
+    pub fn apply_edit_command(&mut self, command: EditCommand) {
+        let (key, code) = match command {
+            EditCommand::Cut => ("x", Code::KeyX),
+            EditCommand::Copy => ("c", Code::KeyC),
+            EditCommand::Paste => ("v", Code::KeyV),
+        };
+        #[cfg(target_os = "macos")]
+        let modifiers = Modifiers::SUPER;
+        #[cfg(not(target_os = "macos"))]
+        let modifiers = Modifiers::CONTROL;
+
+        let event = BlissKeyEvent {
+            key: Key::Character(key.to_string()),
+            code,
+            modifiers,
+            location: Location::Standard,
+            is_auto_repeating: false,
+            is_composing: false,
+            state: KeyState::Pressed,
+            text: None,
+        };
+        self.doc.handle_ui_event(UiEvent::KeyDown(event));
+        self.request_redraw();
+    }
+
+    /// Enter or leave fullscreen. The document is sent a `fullscreenchange` event once the
+    /// window has resized.
+    pub fn set_fullscreen(&mut self, mode: FullscreenMode) {
+        let fullscreen = fullscreen_mode_to_winit(&*self.window, mode);
+        self.window.set_fullscreen(fullscreen);
+    }
+
+    /// Dispatch a `fullscreenchange` event if the window has entered or left fullscreen (either
+    /// by request or through the platform's own controls)
+    fn check_fullscreen_change(&mut self) {
+        let mode = winit_fullscreen_to_mode(self.window.fullscreen());
+        if mode != self.fullscreen_mode {
+            self.fullscreen_mode = mode;
+            let event = BlissFullscreenEvent { mode };
+            self.doc.handle_ui_event(UiEvent::FullscreenChange(event));
+            self.request_redraw();
+        }
+    }
+
+    /// Remember the window's position and size, unless it is maximized or fullscreen
+    fn track_normal_geometry(&mut self) {
+        if self.geometry_store.is_none()
+            || self.window.is_maximized()
+            || self.window.fullscreen().is_some()
+        {
+            return;
+        }
+        if let Ok(position) = self.window.outer_position() {
+            self.normal_geometry = Some((position, self.window.surface_size()));
+        }
+    }
+
+    /// Save the window's geometry (if it was created with [`WindowConfig::with_saved_geometry`])
+    pub(crate) fn save_geometry(&self) {
+        let Some(store) = &self.geometry_store else {
+            return;
+        };
+        if let Some(geometry) = current_geometry(&*self.window, self.normal_geometry)
+            && let Err(err) = store.save(&geometry)
+        {
+            eprintln!("Failed to save window geometry: {err}");
+        }
+    }
+
+    /// Render the current state of the window into an image
+    pub(crate) fn capture_screenshot(&mut self, renderer: ScreenshotRenderer) -> Screenshot {
+        let animation_time = self.current_animation_time();
+        let mut inner = self.doc.inner_mut();
+        inner.resolve(animation_time);
+
+        let (width, height) = inner.viewport().window_size;
+        let scale = inner.viewport().scale_f64();
+        let rgba = renderer(&inner, width, height, scale, self.text_rendering);
+        Screenshot {
+            width,
+            height,
+            rgba,
+        }
+    }
+
+    /// Receive [`FrameStats`] for each frame that this window renders (e.g. to detect jank).
+    /// Stats stop being collected for a receiver once it is dropped.
+    pub fn subscribe_frame_stats(&mut self) -> Receiver<FrameStats> {
+        self.frame_stats.subscribe()
+    }
+
+    /// Change the options used to rasterize text in this window
+    pub fn set_text_rendering(&mut self, text_rendering: TextRenderingOptions) {
+        self.text_rendering = text_rendering;
+        self.request_redraw();
     }
 
     pub fn theme_override(&self) -> Option<Theme> {
@@ -209,13 +452,24 @@ impl<Rend: WindowRenderer> View<Rend> {
         self.renderer
             .resume(Arc::new(self.window.clone()), width, height);
         if !self.renderer.is_active() {
-            panic!("Renderer failed to resume");
+            // The application drops windows whose renderer couldn't be initialised
+            return;
         };
 
         // Render
         let insets = self.safe_area_insets.to_logical(scale);
+        let text_rendering = self.text_rendering;
         self.renderer.render(|scene| {
-            paint_scene(scene, &inner, scale, width, height, insets.left, insets.top)
+            paint_scene_with_options(
+                scene,
+                &inner,
+                scale,
+                width,
+                height,
+                insets.left,
+                insets.top,
+                text_rendering,
+            )
         });
 
         // Set waker
@@ -285,23 +539,88 @@ impl<Rend: WindowRenderer> View<Rend> {
         self.ios_request_redraw.set(false);
         let animation_time = self.current_animation_time();
         let is_visible = self.is_visible;
+        let frame_start = Instant::now();
 
         let mut inner = self.doc.inner_mut();
         inner.resolve(animation_time);
+        let resolve_time = frame_start.elapsed();
 
         let (width, height) = inner.viewport().window_size;
         let scale = inner.viewport().scale_f64();
         let is_animating = inner.is_animating();
+        let next_caret_blink = inner.next_caret_blink_time();
         let insets = self.safe_area_insets.to_logical(scale);
+        let text_rendering = self.text_rendering;
+        let render_start = Instant::now();
+        let mut scene_build_time = Duration::ZERO;
         self.renderer.render(|scene| {
-            paint_scene(scene, &inner, scale, width, height, insets.left, insets.top)
+            let scene_start = Instant::now();
+            paint_scene_with_options(
+                scene,
+                &inner,
+                scale,
+                width,
+                height,
+                insets.left,
+                insets.top,
+                text_rendering,
+            );
+            scene_build_time = scene_start.elapsed();
         });
+        let present_time = Instant::now();
 
         drop(inner);
 
+        self.frame_stats.record(
+            &*self.window,
+            frame_start,
+            resolve_time,
+            scene_build_time,
+            present_time
+                .duration_since(render_start)
+                .saturating_sub(scene_build_time),
+            present_time,
+            is_animating,
+        );
+
         if is_visible && is_animating {
             self.request_redraw();
         }
+        self.next_wake_up = next_caret_blink
+            .filter(|_| is_visible)
+            .zip(self.animation_timer)
+            .map(|(time, start)| start + Duration::from_secs_f64(time));
+    }
+
+    /// Re-read the operating system's accessibility settings in the background. The document is
+    /// updated once they have been read.
+    pub fn refresh_system_preferences(&mut self) {
+        if self.reading_system_preferences {
+            return;
+        }
+        self.reading_system_preferences = true;
+        detect_system_preferences_in_background(self.window_id(), self.proxy.clone());
+    }
+
+    /// Apply the operating system's accessibility settings to the document. The text scale set by the
+    /// embedder is kept.
+    pub fn set_system_preferences(&mut self, preferences: SystemPreferences) {
+        self.reading_system_preferences = false;
+        self.with_viewport(|v| v.set_system_preferences(preferences));
+    }
+
+    /// When the window next needs to be redrawn without any input (e.g. to blink the caret), if the
+    /// document isn't animating. The application waits until then before redrawing the window.
+    pub fn next_wake_up(&self) -> Option<Instant> {
+        self.next_wake_up
+    }
+
+    /// Redraw the window if its wake-up time has been reached
+    pub(crate) fn wake_up_if_due(&mut self, now: Instant) {
+        if self.next_wake_up.is_some_and(|wake_up| wake_up <= now) {
+            self.next_wake_up = None;
+            self.request_redraw();
+        }
     }
 
     pub fn pointer_coords(&self, position: PhysicalPosition<f64>) -> PointerCoords {
@@ -327,6 +646,18 @@ impl<Rend: WindowRenderer> View<Rend> {
         }
     }
 
+    fn drag_event(
+        &self,
+        position: PhysicalPosition<f64>,
+        data_transfer: BlissDataTransfer,
+    ) -> BlissDragEvent {
+        BlissDragEvent {
+            coords: self.pointer_coords(position),
+            mods: winit_modifiers_to_kbt_modifiers(self.keyboard_modifiers.state()),
+            data_transfer,
+        }
+    }
+
     pub fn window_id(&self) -> WindowId {
         self.window.id()
     }
@@ -370,7 +701,7 @@ impl<Rend: WindowRenderer> View<Rend> {
             WindowEvent::RedrawRequested => {
                 self.redraw();
             }
-            WindowEvent::Moved(_) => {}
+            WindowEvent::Moved(_) => self.track_normal_geometry(),
             WindowEvent::Occluded(is_occluded) => {
                 self.is_visible = !is_occluded;
                 if self.is_visible {
@@ -383,6 +714,8 @@ impl<Rend: WindowRenderer> View<Rend> {
                 let width = physical_size.width - insets.left - insets.right;
                 let height = physical_size.height - insets.top - insets.bottom;
                 self.with_viewport(|v| v.window_size = (width, height));
+                self.check_fullscreen_change();
+                self.track_normal_geometry();
                 self.request_redraw();
             }
             WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
@@ -412,14 +745,14 @@ impl<Rend: WindowRenderer> View<Rend> {
                         // Ctrl/Super keyboard shortcuts
                         if ctrl | meta {
                             match key_code {
-                                KeyCode::Equal => {
-                                    self.doc.inner_mut().viewport_mut().zoom_by(0.1);
+                                KeyCode::Equal | KeyCode::NumpadAdd => {
+                                    self.zoom_by(ZOOM_STEP);
                                 },
-                                KeyCode::Minus => {
-                                    self.doc.inner_mut().viewport_mut().zoom_by(-0.1);
+                                KeyCode::Minus | KeyCode::NumpadSubtract => {
+                                    self.zoom_by(-ZOOM_STEP);
                                 },
-                                KeyCode::Digit0 => {
-                                    self.doc.inner_mut().viewport_mut().set_zoom(1.0);
+                                KeyCode::Digit0 | KeyCode::Numpad0 => {
+                                    self.set_zoom(1.0);
                                 }
                                 _ => {}
                             };
@@ -476,6 +809,16 @@ impl<Rend: WindowRenderer> View<Rend> {
                 let id = button_source_to_bliss(&button);
                 let coords = self.pointer_coords(position);
                 self.pointer_pos = position;
+
+                // Pressing on a drag region moves the window instead of being dispatched to the document
+                if state == ElementState::Pressed
+                    && matches!(button, ButtonSource::Mouse(MouseButton::Left))
+                    && self.doc.inner().is_drag_region(coords.page_x, coords.page_y)
+                {
+                    let _ = self.window.drag_window();
+                    return;
+                }
+
                 let button = match &button {
                     ButtonSource::Mouse(mouse_button) => match mouse_button {
                         MouseButton::Left => MouseEventButton::Main,
@@ -526,6 +869,18 @@ impl<Rend: WindowRenderer> View<Rend> {
                 self.request_redraw();
             }
             WindowEvent::MouseWheel { delta, .. } => {
+                // Ctrl+wheel zooms rather than scrolling
+                if self.keyboard_modifiers.state().control_key() {
+                    let lines = match delta {
+                        winit::event::MouseScrollDelta::LineDelta(_, y) => y,
+                        winit::event::MouseScrollDelta::PixelDelta(pos) => {
+                            (pos.to_logical::<f64>(self.window.scale_factor()).y / 100.0) as f32
+                        }
+                    };
+                    self.zoom_by(lines * ZOOM_STEP);
+                    return;
+                }
+
                 let bliss_delta = match delta {
                     winit::event::MouseScrollDelta::LineDelta(x, y) => BlissWheelDelta::Lines(x as f64, y as f64),
                     winit::event::MouseScrollDelta::PixelDelta(pos) => BlissWheelDelta::Pixels(pos.x, pos.y),
@@ -540,16 +895,47 @@ impl<Rend: WindowRenderer> View<Rend> {
 
                 self.doc.handle_ui_event(UiEvent::Wheel(event));
             }
-            WindowEvent::Focused(_) => {}
+            WindowEvent::Focused(focused) => {
+                // There's no cross-platform notification for accessibility settings changing, so
+                // re-read them whenever the user returns to the window
+                if focused {
+                    self.refresh_system_preferences();
+                }
+            }
             WindowEvent::TouchpadPressure { .. } => {}
             WindowEvent::PinchGesture { .. } => {},
             WindowEvent::PanGesture { .. } => {},
             WindowEvent::DoubleTapGesture { .. } => {},
             WindowEvent::RotationGesture { .. } => {},
-            WindowEvent::DragEntered { .. } => {},
-            WindowEvent::DragMoved { .. } => {},
-            WindowEvent::DragDropped { .. } => {},
-            WindowEvent::DragLeft { .. } => {},
+            WindowEvent::DragEntered { paths, position } => {
+                let data_transfer = BlissDataTransfer { files: paths };
+                self.drag_data = Some(data_transfer.clone());
+                self.pointer_pos = position;
+                let event = self.drag_event(position, data_transfer);
+                self.doc.handle_ui_event(UiEvent::DragEnter(event));
+                self.request_redraw();
+            }
+            WindowEvent::DragMoved { position } => {
+                let data_transfer = self.drag_data.clone().unwrap_or_default();
+                self.pointer_pos = position;
+                let event = self.drag_event(position, data_transfer);
+                self.doc.handle_ui_event(UiEvent::DragMove(event));
+                self.request_redraw();
+            }
+            WindowEvent::DragDropped { paths, position } => {
+                self.drag_data = None;
+                self.pointer_pos = position;
+                let event = self.drag_event(position, BlissDataTransfer { files: paths });
+                self.doc.handle_ui_event(UiEvent::Drop(event));
+                self.request_redraw();
+            }
+            WindowEvent::DragLeft { position } => {
+                let data_transfer = self.drag_data.take().unwrap_or_default();
+                let position = position.unwrap_or(self.pointer_pos);
+                let event = self.drag_event(position, data_transfer);
+                self.doc.handle_ui_event(UiEvent::DragLeave(event));
+                self.request_redraw();
+            }
         }
     }
 }
@@ -0,0 +1,273 @@
+//! Saving and restoring the position and size of windows between launches
+
+use std::path::PathBuf;
+
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event_loop::ActiveEventLoop;
+use winit::monitor::MonitorHandle;
+use winit::window::{Window, WindowAttributes};
+
+/// The placement of a window on the desktop. Positions and sizes are in physical pixels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowGeometry {
+    /// The position of the window's outer top-left corner
+    pub position: (i32, i32),
+    /// The size of the window's content area when it isn't maximized
+    pub size: (u32, u32),
+    pub maximized: bool,
+    /// The name of the monitor which the window was on
+    pub monitor: Option<String>,
+}
+
+impl WindowGeometry {
+    fn serialize(&self) -> String {
+        let mut out = format!(
+            "position={},{}\nsize={},{}\nmaximized={}\n",
+            self.position.0, self.position.1, self.size.0, self.size.1, self.maximized
+        );
+        if let Some(monitor) = &self.monitor {
+            out.push_str(&format!("monitor={monitor}\n"));
+        }
+        out
+    }
+
+    fn parse(input: &str) -> Option<Self> {
+        fn pair<T: std::str::FromStr>(value: &str) -> Option<(T, T)> {
+            let (a, b) = value.split_once(',')?;
+            Some((a.trim().parse().ok()?, b.trim().parse().ok()?))
+        }
+
+        let mut position = None;
+        let mut size = None;
+        let mut maximized = false;
+        let mut monitor = None;
+        for line in input.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match key.trim() {
+                "position" => position = pair(value),
+                "size" => size = pair(value),
+                "maximized" => maximized = value.trim() == "true",
+                "monitor" => monitor = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            position: position?,
+            size: size?,
+            maximized,
+            monitor,
+        })
+    }
+}
+
+/// Where a window's geometry is saved. Each application (and each profile within an application)
+/// has its own saved geometry, which is stored in the platform's configuration directory.
+#[derive(Debug, Clone)]
+pub struct WindowGeometryStore {
+    path: Option<PathBuf>,
+}
+
+impl WindowGeometryStore {
+    /// Store geometry for the application `app_name`
+    pub fn new(app_name: &str) -> Self {
+        Self {
+            path: config_dir().map(|dir| dir.join(app_name).join("window-geometry")),
+        }
+    }
+
+    /// Store geometry in a specific file
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+        }
+    }
+
+    /// Store geometry separately for the profile `profile` of the application
+    pub fn with_profile(mut self, profile: &str) -> Self {
+        self.path = self
+            .path
+            .map(|path| path.with_file_name(format!("window-geometry-{profile}")));
+        self
+    }
+
+    /// The saved geometry, if there is any
+    pub fn load(&self) -> Option<WindowGeometry> {
+        let contents = std::fs::read_to_string(self.path.as_ref()?).ok()?;
+        WindowGeometry::parse(&contents)
+    }
+
+    pub fn save(&self, geometry: &WindowGeometry) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, geometry.serialize())
+    }
+}
+
+/// The directory which per-user application configuration is stored in
+fn config_dir() -> Option<PathBuf> {
+    let env_path = |name: &str| std::env::var_os(name).map(PathBuf::from);
+    if cfg!(target_os = "windows") {
+        env_path("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_path("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        env_path("XDG_CONFIG_HOME").or_else(|| env_path("HOME").map(|home| home.join(".config")))
+    }
+}
+
+/// The bounds of a monitor in physical pixels
+#[derive(Debug, Clone)]
+struct MonitorBounds {
+    name: Option<String>,
+    position: (i32, i32),
+    size: (u32, u32),
+}
+
+impl MonitorBounds {
+    fn from_handle(monitor: &MonitorHandle) -> Option<Self> {
+        let position = monitor.position()?;
+        let size = monitor.current_video_mode()?.size();
+        Some(Self {
+            name: monitor.name().map(|name| name.to_string()),
+            position: (position.x, position.y),
+            size: (size.width, size.height),
+        })
+    }
+}
+
+/// Where a restored window should be placed. A position of `None` leaves placement to the platform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Placement {
+    position: Option<(i32, i32)>,
+    size: (u32, u32),
+}
+
+/// Fit saved geometry onto the currently attached monitors. The window is kept on its saved
+/// monitor (moved back within its bounds if necessary). If that monitor is no longer attached,
+/// the platform picks the window's position and its size is limited to the primary monitor.
+fn place(
+    geometry: &WindowGeometry,
+    monitors: &[MonitorBounds],
+    primary: Option<&MonitorBounds>,
+) -> Placement {
+    let saved_monitor = geometry
+        .monitor
+        .as_ref()
+        .and_then(|name| monitors.iter().find(|m| m.name.as_ref() == Some(name)));
+
+    let Some(monitor) = saved_monitor else {
+        let size = match primary.or(monitors.first()) {
+            Some(monitor) => (
+                geometry.size.0.min(monitor.size.0),
+                geometry.size.1.min(monitor.size.1),
+            ),
+            None => geometry.size,
+        };
+        return Placement {
+            position: None,
+            size,
+        };
+    };
+
+    let size = (
+        geometry.size.0.min(monitor.size.0),
+        geometry.size.1.min(monitor.size.1),
+    );
+    let max_x = monitor.position.0 + (monitor.size.0 - size.0) as i32;
+    let max_y = monitor.position.1 + (monitor.size.1 - size.1) as i32;
+    let position = (
+        geometry.position.0.clamp(monitor.position.0, max_x),
+        geometry.position.1.clamp(monitor.position.1, max_y),
+    );
+
+    Placement {
+        position: Some(position),
+        size,
+    }
+}
+
+/// Apply saved geometry to the attributes of a window which is about to be created
+pub(crate) fn restore_geometry(
+    attributes: WindowAttributes,
+    geometry: &WindowGeometry,
+    event_loop: &dyn ActiveEventLoop,
+) -> WindowAttributes {
+    let monitors: Vec<MonitorBounds> = event_loop
+        .available_monitors()
+        .filter_map(|monitor| MonitorBounds::from_handle(&monitor))
+        .collect();
+    let primary = event_loop
+        .primary_monitor()
+        .and_then(|monitor| MonitorBounds::from_handle(&monitor));
+
+    let placement = place(geometry, &monitors, primary.as_ref());
+    let mut attributes = attributes
+        .with_surface_size(PhysicalSize::new(placement.size.0, placement.size.1))
+        .with_maximized(geometry.maximized);
+    if let Some((x, y)) = placement.position {
+        attributes = attributes.with_position(PhysicalPosition::new(x, y));
+    }
+    attributes
+}
+
+/// The current geometry of a window. `normal_geometry` is the window's last known position and
+/// size while it wasn't maximized (which is what should be restored when it is unmaximized).
+pub(crate) fn current_geometry(
+    window: &dyn Window,
+    normal_geometry: Option<(PhysicalPosition<i32>, PhysicalSize<u32>)>,
+) -> Option<WindowGeometry> {
+    let (position, size) = match normal_geometry {
+        Some(geometry) => geometry,
+        None => (window.outer_position().ok()?, window.surface_size()),
+    };
+    Some(WindowGeometry {
+        position: (position.x, position.y),
+        size: (size.width, size.height),
+        maximized: window.is_maximized(),
+        monitor: window
+            .current_monitor()
+            .and_then(|monitor| monitor.name().map(|name| name.to_string())),
+    })
+}
+
+#[test]
+fn falls_back_when_monitor_is_missing() {
+    let geometry = WindowGeometry {
+        position: (2200, 100),
+        size: (2000, 900),
+        maximized: false,
+        monitor: Some("External".to_string()),
+    };
+    assert_eq!(
+        WindowGeometry::parse(&geometry.serialize()),
+        Some(geometry.clone())
+    );
+
+    let laptop = MonitorBounds {
+        name: Some("Built-in".to_string()),
+        position: (0, 0),
+        size: (1920, 1080),
+    };
+    let external = MonitorBounds {
+        name: Some("External".to_string()),
+        position: (1920, 0),
+        size: (2560, 1440),
+    };
+
+    // The saved monitor is attached, so the window is kept on it
+    let placement = place(&geometry, &[laptop.clone(), external], Some(&laptop));
+    assert_eq!(placement.position, Some((2200, 100)));
+    assert_eq!(placement.size, (2000, 900));
+
+    // The saved monitor has been unplugged
+    let placement = place(&geometry, std::slice::from_ref(&laptop), Some(&laptop));
+    assert_eq!(placement.position, None);
+    assert_eq!(placement.size, (1920, 900));
+}
@@ -0,0 +1,75 @@
+//! Per-origin zoom levels
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use bliss_dom::BaseDocument;
+
+pub(crate) const MIN_ZOOM: f32 = 0.25;
+pub(crate) const MAX_ZOOM: f32 = 5.0;
+/// The change in zoom for each press of Ctrl+= / Ctrl+- (or each line scrolled with Ctrl held)
+pub(crate) const ZOOM_STEP: f32 = 0.1;
+
+/// The zoom level of each origin which has been zoomed. This is shared between the windows of an
+/// application so that documents open at the zoom level that was last used for their origin.
+///
+/// Levels only last as long as the application, but can be saved with [`to_map`](Self::to_map)
+/// and restored with [`from_map`](Self::from_map).
+#[derive(Debug, Clone, Default)]
+pub struct ZoomLevels(Arc<Mutex<HashMap<String, f32>>>);
+
+impl ZoomLevels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_map(levels: HashMap<String, f32>) -> Self {
+        Self(Arc::new(Mutex::new(levels)))
+    }
+
+    /// A copy of the zoom level of each origin
+    pub fn to_map(&self) -> HashMap<String, f32> {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub fn get(&self, origin: &str) -> Option<f32> {
+        self.0.lock().unwrap().get(origin).copied()
+    }
+
+    /// Record the zoom level of an origin. Origins at the default zoom (100%) aren't stored.
+    pub fn set(&self, origin: String, zoom: f32) {
+        let mut levels = self.0.lock().unwrap();
+        if zoom == 1.0 {
+            levels.remove(&origin);
+        } else {
+            levels.insert(origin, zoom);
+        }
+    }
+}
+
+/// The origin which a document's zoom level is stored under. Documents with opaque origins
+/// (e.g. `file:` and `data:` URLs) don't have their zoom level stored.
+pub(crate) fn document_origin(doc: &BaseDocument) -> Option<String> {
+    let origin = doc.url().origin();
+    origin.is_tuple().then(|| origin.ascii_serialization())
+}
+
+/// Clamp a zoom level to the supported range, rounding away floating point error from
+/// repeated steps (so that zooming in and back out returns to exactly 100%)
+pub(crate) fn normalize_zoom(zoom: f32) -> f32 {
+    ((zoom * 100.0).round() / 100.0).clamp(MIN_ZOOM, MAX_ZOOM)
+}
+
+#[test]
+fn default_zoom_is_not_stored() {
+    let levels = ZoomLevels::new();
+    levels.set("https://example.com".to_string(), 1.5);
+    assert_eq!(levels.get("https://example.com"), Some(1.5));
+
+    levels.set(
+        "https://example.com".to_string(),
+        normalize_zoom(1.5 - 5.0 * ZOOM_STEP),
+    );
+    assert_eq!(levels.get("https://example.com"), None);
+    assert!(levels.to_map().is_empty());
+}
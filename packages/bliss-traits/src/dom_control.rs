@@ -4,6 +4,8 @@
 //! from outside the rendering engine (e.g., from Exosphere's dom-capability crate).
 
 use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::SystemTime;
 
 pub type NodeId = usize;
 
@@ -79,10 +81,41 @@ pub trait DomController {
     ) -> DomControlResult<()>;
 }
 
+/// The kind of operation a [`DomCapabilityPolicy`] was asked to decide on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DomPolicyOp {
+    Query,
+    Mutation,
+    EventListener,
+}
+
+/// Details about the subject of a [`DomCapabilityPolicy`] decision
+#[derive(Debug, Clone, Copy)]
+pub struct DomDecisionContext<'a> {
+    /// The node being mutated or listened to. `None` for queries.
+    pub node_id: Option<NodeId>,
+    /// The selector, mutation name or event name the decision was made for
+    pub detail: &'a str,
+}
+
 pub trait DomCapabilityPolicy: Send + Sync {
     fn allow_query(&self, doc_id: usize, selector: &str) -> bool;
     fn allow_mutation(&self, doc_id: usize, node_id: NodeId, op: &str) -> bool;
     fn allow_event_listener(&self, doc_id: usize, node_id: NodeId, event: &str) -> bool;
+
+    /// Called after every decision made by this policy. Does nothing by default.
+    fn on_decision(
+        &self,
+        doc_id: usize,
+        op: DomPolicyOp,
+        allowed: bool,
+        context: DomDecisionContext<'_>,
+    ) {
+        let _ = doc_id;
+        let _ = op;
+        let _ = allowed;
+        let _ = context;
+    }
 }
 
 pub struct DefaultDomPolicy;
@@ -97,3 +130,152 @@ impl DomCapabilityPolicy for DefaultDomPolicy {
         true
     }
 }
+
+/// An owned record of a single [`DomCapabilityPolicy`] decision
+#[derive(Debug, Clone)]
+pub struct DomAuditRecord {
+    pub timestamp: SystemTime,
+    pub doc_id: usize,
+    pub op: DomPolicyOp,
+    pub allowed: bool,
+    pub node_id: Option<NodeId>,
+    pub detail: String,
+}
+
+/// A destination for [`DomAuditRecord`]s produced by an [`AuditingPolicy`]
+pub trait DomAuditSink: Send + Sync {
+    fn record(&self, record: DomAuditRecord);
+}
+
+impl<F: Fn(DomAuditRecord) + Send + Sync> DomAuditSink for F {
+    fn record(&self, record: DomAuditRecord) {
+        self(record)
+    }
+}
+
+impl DomAuditSink for Mutex<Vec<DomAuditRecord>> {
+    fn record(&self, record: DomAuditRecord) {
+        self.lock().unwrap().push(record);
+    }
+}
+
+/// A [`DomCapabilityPolicy`] which delegates decisions to an inner policy and
+/// records every decision to a [`DomAuditSink`]
+pub struct AuditingPolicy<P: DomCapabilityPolicy, S: DomAuditSink> {
+    inner: P,
+    sink: S,
+}
+
+impl<P: DomCapabilityPolicy, S: DomAuditSink> AuditingPolicy<P, S> {
+    pub fn new(inner: P, sink: S) -> Self {
+        Self { inner, sink }
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    fn decide(
+        &self,
+        doc_id: usize,
+        op: DomPolicyOp,
+        allowed: bool,
+        context: DomDecisionContext<'_>,
+    ) -> bool {
+        self.on_decision(doc_id, op, allowed, context);
+        allowed
+    }
+}
+
+impl<P: DomCapabilityPolicy, S: DomAuditSink> DomCapabilityPolicy for AuditingPolicy<P, S> {
+    fn allow_query(&self, doc_id: usize, selector: &str) -> bool {
+        let allowed = self.inner.allow_query(doc_id, selector);
+        let context = DomDecisionContext {
+            node_id: None,
+            detail: selector,
+        };
+        self.decide(doc_id, DomPolicyOp::Query, allowed, context)
+    }
+
+    fn allow_mutation(&self, doc_id: usize, node_id: NodeId, op: &str) -> bool {
+        let allowed = self.inner.allow_mutation(doc_id, node_id, op);
+        let context = DomDecisionContext {
+            node_id: Some(node_id),
+            detail: op,
+        };
+        self.decide(doc_id, DomPolicyOp::Mutation, allowed, context)
+    }
+
+    fn allow_event_listener(&self, doc_id: usize, node_id: NodeId, event: &str) -> bool {
+        let allowed = self.inner.allow_event_listener(doc_id, node_id, event);
+        let context = DomDecisionContext {
+            node_id: Some(node_id),
+            detail: event,
+        };
+        self.decide(doc_id, DomPolicyOp::EventListener, allowed, context)
+    }
+
+    fn on_decision(
+        &self,
+        doc_id: usize,
+        op: DomPolicyOp,
+        allowed: bool,
+        context: DomDecisionContext<'_>,
+    ) {
+        self.inner.on_decision(doc_id, op, allowed, context);
+        self.sink.record(DomAuditRecord {
+            timestamp: SystemTime::now(),
+            doc_id,
+            op,
+            allowed,
+            node_id: context.node_id,
+            detail: context.detail.to_string(),
+        });
+    }
+}
+
+/// A policy which denies queries for `#secret` and `set_inner_html` mutations
+#[cfg(test)]
+struct DenySecretsPolicy;
+
+#[cfg(test)]
+impl DomCapabilityPolicy for DenySecretsPolicy {
+    fn allow_query(&self, _doc_id: usize, selector: &str) -> bool {
+        selector != "#secret"
+    }
+    fn allow_mutation(&self, _doc_id: usize, _node_id: NodeId, op: &str) -> bool {
+        op != "set_inner_html"
+    }
+    fn allow_event_listener(&self, _doc_id: usize, _node_id: NodeId, _event: &str) -> bool {
+        true
+    }
+}
+
+#[test]
+fn auditing_policy_records_each_decision_once() {
+    let policy = AuditingPolicy::new(DenySecretsPolicy, Mutex::new(Vec::new()));
+
+    assert!(policy.allow_query(1, "#public"));
+    assert!(!policy.allow_query(1, "#secret"));
+    assert!(policy.allow_mutation(2, 7, "set_attribute"));
+    assert!(!policy.allow_mutation(2, 8, "set_inner_html"));
+
+    let records = policy.sink().lock().unwrap();
+    let decisions: Vec<_> = records
+        .iter()
+        .map(|r| (r.doc_id, r.op, r.allowed, r.node_id, r.detail.as_str()))
+        .collect();
+    assert_eq!(
+        decisions,
+        [
+            (1, DomPolicyOp::Query, true, None, "#public"),
+            (1, DomPolicyOp::Query, false, None, "#secret"),
+            (2, DomPolicyOp::Mutation, true, Some(7), "set_attribute"),
+            (2, DomPolicyOp::Mutation, false, Some(8), "set_inner_html"),
+        ]
+    );
+}
@@ -0,0 +1,385 @@
+//! Abstractions allowing embedders to handle downloads
+//!
+//! Downloads are triggered by clicking a link with a `download` attribute, or by navigating to
+//! a resource with a content type that cannot be rendered as a document.
+
+use std::sync::Mutex;
+
+use http::header;
+
+use crate::net::{AbortController, Bytes, HeaderMap, NetHandler, Url};
+
+/// Why a resource is being downloaded rather than rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadReason {
+    /// A link with a `download` attribute was activated
+    DownloadAttribute,
+    /// A navigation resolved to a content type that cannot be rendered
+    UnsupportedContentType,
+}
+
+/// Information about a download, provided to [`DownloadHandler::start`]
+#[derive(Debug, Clone)]
+pub struct DownloadMetadata {
+    /// The URL of the resource being downloaded
+    pub url: Url,
+    /// The filename suggested by the `download` attribute or the URL path (if any)
+    pub suggested_filename: Option<String>,
+    /// The content type of the resource (if known)
+    pub content_type: Option<String>,
+    /// The total length of the resource in bytes (if known)
+    pub content_length: Option<u64>,
+    /// The document which triggered the download
+    pub source_document: usize,
+    pub reason: DownloadReason,
+}
+
+impl DownloadMetadata {
+    pub fn new(url: Url, source_document: usize, reason: DownloadReason) -> Self {
+        let suggested_filename = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|name| !name.is_empty())
+            .map(|name| name.to_string());
+        Self {
+            url,
+            suggested_filename,
+            content_type: None,
+            content_length: None,
+            source_document,
+            reason,
+        }
+    }
+
+    /// Fill in the content type, length and filename (from `Content-Disposition`) of the download from
+    /// the headers of its response
+    pub fn with_response_headers(mut self, headers: &HeaderMap) -> Self {
+        if let Some(content_type) = header_str(headers, header::CONTENT_TYPE) {
+            self.content_type = Some(content_type.to_string());
+        }
+        if let Some(length) = content_length(headers) {
+            self.content_length = Some(length);
+        }
+        if let Some(filename) =
+            header_str(headers, header::CONTENT_DISPOSITION).and_then(disposition_filename)
+        {
+            self.suggested_filename = Some(filename);
+        }
+        self
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    header_str(headers, header::CONTENT_LENGTH).and_then(|value| value.trim().parse().ok())
+}
+
+/// The `filename` parameter of a `Content-Disposition` header
+fn disposition_filename(disposition: &str) -> Option<String> {
+    disposition.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("filename") {
+            return None;
+        }
+        let value = value.trim().trim_matches('"');
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+/// Returned by [`DownloadSink::chunk`] to indicate whether the download should continue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadControl {
+    Continue,
+    Cancel,
+}
+
+/// Receives the body of a single download
+pub trait DownloadSink: Send + 'static {
+    /// Called for each chunk of the body as it is received
+    fn chunk(&mut self, bytes: Bytes) -> DownloadControl;
+    /// Called after each chunk with the total number of bytes received so far
+    fn progress(&mut self, received: u64, total: Option<u64>) {
+        let _ = received;
+        let _ = total;
+    }
+    /// Called once the entire body has been received
+    fn finish(self: Box<Self>);
+    /// Called if the download fails or is cancelled
+    fn error(self: Box<Self>, message: String) {
+        let _ = message;
+    }
+}
+
+/// An abstraction to allow embedders to handle downloads. Without a handler, downloads are ignored.
+pub trait DownloadHandler: Send + Sync + 'static {
+    /// Called when a download starts. Return `None` to decline the download.
+    fn start(&self, metadata: DownloadMetadata) -> Option<Box<dyn DownloadSink>>;
+}
+
+pub struct DummyDownloadHandler;
+impl DownloadHandler for DummyDownloadHandler {
+    fn start(&self, _metadata: DownloadMetadata) -> Option<Box<dyn DownloadSink>> {
+        None
+    }
+}
+
+/// Whether a response with the specified content type can be rendered as a document.
+/// Navigations to other content types should be handed to a [`DownloadHandler`].
+pub fn is_renderable_content_type(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    matches!(
+        mime.as_str(),
+        "" | "text/html"
+            | "application/xhtml+xml"
+            | "text/plain"
+            | "text/markdown"
+            | "image/svg+xml"
+    )
+}
+
+/// Whether the response to a navigation should be handed to a [`DownloadHandler`] rather than rendered:
+/// either its `Content-Disposition` is `attachment` or its content type cannot be rendered
+pub fn is_download_response(headers: &HeaderMap) -> bool {
+    let is_attachment = header_str(headers, header::CONTENT_DISPOSITION).is_some_and(|value| {
+        value
+            .split(';')
+            .next()
+            .is_some_and(|kind| kind.trim().eq_ignore_ascii_case("attachment"))
+    });
+    let content_type = header_str(headers, header::CONTENT_TYPE).unwrap_or_default();
+    is_attachment || !is_renderable_content_type(content_type)
+}
+
+/// Hand a response which has already been received (e.g. the response to a navigation for which
+/// [`is_download_response`] is `true`) to a download handler.
+/// Returns `false` if the handler declined the download.
+pub fn download_response(
+    handler: &dyn DownloadHandler,
+    url: Url,
+    source_document: usize,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> bool {
+    let metadata =
+        DownloadMetadata::new(url, source_document, DownloadReason::UnsupportedContentType)
+            .with_response_headers(headers);
+    let resolved_url = metadata.url.to_string();
+    let total = metadata.content_length;
+    let Some(sink) = handler.start(metadata) else {
+        return false;
+    };
+
+    let handler = Box::new(DownloadNetHandler::new(sink, total, None));
+    handler.bytes(resolved_url, body);
+    true
+}
+
+struct DownloadState {
+    sink: Option<Box<dyn DownloadSink>>,
+    controller: Option<AbortController>,
+    received: u64,
+    total: Option<u64>,
+}
+
+/// A [`NetHandler`] which streams a response body into a [`DownloadSink`]
+pub struct DownloadNetHandler {
+    state: Mutex<DownloadState>,
+}
+
+impl DownloadNetHandler {
+    /// Create a handler. The `controller` should be the controller whose signal is attached to
+    /// the [`Request`](crate::net::Request), and is used to abort the fetch if the sink cancels.
+    /// If `total` is `None`, it is taken from the `Content-Length` of the response (if any).
+    pub fn new(
+        sink: Box<dyn DownloadSink>,
+        total: Option<u64>,
+        controller: Option<AbortController>,
+    ) -> Self {
+        Self {
+            state: Mutex::new(DownloadState {
+                sink: Some(sink),
+                controller,
+                received: 0,
+                total,
+            }),
+        }
+    }
+
+    fn write(&self, bytes: Bytes) {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        let Some(sink) = state.sink.as_mut() else {
+            return;
+        };
+
+        state.received += bytes.len() as u64;
+        let control = sink.chunk(bytes);
+        sink.progress(state.received, state.total);
+
+        if control == DownloadControl::Cancel {
+            if let Some(controller) = state.controller.take() {
+                controller.abort();
+            }
+            if let Some(sink) = state.sink.take() {
+                sink.error(String::from("Download cancelled"));
+            }
+        }
+    }
+
+    fn finish(&self) {
+        if let Some(sink) = self.state.lock().unwrap().sink.take() {
+            sink.finish();
+        }
+    }
+}
+
+impl NetHandler for DownloadNetHandler {
+    fn headers(&self, headers: &HeaderMap) {
+        let mut state = self.state.lock().unwrap();
+        if state.total.is_none() {
+            state.total = content_length(headers);
+        }
+    }
+    fn bytes(self: Box<Self>, _resolved_url: String, bytes: Bytes) {
+        self.write(bytes);
+        self.finish();
+    }
+    fn chunk(&self, _seq: u64, bytes: Bytes) {
+        self.write(bytes);
+    }
+    fn end(&self) {
+        self.finish();
+    }
+    fn error(&self, message: String) {
+        if let Some(sink) = self.state.lock().unwrap().sink.take() {
+            sink.error(message);
+        }
+    }
+}
+
+#[cfg(test)]
+#[derive(Default)]
+struct RecordedDownload {
+    received: Vec<u8>,
+    progress: Vec<(u64, Option<u64>)>,
+    finished: bool,
+    error: Option<String>,
+}
+
+#[cfg(test)]
+struct RecordingSink {
+    record: std::sync::Arc<Mutex<RecordedDownload>>,
+    cancel_after: Option<usize>,
+}
+
+#[cfg(test)]
+impl DownloadSink for RecordingSink {
+    fn chunk(&mut self, bytes: Bytes) -> DownloadControl {
+        let mut record = self.record.lock().unwrap();
+        record.received.extend_from_slice(&bytes);
+        match self.cancel_after {
+            Some(limit) if record.received.len() >= limit => DownloadControl::Cancel,
+            _ => DownloadControl::Continue,
+        }
+    }
+    fn progress(&mut self, received: u64, total: Option<u64>) {
+        self.record.lock().unwrap().progress.push((received, total));
+    }
+    fn finish(self: Box<Self>) {
+        self.record.lock().unwrap().finished = true;
+    }
+    fn error(self: Box<Self>, message: String) {
+        self.record.lock().unwrap().error = Some(message);
+    }
+}
+
+#[test]
+fn streams_downloads_with_total_from_headers() {
+    let record = std::sync::Arc::new(Mutex::new(RecordedDownload::default()));
+    let sink = RecordingSink {
+        record: record.clone(),
+        cancel_after: None,
+    };
+    let handler = DownloadNetHandler::new(Box::new(sink), None, None);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_LENGTH, "6".parse().unwrap());
+    handler.headers(&headers);
+    handler.chunk(0, Bytes::from_static(b"abc"));
+    handler.chunk(1, Bytes::from_static(b"def"));
+    handler.end();
+
+    let record = record.lock().unwrap();
+    assert_eq!(record.received, b"abcdef");
+    assert_eq!(record.progress, vec![(3, Some(6)), (6, Some(6))]);
+    assert!(record.finished);
+    assert!(record.error.is_none());
+}
+
+#[test]
+fn cancelling_download_aborts_fetch() {
+    let record = std::sync::Arc::new(Mutex::new(RecordedDownload::default()));
+    let sink = RecordingSink {
+        record: record.clone(),
+        cancel_after: Some(3),
+    };
+    let controller = AbortController::default();
+    let signal = controller.signal.clone();
+    let handler = DownloadNetHandler::new(Box::new(sink), Some(6), Some(controller));
+
+    handler.chunk(0, Bytes::from_static(b"abc"));
+    // Chunks received after cancelling are ignored
+    handler.chunk(1, Bytes::from_static(b"def"));
+    handler.end();
+
+    let record = record.lock().unwrap();
+    assert!(signal.aborted());
+    assert_eq!(record.received, b"abc");
+    assert!(!record.finished);
+    assert_eq!(record.error.as_deref(), Some("Download cancelled"));
+}
+
+#[test]
+fn detects_download_responses() {
+    let headers = |pairs: &[(header::HeaderName, &str)]| {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(name.clone(), value.parse().unwrap());
+        }
+        headers
+    };
+
+    assert!(!is_download_response(&headers(&[(
+        header::CONTENT_TYPE,
+        "text/html; charset=utf-8"
+    )])));
+    assert!(is_download_response(&headers(&[(
+        header::CONTENT_TYPE,
+        "application/zip"
+    )])));
+
+    let attachment = headers(&[
+        (header::CONTENT_TYPE, "text/plain"),
+        (header::CONTENT_LENGTH, "42"),
+        (
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"notes.txt\"",
+        ),
+    ]);
+    assert!(is_download_response(&attachment));
+
+    let url = Url::parse("https://example.com/download?id=1").unwrap();
+    let metadata = DownloadMetadata::new(url, 0, DownloadReason::UnsupportedContentType)
+        .with_response_headers(&attachment);
+    assert_eq!(metadata.suggested_filename.as_deref(), Some("notes.txt"));
+    assert_eq!(metadata.content_type.as_deref(), Some("text/plain"));
+    assert_eq!(metadata.content_length, Some(42));
+}
@@ -1,11 +1,14 @@
 //! Types to represent UI and DOM events
 
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use bitflags::bitflags;
 use keyboard_types::{Code, Key, Location, Modifiers};
 use smol_str::SmolStr;
 
+use crate::shell::FullscreenMode;
+
 #[derive(Default)]
 pub struct EventState {
     cancelled: bool,
@@ -63,6 +66,14 @@ pub enum UiEvent {
     KeyUp(BlissKeyEvent),
     KeyDown(BlissKeyEvent),
     Ime(BlissImeEvent),
+    /// Something (e.g. files from another application) was dragged into the window
+    DragEnter(BlissDragEvent),
+    DragMove(BlissDragEvent),
+    /// The drag left the window or was cancelled
+    DragLeave(BlissDragEvent),
+    Drop(BlissDragEvent),
+    /// The window entered or left fullscreen
+    FullscreenChange(BlissFullscreenEvent),
 }
 impl UiEvent {
     pub fn discriminant(&self) -> u8 {
@@ -139,6 +150,13 @@ pub enum DomEventKind {
     Blur,
     FocusIn,
     FocusOut,
+
+    DragEnter,
+    DragOver,
+    DragLeave,
+    Drop,
+
+    FullscreenChange,
 }
 impl DomEventKind {
     pub fn discriminant(self) -> u8 {
@@ -182,6 +200,13 @@ impl FromStr for DomEventKind {
             "blur" => Ok(Self::Blur),
             "focusin" => Ok(Self::FocusIn),
             "focusout" => Ok(Self::FocusOut),
+
+            "dragenter" => Ok(Self::DragEnter),
+            "dragover" => Ok(Self::DragOver),
+            "dragleave" => Ok(Self::DragLeave),
+            "drop" => Ok(Self::Drop),
+
+            "fullscreenchange" => Ok(Self::FullscreenChange),
             _ => Err(()),
         }
     }
@@ -223,6 +248,13 @@ pub enum DomEventData {
     Blur(BlissFocusEvent),
     FocusIn(BlissFocusEvent),
     FocusOut(BlissFocusEvent),
+
+    DragEnter(BlissDragEvent),
+    DragOver(BlissDragEvent),
+    DragLeave(BlissDragEvent),
+    Drop(BlissDragEvent),
+
+    FullscreenChange(BlissFullscreenEvent),
 }
 impl DomEventData {
     pub fn discriminant(&self) -> u8 {
@@ -270,6 +302,13 @@ impl DomEventData {
             Self::Blur { .. } => "blur",
             Self::FocusIn { .. } => "focusin",
             Self::FocusOut { .. } => "focusout",
+
+            Self::DragEnter { .. } => "dragenter",
+            Self::DragOver { .. } => "dragover",
+            Self::DragLeave { .. } => "dragleave",
+            Self::Drop { .. } => "drop",
+
+            Self::FullscreenChange { .. } => "fullscreenchange",
         }
     }
 
@@ -308,6 +347,13 @@ impl DomEventData {
             Self::Blur { .. } => DomEventKind::Blur,
             Self::FocusIn { .. } => DomEventKind::FocusIn,
             Self::FocusOut { .. } => DomEventKind::FocusOut,
+
+            Self::DragEnter { .. } => DomEventKind::DragEnter,
+            Self::DragOver { .. } => DomEventKind::DragOver,
+            Self::DragLeave { .. } => DomEventKind::DragLeave,
+            Self::Drop { .. } => DomEventKind::Drop,
+
+            Self::FullscreenChange { .. } => DomEventKind::FullscreenChange,
         }
     }
 
@@ -346,6 +392,13 @@ impl DomEventData {
             Self::Blur { .. } => false,
             Self::FocusIn { .. } => false,
             Self::FocusOut { .. } => false,
+
+            Self::DragEnter { .. } => true,
+            Self::DragOver { .. } => true,
+            Self::DragLeave { .. } => false,
+            Self::Drop { .. } => true,
+
+            Self::FullscreenChange { .. } => false,
         }
     }
 
@@ -384,6 +437,13 @@ impl DomEventData {
             Self::Blur { .. } => false,
             Self::FocusIn { .. } => true,
             Self::FocusOut { .. } => true,
+
+            Self::DragEnter { .. } => true,
+            Self::DragOver { .. } => true,
+            Self::DragLeave { .. } => true,
+            Self::Drop { .. } => true,
+
+            Self::FullscreenChange { .. } => true,
         }
     }
 }
@@ -627,6 +687,65 @@ pub struct BlissKeyEvent {
     pub text: Option<SmolStr>,
 }
 
+/// The data carried by a drag-and-drop operation
+#[derive(Clone, Debug, Default)]
+pub struct BlissDataTransfer {
+    /// Paths of the files being dragged
+    pub files: Vec<PathBuf>,
+}
+
+impl BlissDataTransfer {
+    /// The names of the files being dragged (without their directories)
+    pub fn file_names(&self) -> impl Iterator<Item = String> + '_ {
+        self.files.iter().map(|path| {
+            path.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        })
+    }
+
+    /// Read the contents of the file at `index`
+    pub fn read_file(&self, index: usize) -> std::io::Result<Vec<u8>> {
+        match self.files.get(index) {
+            Some(path) => std::fs::read(path),
+            None => Err(std::io::ErrorKind::NotFound.into()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BlissDragEvent {
+    pub coords: PointerCoords,
+    pub mods: Modifiers,
+    pub data_transfer: BlissDataTransfer,
+}
+
+impl BlissDragEvent {
+    #[inline(always)]
+    pub fn page_x(&self) -> f32 {
+        self.coords.page_x
+    }
+    #[inline(always)]
+    pub fn page_y(&self) -> f32 {
+        self.coords.page_y
+    }
+    #[inline(always)]
+    pub fn client_x(&self) -> f32 {
+        self.coords.client_x
+    }
+    #[inline(always)]
+    pub fn client_y(&self) -> f32 {
+        self.coords.client_y
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BlissFullscreenEvent {
+    /// The mode which the window is now in
+    pub mode: FullscreenMode,
+}
+
 #[derive(Clone, Debug)]
 pub struct BlissInputEvent {
     pub value: String,
@@ -3,7 +3,7 @@
 use http::{HeaderMap, Method};
 use url::Url;
 
-use crate::net::{Body, Request};
+use crate::net::{Body, Bytes, Request, RequestPriority};
 
 /// An abstraction to allow embedders to hook into "navigation events" such as clicking a link
 /// or submitting a form.
@@ -33,6 +33,10 @@ pub struct NavigationOptions {
     pub method: Method,
 
     pub document_resource: Body,
+
+    /// The browsing context to navigate (e.g. `_blank`), from the link's `target` attribute or
+    /// the document's `<base target>`. `None` navigates the source document's browsing context.
+    pub target: Option<String>,
 }
 
 impl NavigationOptions {
@@ -43,6 +47,7 @@ impl NavigationOptions {
             source_document,
             method: Method::GET,
             document_resource: Body::Empty,
+            target: None,
         }
     }
     pub fn set_document_resource(mut self, document_resource: Body) -> Self {
@@ -50,6 +55,11 @@ impl NavigationOptions {
         self
     }
 
+    pub fn set_target(mut self, target: Option<String>) -> Self {
+        self.target = target;
+        self
+    }
+
     pub fn set_method(mut self, method: Method) -> Self {
         self.method = method;
         self
@@ -64,6 +74,16 @@ impl NavigationOptions {
             body: self.document_resource,
             signal: None,
             metadata: std::collections::HashMap::new(),
+            priority: RequestPriority::Highest,
         }
     }
 }
+
+/// The response to a navigation which should be shown as a document (rather than downloaded)
+#[derive(Debug, Clone)]
+pub struct NavigationResponse {
+    /// The URL of the response (after any redirects)
+    pub url: Url,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
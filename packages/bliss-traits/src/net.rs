@@ -8,10 +8,14 @@ use serde::{
 };
 use std::collections::HashMap;
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU64, Ordering},
     Arc,
 };
-use std::{ops::Deref, path::PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+};
 pub use url::Url;
 
 /// A type that fetches resources for a Document.
@@ -19,11 +23,17 @@ pub use url::Url;
 /// This may be over the network via http(s), via the filesystem, or some other method.
 pub trait NetProvider: Send + Sync + 'static {
     fn fetch(&self, doc_id: usize, request: Request, handler: Box<dyn NetHandler>);
+
+    /// Set the URL of the document `doc_id` (or `None` once the document has been dropped). Providers may
+    /// use this to decide which requests made by the document are third-party.
+    fn set_document_url(&self, _doc_id: usize, _url: Option<&Url>) {}
 }
 
 /// A type that parses raw bytes from a network request into a Data and then calls
 /// the NetCallack with the result.
 pub trait NetHandler: Send + Sync + 'static {
+    /// Called with the headers of the response before its body (if the provider has response headers)
+    fn headers(&self, _headers: &HeaderMap) {}
     fn bytes(self: Box<Self>, resolved_url: String, bytes: Bytes);
     fn chunk(&self, _seq: u64, _bytes: Bytes) {}
     fn end(&self) {}
@@ -42,6 +52,21 @@ impl<F: Fn(usize) + Send + Sync + 'static> NetWaker for F {
     }
 }
 
+/// The priority of a request, which net providers may use to decide the order in which requests are made
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RequestPriority {
+    /// Speculative requests (prefetches)
+    Lowest,
+    /// Resources which do not block rendering (images)
+    Low,
+    #[default]
+    Normal,
+    /// Resources which block rendering (stylesheets and fonts)
+    High,
+    /// Documents
+    Highest,
+}
+
 #[non_exhaustive]
 #[derive(Debug, Clone)]
 /// A request type loosely representing <https://fetch.spec.whatwg.org/#requests>
@@ -53,6 +78,7 @@ pub struct Request {
     pub body: Body,
     pub signal: Option<AbortSignal>,
     pub metadata: HashMap<String, String>,
+    pub priority: RequestPriority,
 }
 impl Request {
     /// A get request to the specified Url and an empty body
@@ -65,6 +91,7 @@ impl Request {
             body: Body::Empty,
             signal: None,
             metadata: HashMap::new(),
+            priority: RequestPriority::Normal,
         }
     }
 
@@ -72,6 +99,20 @@ impl Request {
         self.signal = Some(signal);
         self
     }
+
+    pub fn priority(mut self, priority: RequestPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set the body of the request to an encoded `multipart/form-data` body.
+    /// The request method is changed to `POST`.
+    pub fn multipart(mut self, multipart: Multipart) -> Self {
+        self.method = Method::POST;
+        self.content_type = multipart.content_type();
+        self.body = Body::Bytes(multipart.encode());
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -132,6 +173,184 @@ impl Serialize for Entry {
     }
 }
 
+/// A single part of a [`Multipart`] body
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultipartPart {
+    pub name: String,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub data: Bytes,
+}
+
+/// A builder for `multipart/form-data` request bodies
+///
+/// https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Methods/POST#multipart_form_submission
+#[derive(Debug, Clone, PartialEq)]
+pub struct Multipart {
+    boundary: String,
+    parts: Vec<MultipartPart>,
+}
+
+impl Default for Multipart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Multipart {
+    /// Creates a new empty multipart body with a generated boundary
+    pub fn new() -> Self {
+        static BOUNDARY_COUNTER: AtomicU64 = AtomicU64::new(0);
+        let count = BOUNDARY_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Self::with_boundary(format!("----BlissFormBoundary{nanos:016x}{count:08x}"))
+    }
+
+    /// Creates a new empty multipart body with the specified boundary
+    pub fn with_boundary(boundary: impl Into<String>) -> Self {
+        Self {
+            boundary: boundary.into(),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Creates a multipart body from the entries of a form. Files are read from disk.
+    pub fn from_form_data(form_data: &FormData) -> std::io::Result<Self> {
+        let mut multipart = Self::new();
+        for Entry { name, value } in form_data.iter() {
+            multipart = match value {
+                EntryValue::String(value) => multipart.text(name, value),
+                EntryValue::File(path) => multipart.file_path(name, path)?,
+                EntryValue::EmptyFile => {
+                    multipart.file(name, "", "application/octet-stream", Bytes::new())
+                }
+            };
+        }
+        Ok(multipart)
+    }
+
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    pub fn parts(&self) -> &[MultipartPart] {
+        &self.parts
+    }
+
+    /// Add a plain text field
+    pub fn text(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.parts.push(MultipartPart {
+            name: name.into(),
+            filename: None,
+            content_type: None,
+            data: Bytes::from(value.into()),
+        });
+        self
+    }
+
+    /// Add a file part with the specified filename and content type
+    pub fn file(
+        mut self,
+        name: impl Into<String>,
+        filename: impl Into<String>,
+        content_type: impl Into<String>,
+        data: impl Into<Bytes>,
+    ) -> Self {
+        self.parts.push(MultipartPart {
+            name: name.into(),
+            filename: Some(filename.into()),
+            content_type: Some(content_type.into()),
+            data: data.into(),
+        });
+        self
+    }
+
+    /// Add a file part by reading a file from disk
+    pub fn file_path(
+        self,
+        name: impl Into<String>,
+        path: impl AsRef<Path>,
+    ) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)?;
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let content_type = guess_content_type(path);
+        Ok(self.file(name, filename, content_type, data))
+    }
+
+    /// The value of the `Content-Type` header for this body (including the boundary)
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    /// Encode the parts into a request body
+    pub fn encode(&self) -> Bytes {
+        let mut out = Vec::new();
+        for part in &self.parts {
+            out.extend_from_slice(b"--");
+            out.extend_from_slice(self.boundary.as_bytes());
+            out.extend_from_slice(b"\r\n");
+            out.extend_from_slice(b"Content-Disposition: form-data; name=\"");
+            out.extend_from_slice(escape_quoted(&part.name).as_bytes());
+            out.push(b'"');
+            if let Some(filename) = &part.filename {
+                out.extend_from_slice(b"; filename=\"");
+                out.extend_from_slice(escape_quoted(filename).as_bytes());
+                out.push(b'"');
+            }
+            out.extend_from_slice(b"\r\n");
+            if let Some(content_type) = &part.content_type {
+                out.extend_from_slice(b"Content-Type: ");
+                out.extend_from_slice(content_type.as_bytes());
+                out.extend_from_slice(b"\r\n");
+            }
+            out.extend_from_slice(b"\r\n");
+            out.extend_from_slice(&part.data);
+            out.extend_from_slice(b"\r\n");
+        }
+        out.extend_from_slice(b"--");
+        out.extend_from_slice(self.boundary.as_bytes());
+        out.extend_from_slice(b"--\r\n");
+        Bytes::from(out)
+    }
+}
+
+/// Escape a value for use in a quoted `Content-Disposition` parameter
+/// as per https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#multipart-form-data
+fn escape_quoted(value: &str) -> String {
+    value
+        .replace('\n', "%0A")
+        .replace('\r', "%0D")
+        .replace('"', "%22")
+}
+
+fn guess_content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("txt") => "text/plain",
+        Some("html" | "htm") => "text/html",
+        Some("css") => "text/css",
+        Some("js") => "text/javascript",
+        Some("json") => "application/json",
+        Some("pdf") => "application/pdf",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum EntryValue {
     String(String),
@@ -215,3 +434,25 @@ impl AbortSignal {
         self.0.load(Ordering::SeqCst)
     }
 }
+
+#[test]
+fn encodes_multipart_body() {
+    let multipart = Multipart::with_boundary("XYZ").text("name", "bliss").file(
+        "upload",
+        "a.txt",
+        "text/plain",
+        "hello",
+    );
+    assert_eq!(
+        multipart.content_type(),
+        "multipart/form-data; boundary=XYZ"
+    );
+    assert_eq!(
+        multipart.encode(),
+        Bytes::from(
+            "--XYZ\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\nbliss\r\n\
+             --XYZ\r\nContent-Disposition: form-data; name=\"upload\"; filename=\"a.txt\"\r\n\
+             Content-Type: text/plain\r\n\r\nhello\r\n--XYZ--\r\n"
+        )
+    );
+}
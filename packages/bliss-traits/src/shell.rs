@@ -16,6 +16,27 @@ pub trait ShellProvider: Send + Sync + 'static {
     fn set_window_title(&self, title: String) {
         let _ = title;
     }
+    /// Set (or with `None`, reset) the icon shown in the window's title bar and the taskbar.
+    /// Documents call this when a `<link rel="icon">` image has loaded.
+    fn set_window_icon(&self, icon: Option<WindowIcon>) {
+        let _ = icon;
+    }
+    /// Show the progress of a long-running task (from `0.0` to `1.0`) on the window's taskbar button,
+    /// or remove the progress indicator with `None`
+    ///
+    /// Progress is currently only shown on Windows (with bliss-shell's `taskbar_progress` feature).
+    /// On other platforms, including the macOS dock, this does nothing.
+    fn set_progress(&self, progress: Option<f32>) {
+        let _ = progress;
+    }
+    /// Enter or leave fullscreen. A `fullscreenchange` event is dispatched to the document once
+    /// the window has changed mode.
+    fn set_fullscreen(&self, mode: FullscreenMode) {
+        let _ = mode;
+    }
+    fn fullscreen_mode(&self) -> FullscreenMode {
+        FullscreenMode::Windowed
+    }
     fn set_ime_enabled(&self, is_enabled: bool) {
         let _ = is_enabled;
     }
@@ -41,11 +62,44 @@ pub trait ShellProvider: Send + Sync + 'static {
         let _ = filter;
         vec![]
     }
+    /// Show a native context menu with its top-left corner at (`x`, `y`) in CSS pixels relative to the
+    /// window's content area. The id of the item which is selected (if any) is reported by the shell.
+    fn show_context_menu(&self, menu: ContextMenu, x: f32, y: f32) {
+        let _ = menu;
+        let _ = x;
+        let _ = y;
+    }
 }
 
 pub struct DummyShellProvider;
 impl ShellProvider for DummyShellProvider {}
 
+/// An image to use as the icon of a window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowIcon {
+    /// The pixels of the icon in RGBA8 format
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Whether (and how) a window fills the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FullscreenMode {
+    #[default]
+    Windowed,
+    /// Cover the window's monitor with a borderless window
+    Borderless,
+    /// Take exclusive control of the window's monitor (keeping its current video mode)
+    Exclusive,
+}
+
+impl FullscreenMode {
+    pub fn is_fullscreen(self) -> bool {
+        self != Self::Windowed
+    }
+}
+
 /// The system color scheme (light and dark mode)
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub enum ColorScheme {
@@ -60,6 +114,18 @@ pub struct Viewport {
     pub window_size: (u32, u32),
     pub hidpi_scale: f32,
     pub zoom: f32,
+    /// Additional scale factor applied to text only, set by the embedder (e.g. from an in-app setting)
+    pub text_scale: f32,
+    /// Additional scale factor applied to text only, from the operating system's accessibility settings.
+    /// Combined with (rather than replacing) `text_scale`.
+    pub system_text_scale: f32,
+    /// Whether the user has asked for non-essential motion (animations, transitions,
+    /// smooth scrolling and caret blinking) to be minimised.
+    ///
+    /// Stylo's servo engine doesn't evaluate the `prefers-reduced-motion` media feature, so this
+    /// is honoured with a user agent stylesheet and by the animation engine rather than by
+    /// media queries.
+    pub reduced_motion: bool,
 }
 
 impl Default for Viewport {
@@ -68,7 +134,10 @@ impl Default for Viewport {
             window_size: (0, 0),
             hidpi_scale: 1.0,
             zoom: 1.0,
+            text_scale: 1.0,
+            system_text_scale: 1.0,
             color_scheme: ColorScheme::Light,
+            reduced_motion: false,
         }
     }
 }
@@ -84,7 +153,10 @@ impl Viewport {
             window_size: (physical_width, physical_height),
             hidpi_scale: scale_factor,
             zoom: 1.0,
+            text_scale: 1.0,
+            system_text_scale: 1.0,
             color_scheme,
+            reduced_motion: false,
         }
     }
 
@@ -119,6 +191,50 @@ impl Viewport {
     pub fn zoom_mut(&mut self) -> &mut f32 {
         &mut self.zoom
     }
+
+    /// Get text scale factor set by the embedder
+    pub fn text_scale(&self) -> f32 {
+        self.text_scale
+    }
+
+    /// The scale factor which text is shown at: the embedder's text scale combined with the operating
+    /// system's text scale
+    pub fn effective_text_scale(&self) -> f32 {
+        self.text_scale * self.system_text_scale
+    }
+
+    /// Set text scale factor (`1.0` is unscaled). Unlike zoom, this only affects
+    /// font sizes and line heights.
+    pub fn set_text_scale(&mut self, text_scale: f32) {
+        self.text_scale = text_scale;
+    }
+
+    /// Apply the operating system's accessibility settings to the viewport. The embedder's text scale
+    /// is kept (and combined with the system's text scale).
+    pub fn set_system_preferences(&mut self, preferences: SystemPreferences) {
+        self.reduced_motion = preferences.reduced_motion;
+        self.system_text_scale = preferences.text_scale;
+    }
+}
+
+/// Accessibility settings of the operating system which documents should honor
+///
+/// Increased contrast isn't included: Stylo's servo engine doesn't evaluate the `prefers-contrast`
+/// media feature, so documents would have no way to respond to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SystemPreferences {
+    pub reduced_motion: bool,
+    /// The scale factor which the user has asked for text to be shown at
+    pub text_scale: f32,
+}
+
+impl Default for SystemPreferences {
+    fn default() -> Self {
+        Self {
+            reduced_motion: false,
+            text_scale: 1.0,
+        }
+    }
 }
 
 /// Filter provided by the dom for an file picker
@@ -126,3 +242,84 @@ pub struct FileDialogFilter {
     pub name: String,
     pub extensions: Vec<String>,
 }
+
+/// A menu which can be shown with [`ShellProvider::show_context_menu`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContextMenu {
+    pub items: Vec<ContextMenuItem>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContextMenuItem {
+    /// An item which can be selected. `checked` is `Some` for items with a checkmark.
+    Action {
+        id: String,
+        label: String,
+        enabled: bool,
+        checked: Option<bool>,
+    },
+    Separator,
+    Submenu {
+        label: String,
+        enabled: bool,
+        items: Vec<ContextMenuItem>,
+    },
+}
+
+impl ContextMenuItem {
+    pub fn action(id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self::Action {
+            id: id.into(),
+            label: label.into(),
+            enabled: true,
+            checked: None,
+        }
+    }
+}
+
+impl ContextMenu {
+    /// The default context menu for text inputs
+    pub fn edit_menu(has_selection: bool) -> Self {
+        let item = |command: EditCommand, label: &str, enabled: bool| ContextMenuItem::Action {
+            id: command.menu_item_id().to_string(),
+            label: label.to_string(),
+            enabled,
+            checked: None,
+        };
+        Self {
+            items: vec![
+                item(EditCommand::Cut, "Cut", has_selection),
+                item(EditCommand::Copy, "Copy", has_selection),
+                item(EditCommand::Paste, "Paste", true),
+            ],
+        }
+    }
+}
+
+/// Clipboard commands offered by the default context menu of text inputs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditCommand {
+    Cut,
+    Copy,
+    Paste,
+}
+
+impl EditCommand {
+    /// The id of the [`ContextMenuItem`] which performs this command
+    pub const fn menu_item_id(self) -> &'static str {
+        match self {
+            Self::Cut => "bliss:cut",
+            Self::Copy => "bliss:copy",
+            Self::Paste => "bliss:paste",
+        }
+    }
+
+    pub fn from_menu_item_id(id: &str) -> Option<Self> {
+        match id {
+            "bliss:cut" => Some(Self::Cut),
+            "bliss:copy" => Some(Self::Copy),
+            "bliss:paste" => Some(Self::Paste),
+            _ => None,
+        }
+    }
+}
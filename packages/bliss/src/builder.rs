@@ -0,0 +1,258 @@
+use std::sync::Arc;
+
+use anyrender_vello::VelloWindowRenderer as WindowRenderer;
+use bliss_dom::DocumentConfig;
+use bliss_html::HtmlDocument;
+use bliss_shell::{
+    BlissApplication, BlissShellProxy, Config, EventLoop, TextRenderingOptions, WindowConfig,
+    WindowGeometryStore, try_create_default_event_loop,
+};
+use bliss_traits::download::DownloadHandler;
+#[cfg(feature = "net")]
+use bliss_traits::download::{download_response, is_download_response};
+use bliss_traits::net::NetProvider;
+#[cfg(feature = "net")]
+use bliss_traits::net::http::header;
+use bliss_traits::shell::WindowIcon;
+use winit::dpi::LogicalSize;
+use winit::window::WindowAttributes;
+
+use crate::{BlissLaunchError, create_net_provider};
+
+/// Entry point for configuring and launching a Bliss application
+///
+/// ```no_run
+/// bliss::Bliss::builder()
+///     .with_title("Hello")
+///     .with_size(800.0, 600.0)
+///     .launch_html("<h1>Hello world</h1>");
+/// ```
+pub struct Bliss;
+
+impl Bliss {
+    pub fn builder() -> BlissBuilder {
+        BlissBuilder::default()
+    }
+}
+
+/// Configures the window and document of a Bliss application. Created with [`Bliss::builder`].
+#[derive(Default)]
+pub struct BlissBuilder {
+    config: Config,
+    attributes: WindowAttributes,
+    icon: Option<WindowIcon>,
+    text_rendering: TextRenderingOptions,
+    download_handler: Option<Arc<dyn DownloadHandler>>,
+}
+
+impl BlissBuilder {
+    /// Create a builder with the document options from `config`
+    pub fn from_config(config: Config) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.attributes = self.attributes.with_title(title);
+        self
+    }
+
+    /// Set the initial size of the window's content area in logical pixels
+    pub fn with_size(mut self, width: f64, height: f64) -> Self {
+        self.attributes = self
+            .attributes
+            .with_surface_size(LogicalSize::new(width, height));
+        self
+    }
+
+    /// Set the minimum size of the window's content area in logical pixels
+    pub fn with_min_size(mut self, width: f64, height: f64) -> Self {
+        self.attributes = self
+            .attributes
+            .with_min_surface_size(LogicalSize::new(width, height));
+        self
+    }
+
+    /// Set the maximum size of the window's content area in logical pixels
+    pub fn with_max_size(mut self, width: f64, height: f64) -> Self {
+        self.attributes = self
+            .attributes
+            .with_max_surface_size(LogicalSize::new(width, height));
+        self
+    }
+
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.attributes = self.attributes.with_resizable(resizable);
+        self
+    }
+
+    /// See [`WindowConfig::with_decorations`]
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.attributes = self.attributes.with_decorations(decorations);
+        self
+    }
+
+    /// See [`WindowConfig::with_transparent`]
+    pub fn with_transparent(mut self, transparent: bool) -> Self {
+        self.attributes = self.attributes.with_transparent(transparent);
+        self
+    }
+
+    /// See [`WindowConfig::with_window_icon`]
+    pub fn with_icon(mut self, icon: WindowIcon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_text_rendering(mut self, text_rendering: TextRenderingOptions) -> Self {
+        self.text_rendering = text_rendering;
+        self
+    }
+
+    /// Add a user agent stylesheet to the document
+    pub fn with_stylesheet(mut self, css: impl Into<String>) -> Self {
+        self.config.stylesheets.push(css.into());
+        self
+    }
+
+    /// Set the URL which relative URLs in the document are resolved against. When launching a
+    /// URL, this is set to the URL of the fetched document.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.config.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Set the handler which receives downloads: links with a `download` attribute, and URLs
+    /// (including the URL to launch) whose response is an attachment or can't be rendered
+    pub fn with_download_handler(mut self, handler: Arc<dyn DownloadHandler>) -> Self {
+        self.download_handler = Some(handler);
+        self
+    }
+
+    /// See [`WindowConfig::with_saved_geometry`]
+    pub fn with_saved_geometry(mut self, store: WindowGeometryStore) -> Self {
+        self.config.window_geometry = Some(store);
+        self
+    }
+
+    /// Fetch the document at `url` and show it in a window. Panics if launching fails (see
+    /// [`try_launch_url`](Self::try_launch_url)).
+    #[cfg(feature = "net")]
+    pub fn launch_url(self, url: &str) {
+        self.try_launch_url(url).unwrap()
+    }
+
+    /// Show `html` in a window. Panics if launching fails (see
+    /// [`try_launch_html`](Self::try_launch_html)).
+    pub fn launch_html(self, html: &str) {
+        self.try_launch_html(html).unwrap()
+    }
+
+    /// Fetch the document at `url` and show it in a window
+    #[cfg(feature = "net")]
+    pub fn try_launch_url(mut self, url: &str) -> Result<(), BlissLaunchError> {
+        let url = url::Url::parse(url)?;
+
+        // Turn on the runtime and enter it
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(BlissLaunchError::Runtime)?;
+        let _guard = rt.enter();
+
+        let event_loop = try_create_default_event_loop()?;
+        let (proxy, reciever) = BlissShellProxy::new(event_loop.create_proxy());
+        let net_provider = create_net_provider(proxy.clone());
+        let application = BlissApplication::new(proxy, reciever);
+
+        let (url, headers, bytes) = rt.block_on(
+            net_provider.fetch_async_with_headers(bliss_traits::net::Request::get(url)),
+        )?;
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        // Responses which can't be shown as a document are handed to the download handler. There
+        // is no source document yet, so the download is attributed to document 0 (ids start at 1).
+        if is_download_response(&headers) {
+            let url = url::Url::parse(&url)?;
+            let downloaded = self
+                .download_handler
+                .as_deref()
+                .is_some_and(|handler| download_response(handler, url, 0, &headers, bytes));
+            return match downloaded {
+                true => Ok(()),
+                false => Err(BlissLaunchError::NotRenderable(content_type)),
+            };
+        }
+
+        let html = bliss_html::decode_html(bytes.as_ref(), content_type.as_deref());
+
+        self.config.base_url = Some(url);
+        self.launch(&html, event_loop, application, net_provider)
+    }
+
+    /// Show `html` in a window
+    pub fn try_launch_html(self, html: &str) -> Result<(), BlissLaunchError> {
+        // Turn on the runtime and enter it
+        #[cfg(feature = "net")]
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(BlissLaunchError::Runtime)?;
+        #[cfg(feature = "net")]
+        let _guard = rt.enter();
+
+        let event_loop = try_create_default_event_loop()?;
+        let (proxy, reciever) = BlissShellProxy::new(event_loop.create_proxy());
+        let net_provider = create_net_provider(proxy.clone());
+        let application = BlissApplication::new(proxy, reciever);
+
+        self.launch(html, event_loop, application, net_provider)
+    }
+
+    fn launch(
+        self,
+        html: &str,
+        event_loop: EventLoop,
+        mut application: BlissApplication<WindowRenderer>,
+        net_provider: Arc<dyn NetProvider>,
+    ) -> Result<(), BlissLaunchError> {
+        let cfg = self.config;
+        let doc = HtmlDocument::from_html(
+            html,
+            DocumentConfig {
+                base_url: cfg.base_url,
+                ua_stylesheets: Some(cfg.stylesheets),
+                net_provider: Some(net_provider),
+                download_handler: self.download_handler,
+                ..Default::default()
+            },
+        );
+        let renderer = WindowRenderer::new();
+        let mut window =
+            WindowConfig::with_attributes(Box::new(doc) as _, renderer, self.attributes)
+                .with_text_rendering(self.text_rendering);
+        if let Some(icon) = self.icon {
+            window = window.with_window_icon(icon);
+        }
+        if let Some(store) = cfg.window_geometry {
+            window = window.with_saved_geometry(store);
+        }
+
+        // Create application
+
+        application.add_window(window);
+
+        // Run event loop
+        event_loop.run_app(&mut application)?;
+
+        if application.renderer_failed() {
+            return Err(BlissLaunchError::RendererInit);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use winit::error::EventLoopError;
+
+/// Why a Bliss application couldn't be launched
+#[derive(Debug)]
+pub enum BlissLaunchError {
+    /// The URL to launch couldn't be parsed
+    #[cfg(feature = "net")]
+    InvalidUrl(url::ParseError),
+    /// The async runtime used for networking couldn't be started
+    #[cfg(feature = "net")]
+    Runtime(std::io::Error),
+    /// The document at the URL to launch couldn't be fetched
+    #[cfg(feature = "net")]
+    Network(bliss_net::ProviderError),
+    /// The document at the URL to launch is an attachment or has a content type (included if
+    /// known) which can't be rendered, and no download handler accepted it
+    #[cfg(feature = "net")]
+    NotRenderable(Option<String>),
+    /// The event loop couldn't be created or failed while running
+    EventLoop(EventLoopError),
+    /// The renderer failed to initialise (e.g. because no suitable GPU was found)
+    RendererInit,
+}
+
+impl std::fmt::Display for BlissLaunchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "net")]
+            Self::InvalidUrl(err) => write!(f, "invalid url: {}", err),
+            #[cfg(feature = "net")]
+            Self::Runtime(err) => write!(f, "failed to start async runtime: {}", err),
+            #[cfg(feature = "net")]
+            Self::Network(err) => write!(f, "failed to fetch document: {:?}", err),
+            #[cfg(feature = "net")]
+            Self::NotRenderable(Some(content_type)) => {
+                write!(f, "cannot render content of type {}", content_type)
+            }
+            #[cfg(feature = "net")]
+            Self::NotRenderable(None) => write!(f, "cannot render content"),
+            Self::EventLoop(err) => write!(f, "event loop error: {}", err),
+            Self::RendererInit => write!(f, "failed to initialise renderer"),
+        }
+    }
+}
+
+impl std::error::Error for BlissLaunchError {}
+
+#[cfg(feature = "net")]
+impl From<url::ParseError> for BlissLaunchError {
+    fn from(value: url::ParseError) -> Self {
+        Self::InvalidUrl(value)
+    }
+}
+
+#[cfg(feature = "net")]
+impl From<bliss_net::ProviderError> for BlissLaunchError {
+    fn from(value: bliss_net::ProviderError) -> Self {
+        Self::Network(value)
+    }
+}
+
+impl From<EventLoopError> for BlissLaunchError {
+    fn from(value: EventLoopError) -> Self {
+        Self::EventLoop(value)
+    }
+}
+
+/// Why a document couldn't be rendered to an image
+#[cfg(feature = "headless")]
+#[derive(Debug)]
+pub enum RenderError {
+    /// The async runtime used for loading subresources couldn't be started
+    #[cfg(feature = "net")]
+    Runtime(std::io::Error),
+    /// The requested image has no pixels or is too large to allocate
+    InvalidSize { width: u32, height: u32 },
+    /// The renderer didn't produce an image of the requested size
+    Render,
+}
+
+#[cfg(feature = "headless")]
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "net")]
+            Self::Runtime(err) => write!(f, "failed to start async runtime: {}", err),
+            Self::InvalidSize { width, height } => {
+                write!(f, "invalid image size: {}x{}", width, height)
+            }
+            Self::Render => write!(f, "failed to render document"),
+        }
+    }
+}
+
+#[cfg(feature = "headless")]
+impl std::error::Error for RenderError {}
@@ -10,15 +10,21 @@
 //!
 //! [`bliss-dom`]: https://docs.rs/bliss-dom
 
+mod builder;
+mod error;
+#[cfg(feature = "headless")]
+mod render;
+
 use std::sync::Arc;
 
-use anyrender_vello::VelloWindowRenderer as WindowRenderer;
-use bliss_dom::DocumentConfig;
-use bliss_html::HtmlDocument;
-use bliss_shell::{
-    BlissApplication, BlissShellProxy, Config, EventLoop, WindowConfig, create_default_event_loop,
-};
-use bliss_traits::net::NetProvider;
+use bliss_shell::{BlissShellProxy, Config};
+
+pub use crate::builder::{Bliss, BlissBuilder};
+pub use crate::error::BlissLaunchError;
+#[cfg(feature = "headless")]
+pub use crate::error::RenderError;
+#[cfg(feature = "headless")]
+pub use crate::render::render_html_to_image;
 
 #[doc(inline)]
 /// Re-export of [`bliss_dom`].
@@ -42,38 +48,7 @@ pub use bliss_traits as traits;
 
 #[cfg(feature = "net")]
 pub fn launch_url(url: &str) {
-    // Assert that url is valid
-    println!("{url}");
-    let url = url.to_owned();
-    let url = url::Url::parse(&url).expect("Invalid url");
-
-    // Turn on the runtime and enter it
-    let rt = tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .unwrap();
-    let _guard = rt.enter();
-
-    let event_loop = create_default_event_loop();
-    let (proxy, reciever) = BlissShellProxy::new(event_loop.create_proxy());
-    let net_provider = create_net_provider(proxy.clone());
-    let application = BlissApplication::new(proxy, reciever);
-
-    let (url, bytes) = rt
-        .block_on(net_provider.fetch_async(bliss_traits::net::Request::get(url)))
-        .unwrap();
-    let html = std::str::from_utf8(bytes.as_ref()).unwrap();
-
-    launch_internal(
-        html,
-        Config {
-            stylesheets: Vec::new(),
-            base_url: Some(url),
-        },
-        event_loop,
-        application,
-        net_provider,
-    )
+    Bliss::builder().launch_url(url)
 }
 
 pub fn launch_static_html(html: &str) {
@@ -81,48 +56,18 @@ pub fn launch_static_html(html: &str) {
 }
 
 pub fn launch_static_html_cfg(html: &str, cfg: Config) {
-    // Turn on the runtime and enter it
-    #[cfg(feature = "net")]
-    let rt = tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .unwrap();
-    #[cfg(feature = "net")]
-    let _guard = rt.enter();
-
-    let event_loop = create_default_event_loop();
-    let (proxy, reciever) = BlissShellProxy::new(event_loop.create_proxy());
-    let net_provider = create_net_provider(proxy.clone());
-    let application = BlissApplication::new(proxy, reciever);
-
-    launch_internal(html, cfg, event_loop, application, net_provider)
+    BlissBuilder::from_config(cfg).launch_html(html)
 }
 
-fn launch_internal(
-    html: &str,
-    cfg: Config,
-    event_loop: EventLoop,
-    mut application: BlissApplication<WindowRenderer>,
-    net_provider: Arc<dyn NetProvider>,
-) {
-    let doc = HtmlDocument::from_html(
-        html,
-        DocumentConfig {
-            base_url: cfg.base_url,
-            ua_stylesheets: Some(cfg.stylesheets),
-            net_provider: Some(net_provider),
-            ..Default::default()
-        },
-    );
-    let renderer = WindowRenderer::new();
-    let window = WindowConfig::new(Box::new(doc) as _, renderer);
-
-    // Create application
-
-    application.add_window(window);
+/// Like [`launch_url`], but returns an error instead of panicking if launching fails
+#[cfg(feature = "net")]
+pub fn try_launch_url(url: &str) -> Result<(), BlissLaunchError> {
+    Bliss::builder().try_launch_url(url)
+}
 
-    // Run event loop
-    event_loop.run_app(application).unwrap()
+/// Like [`launch_static_html_cfg`], but returns an error instead of panicking if launching fails
+pub fn try_launch_static_html_cfg(html: &str, cfg: Config) -> Result<(), BlissLaunchError> {
+    BlissBuilder::from_config(cfg).try_launch_html(html)
 }
 
 #[cfg(feature = "net")]
@@ -130,7 +75,7 @@ type EnabledNetProvider = bliss_net::Provider;
 #[cfg(not(feature = "net"))]
 type EnabledNetProvider = bliss_traits::net::DummyNetProvider;
 
-fn create_net_provider(proxy: BlissShellProxy) -> Arc<EnabledNetProvider> {
+pub(crate) fn create_net_provider(proxy: BlissShellProxy) -> Arc<EnabledNetProvider> {
     #[cfg(feature = "net")]
     let net_provider = Arc::new(bliss_net::Provider::new(Some(Arc::new(proxy))));
     #[cfg(not(feature = "net"))]
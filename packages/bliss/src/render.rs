@@ -0,0 +1,76 @@
+//! Rendering documents to images without a window
+
+use anyrender_vello_cpu::VelloCpuImageRenderer;
+use bliss_dom::DocumentConfig;
+use bliss_html::HtmlDocument;
+use bliss_shell::{Config, create_headless_document_loop};
+use image::RgbaImage;
+
+use crate::create_net_provider;
+use crate::error::RenderError;
+
+/// How long to wait for subresources to load before rendering
+#[cfg(feature = "net")]
+const RESOURCE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Render `html` into an image of `width` x `height` pixels, without creating a window or
+/// connecting to a display server. The document is rendered on the CPU, so no GPU is required.
+///
+/// Subresources (stylesheets, images and fonts) are loaded before rendering, waiting at most
+/// 30 seconds for them. An error is returned if the image would be empty or too large to
+/// allocate.
+pub fn render_html_to_image(
+    html: &str,
+    width: u32,
+    height: u32,
+    cfg: Config,
+) -> Result<RgbaImage, RenderError> {
+    let is_allocatable = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|pixels| pixels.checked_mul(4))
+        .is_some_and(|len| len > 0 && len <= isize::MAX as usize);
+    if !is_allocatable {
+        return Err(RenderError::InvalidSize { width, height });
+    }
+
+    // Turn on the runtime and enter it
+    #[cfg(feature = "net")]
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .map_err(RenderError::Runtime)?;
+    #[cfg(feature = "net")]
+    let _guard = rt.enter();
+
+    let mut event_loop = create_headless_document_loop();
+    let net_provider = create_net_provider(event_loop.proxy());
+    let doc = HtmlDocument::from_html(
+        html,
+        DocumentConfig {
+            base_url: cfg.base_url,
+            ua_stylesheets: Some(cfg.stylesheets),
+            net_provider: Some(net_provider.clone()),
+            ..Default::default()
+        },
+    );
+    let doc_id = event_loop.add_document(Box::new(doc), width, height, 1.0);
+
+    // Wait for in-flight requests to complete
+    #[cfg(feature = "net")]
+    {
+        let deadline = std::time::Instant::now() + RESOURCE_TIMEOUT;
+        loop {
+            event_loop.run_until_idle();
+            if net_provider.is_empty() || std::time::Instant::now() >= deadline {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+    event_loop.run_until_idle();
+
+    let rgba = event_loop
+        .render::<VelloCpuImageRenderer>(doc_id)
+        .ok_or(RenderError::Render)?;
+    RgbaImage::from_raw(width, height, rgba).ok_or(RenderError::Render)
+}
@@ -0,0 +1,79 @@
+//! Caching of converted styles
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+
+use style::Atom;
+use style::properties::ComputedValues;
+use style::servo_arc::Arc as ServoArc;
+
+use crate::convert;
+
+/// A cache of [`taffy::Style`]s converted from stylo [`ComputedValues`], keyed on the identity of the
+/// [`ComputedValues`] allocation.
+///
+/// Stylo allocates a new [`ComputedValues`] whenever an element is restyled with different values (and
+/// shares a single allocation between elements with identical styles), so an entry is implicitly
+/// invalidated by a restyle. Entries hold a reference to their [`ComputedValues`] so that a key can never
+/// be reused by a different style while it is in the cache. Call [`evict_unused`](Self::evict_unused)
+/// after each layout pass to drop entries for styles which are no longer referenced by any element.
+///
+/// Converted styles are reference counted, so elements sharing a style share a single converted style.
+#[derive(Default)]
+pub struct TaffyStyleCache {
+    entries: HashMap<usize, (ServoArc<ComputedValues>, Arc<taffy::Style<Atom>>)>,
+    hits: u64,
+    misses: u64,
+}
+
+impl TaffyStyleCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the converted style for `style`, converting and caching it if it isn't already cached
+    pub fn get_or_convert(&mut self, style: &ServoArc<ComputedValues>) -> &Arc<taffy::Style<Atom>> {
+        let key = &**style as *const ComputedValues as usize;
+        match self.entries.entry(key) {
+            Entry::Occupied(entry) => {
+                self.hits += 1;
+                &entry.into_mut().1
+            }
+            Entry::Vacant(entry) => {
+                self.misses += 1;
+                let converted = Arc::new(convert::to_taffy_style(style));
+                &entry.insert((style.clone(), converted)).1
+            }
+        }
+    }
+
+    /// Remove entries for styles which are only referenced by this cache
+    pub fn evict_unused(&mut self) {
+        self.entries.retain(|_, (style, _)| !style.is_unique());
+    }
+
+    /// Remove all entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// The number of cached styles
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The number of lookups which were served from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// The number of lookups which required a conversion
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+}
@@ -3,13 +3,17 @@
 /// Private module of type aliases so we can refer to stylo types with nicer names
 pub(crate) mod stylo {
     pub(crate) use style::Atom;
+    pub(crate) use style::logical_geometry::WritingMode;
     pub(crate) use style::properties::ComputedValues;
     pub(crate) use style::properties::generated::longhands::box_sizing::computed_value::T as BoxSizing;
     pub(crate) use style::properties::longhands::aspect_ratio::computed_value::T as AspectRatio;
     pub(crate) use style::properties::longhands::position::computed_value::T as Position;
+    pub(crate) use style::values::computed::font::{FontSize, LineHeight};
     pub(crate) use style::values::computed::length_percentage::CalcLengthPercentage;
     pub(crate) use style::values::computed::length_percentage::Unpacked as UnpackedLengthPercentage;
-    pub(crate) use style::values::computed::{BorderSideWidth, LengthPercentage, Percentage};
+    pub(crate) use style::values::computed::{
+        BorderSideWidth, Length, LengthPercentage, Percentage,
+    };
     pub(crate) use style::values::generics::NonNegative;
     pub(crate) use style::values::generics::length::{
         GenericLengthPercentageOrNormal, GenericMargin, GenericMaxSize, GenericSize,
@@ -18,7 +22,7 @@ pub(crate) mod stylo {
     pub(crate) use style::values::specified::align::{AlignFlags, ContentDistribution};
     pub(crate) use style::values::specified::border::BorderStyle;
     pub(crate) use style::values::specified::box_::{
-        Display, DisplayInside, DisplayOutside, Overflow,
+        Contain, Display, DisplayInside, DisplayOutside, Overflow,
     };
     pub(crate) use style::values::specified::position::GridTemplateAreas;
     pub(crate) use style::values::specified::position::NamedArea;
@@ -72,6 +76,47 @@ pub fn length_percentage(val: &stylo::LengthPercentage) -> taffy::LengthPercenta
     }
 }
 
+/// Get the `calc()` expression which a calc value created by [`length_percentage`] points to
+///
+/// # Safety
+///
+/// `calc_ptr` must be the calc value of a [`CompactLength`] created by this crate's conversion
+/// functions, and the [`stylo::ComputedValues`] it was converted from must still be alive.
+#[inline]
+pub(crate) unsafe fn calc_length_percentage<'a>(
+    calc_ptr: *const (),
+) -> &'a stylo::CalcLengthPercentage {
+    // SAFETY: the caller guarantees that the pointer refers to a live CalcLengthPercentage
+    unsafe { &*(calc_ptr as *const stylo::CalcLengthPercentage) }
+}
+
+/// Resolve a `calc()` value created by [`length_percentage`] against a percentage basis (in CSS px).
+///
+/// This can be used to implement the calc resolver passed to Taffy's `resolve_or_zero` and
+/// `maybe_resolve` methods. Use [`resolve_length_percentage`] to resolve Stylo values directly.
+///
+/// # Safety
+///
+/// `calc_ptr` must be the calc value of a [`CompactLength`] created by this crate's conversion
+/// functions, and the [`stylo::ComputedValues`] it was converted from must still be alive.
+#[inline]
+pub unsafe fn resolve_calc(calc_ptr: *const (), basis: f32) -> f32 {
+    // SAFETY: guaranteed by the caller
+    let calc = unsafe { calc_length_percentage(calc_ptr) };
+    calc.resolve(stylo::Length::new(basis)).px()
+}
+
+/// Resolve a [`stylo::LengthPercentage`] (including `calc()` expressions such as
+/// `calc(100% - 240px)`) to a length in CSS px against a percentage basis
+#[inline]
+pub fn resolve_length_percentage(val: &stylo::LengthPercentage, basis: f32) -> f32 {
+    match val.unpack() {
+        stylo::UnpackedLengthPercentage::Calc(calc) => calc.resolve(stylo::Length::new(basis)).px(),
+        stylo::UnpackedLengthPercentage::Length(len) => len.px(),
+        stylo::UnpackedLengthPercentage::Percentage(percentage) => percentage.0 * basis,
+    }
+}
+
 #[inline]
 pub fn dimension(val: &stylo::Size) -> taffy::Dimension {
     match val {
@@ -162,6 +207,14 @@ pub fn is_table(input: stylo::Display) -> bool {
     matches!(input.inside(), stylo::DisplayInside::Table)
 }
 
+/// Whether an element with this display generates no box of its own, and instead contributes its
+/// children directly to its parent's layout. Taffy has no notion of `display: contents`, so such
+/// elements must be flattened out of the layout tree before layout.
+#[inline]
+pub fn is_display_contents(input: stylo::Display) -> bool {
+    matches!(input.inside(), stylo::DisplayInside::Contents)
+}
+
 #[inline]
 pub fn display(input: stylo::Display) -> taffy::Display {
     let mut display = match input.inside() {
@@ -176,7 +229,7 @@ pub fn display(input: stylo::Display) -> taffy::Display {
         stylo::DisplayInside::FlowRoot => taffy::Display::Block,
         #[cfg(feature = "block")]
         stylo::DisplayInside::TableCell => taffy::Display::Block,
-        // TODO: Support display:contents in Taffy
+        // display:contents nodes are flattened out of the layout tree (see `is_display_contents`)
         // TODO: Support table layout in Taffy
         #[cfg(feature = "grid")]
         stylo::DisplayInside::Table => taffy::Display::Grid,
@@ -210,6 +263,35 @@ pub fn box_generation_mode(input: stylo::Display) -> taffy::BoxGenerationMode {
     }
 }
 
+/// Layout-relevant hints derived from the CSS `contain` property.
+///
+/// Only size containment is converted. `contain: layout` doesn't affect the result of layout (only what can
+/// be skipped when relaying out), and `content-visibility` and `contain-intrinsic-size` aren't supported by
+/// Stylo's servo engine, so off-screen subtrees are always laid out.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutContainment {
+    /// `contain: size` (or `strict`). The element is sized as if it had no contents.
+    pub size: bool,
+    /// `contain: inline-size`. The element's inline size is computed as if it had no contents.
+    pub inline_size: bool,
+}
+
+impl LayoutContainment {
+    /// Whether layout of the element's subtree can be skipped when only computing the element's size
+    pub fn skips_intrinsic_sizing(&self) -> bool {
+        self.size
+    }
+}
+
+#[inline]
+pub fn containment(style: &stylo::ComputedValues) -> LayoutContainment {
+    let contain = style.clone_contain();
+    LayoutContainment {
+        size: contain.contains(stylo::Contain::SIZE),
+        inline_size: contain.contains(stylo::Contain::INLINE_SIZE),
+    }
+}
+
 #[inline]
 pub fn box_sizing(input: stylo::BoxSizing) -> taffy::BoxSizing {
     match input {
@@ -346,6 +428,123 @@ pub fn flex_wrap(input: stylo::FlexWrap) -> taffy::FlexWrap {
     }
 }
 
+/// The physical directions of the inline and block axes of a writing mode, expressed as taffy flex directions
+#[cfg(feature = "flexbox")]
+fn writing_mode_axes(
+    writing_mode: stylo::WritingMode,
+) -> (taffy::FlexDirection, taffy::FlexDirection) {
+    if writing_mode.is_vertical() {
+        let inline = match writing_mode.is_inline_tb() {
+            true => taffy::FlexDirection::Column,
+            false => taffy::FlexDirection::ColumnReverse,
+        };
+        let block = match writing_mode.is_vertical_lr() {
+            true => taffy::FlexDirection::Row,
+            false => taffy::FlexDirection::RowReverse,
+        };
+        (inline, block)
+    } else {
+        let inline = match writing_mode.is_bidi_ltr() {
+            true => taffy::FlexDirection::Row,
+            false => taffy::FlexDirection::RowReverse,
+        };
+        (inline, taffy::FlexDirection::Column)
+    }
+}
+
+/// Map the flex direction and flex wrap of an element (which are relative to its inline and block axes)
+/// to the physical directions used by taffy.
+#[cfg(feature = "flexbox")]
+pub fn flex_flow_for_writing_mode(
+    direction: taffy::FlexDirection,
+    wrap: taffy::FlexWrap,
+    writing_mode: stylo::WritingMode,
+) -> (taffy::FlexDirection, taffy::FlexWrap) {
+    fn reverse(direction: taffy::FlexDirection) -> taffy::FlexDirection {
+        match direction {
+            taffy::FlexDirection::Row => taffy::FlexDirection::RowReverse,
+            taffy::FlexDirection::RowReverse => taffy::FlexDirection::Row,
+            taffy::FlexDirection::Column => taffy::FlexDirection::ColumnReverse,
+            taffy::FlexDirection::ColumnReverse => taffy::FlexDirection::Column,
+        }
+    }
+
+    let (inline, block) = writing_mode_axes(writing_mode);
+    let (main, cross) = match direction {
+        taffy::FlexDirection::Row => (inline, block),
+        taffy::FlexDirection::RowReverse => (reverse(inline), block),
+        taffy::FlexDirection::Column => (block, inline),
+        taffy::FlexDirection::ColumnReverse => (reverse(block), inline),
+    };
+
+    // Taffy always places flex lines left-to-right or top-to-bottom, so a reversed cross axis
+    // is expressed by reversing the wrap direction.
+    let cross_is_reversed = matches!(
+        cross,
+        taffy::FlexDirection::RowReverse | taffy::FlexDirection::ColumnReverse
+    );
+    let wrap = match (wrap, cross_is_reversed) {
+        (taffy::FlexWrap::Wrap, true) => taffy::FlexWrap::WrapReverse,
+        (taffy::FlexWrap::WrapReverse, true) => taffy::FlexWrap::Wrap,
+        (wrap, _) => wrap,
+    };
+
+    (main, wrap)
+}
+
+/// Grid rows and columns are relative to the block and inline axes respectively. In vertical writing
+/// modes these map to physical columns and rows, so swap the row and column properties of a grid container.
+///
+/// Does nothing for styles which aren't grid containers. The placement and self-alignment of grid items
+/// depend on the writing mode of their container, so are mapped separately by [`apply_grid_item_writing_mode`].
+#[cfg(feature = "grid")]
+pub fn apply_grid_container_writing_mode(
+    style: &mut taffy::Style<Atom>,
+    writing_mode: stylo::WritingMode,
+) {
+    if style.display != taffy::Display::Grid || !writing_mode.is_vertical() {
+        return;
+    }
+
+    core::mem::swap(
+        &mut style.grid_template_rows,
+        &mut style.grid_template_columns,
+    );
+    core::mem::swap(
+        &mut style.grid_template_row_names,
+        &mut style.grid_template_column_names,
+    );
+    core::mem::swap(&mut style.grid_auto_rows, &mut style.grid_auto_columns);
+    core::mem::swap(&mut style.gap.width, &mut style.gap.height);
+    core::mem::swap(&mut style.align_content, &mut style.justify_content);
+    core::mem::swap(&mut style.align_items, &mut style.justify_items);
+    style.grid_auto_flow = match style.grid_auto_flow {
+        taffy::GridAutoFlow::Row => taffy::GridAutoFlow::Column,
+        taffy::GridAutoFlow::Column => taffy::GridAutoFlow::Row,
+        taffy::GridAutoFlow::RowDense => taffy::GridAutoFlow::ColumnDense,
+        taffy::GridAutoFlow::ColumnDense => taffy::GridAutoFlow::RowDense,
+    };
+    for area in style.grid_template_areas.iter_mut() {
+        core::mem::swap(&mut area.row_start, &mut area.column_start);
+        core::mem::swap(&mut area.row_end, &mut area.column_end);
+    }
+}
+
+/// Map the placement and self-alignment of a grid item to physical axes, given the writing mode of
+/// its grid container (see [`apply_grid_container_writing_mode`])
+#[cfg(feature = "grid")]
+pub fn apply_grid_item_writing_mode(
+    style: &mut taffy::Style<Atom>,
+    container_writing_mode: stylo::WritingMode,
+) {
+    if !container_writing_mode.is_vertical() {
+        return;
+    }
+
+    core::mem::swap(&mut style.grid_row, &mut style.grid_column);
+    core::mem::swap(&mut style.align_self, &mut style.justify_self);
+}
+
 #[inline]
 #[cfg(feature = "floats")]
 pub fn float(input: stylo::Float) -> taffy::Float {
@@ -494,6 +693,42 @@ fn grid_template_areas(input: &stylo::GridTemplateAreas) -> Vec<taffy::GridTempl
     }
 }
 
+/// Resolve a grid item placement which references a named area of its container (e.g. `grid-area: header`)
+/// into a line-based placement. `is_row` selects whether `placement` is the item's `grid-row` or `grid-column`.
+///
+/// Placements which don't reference one of `areas` are returned unchanged.
+#[cfg(feature = "grid")]
+pub fn resolve_area_placement(
+    areas: &[taffy::GridTemplateArea<Atom>],
+    placement: taffy::Line<taffy::GridPlacement<Atom>>,
+    is_row: bool,
+) -> taffy::Line<taffy::GridPlacement<Atom>> {
+    let find_area_lines = |placement: &taffy::GridPlacement<Atom>| {
+        let taffy::GridPlacement::NamedLine(name, idx) = placement else {
+            return None;
+        };
+        if !(0..=1).contains(idx) {
+            return None;
+        }
+        let area = areas.iter().find(|area| area.name == *name)?;
+        Some(match is_row {
+            true => (area.row_start, area.row_end),
+            false => (area.column_start, area.column_end),
+        })
+    };
+
+    let start = match find_area_lines(&placement.start) {
+        Some((start, _)) => taffy::style_helpers::line(start as i16),
+        None => placement.start,
+    };
+    let end = match find_area_lines(&placement.end) {
+        Some((_, end)) => taffy::style_helpers::line(end as i16),
+        None => placement.end,
+    };
+
+    taffy::Line { start, end }
+}
+
 #[inline]
 #[cfg(feature = "grid")]
 pub fn grid_auto_tracks(input: &stylo::ImplicitGridTracks) -> Vec<taffy::TrackSizingFunction> {
@@ -577,16 +812,37 @@ pub fn max_track(
 }
 
 /// Eagerly convert an entire [`stylo::ComputedValues`] into a [`taffy::Style`]
+///
+/// Logical properties (`margin-inline-start`, `inset-block-end`, etc) are mapped to physical properties
+/// by stylo during the cascade using the element's writing mode and direction. The remaining axis-relative
+/// properties (flex direction and the axes of grid containers) are mapped here. The placement of grid items
+/// depends on the writing mode of their container, so must be mapped by the caller with
+/// [`apply_grid_item_writing_mode`].
 pub fn to_taffy_style(style: &stylo::ComputedValues) -> taffy::Style<Atom> {
     let display = style.clone_display();
+    let taffy_display = self::display(display);
     let pos = style.get_position();
     let margin = style.get_margin();
     let padding = style.get_padding();
     let border = style.get_border();
+    let writing_mode = style.writing_mode;
 
-    taffy::Style {
+    #[cfg(feature = "flexbox")]
+    let (flex_direction, flex_wrap) = self::flex_flow_for_writing_mode(
+        self::flex_direction(pos.flex_direction),
+        self::flex_wrap(pos.flex_wrap),
+        writing_mode,
+    );
+
+    // Grid container properties are only converted for grid containers, as they are the only
+    // properties which require allocation and are ignored for every other node.
+    #[cfg(feature = "grid")]
+    let is_grid_container = taffy_display == taffy::Display::Grid;
+
+    #[allow(unused_mut)]
+    let mut taffy_style = taffy::Style {
         dummy: core::marker::PhantomData,
-        display: self::display(display),
+        display: taffy_display,
         box_sizing: self::box_sizing(style.clone_box_sizing()),
         item_is_table: display.inside() == stylo::DisplayInside::Table,
         item_is_replaced: false,
@@ -666,9 +922,9 @@ pub fn to_taffy_style(style: &stylo::ComputedValues) -> taffy::Style<Atom> {
 
         // Flexbox
         #[cfg(feature = "flexbox")]
-        flex_direction: self::flex_direction(pos.flex_direction),
+        flex_direction,
         #[cfg(feature = "flexbox")]
-        flex_wrap: self::flex_wrap(pos.flex_wrap),
+        flex_wrap,
         #[cfg(feature = "flexbox")]
         flex_grow: pos.flex_grow.0,
         #[cfg(feature = "flexbox")]
@@ -680,30 +936,45 @@ pub fn to_taffy_style(style: &stylo::ComputedValues) -> taffy::Style<Atom> {
         #[cfg(feature = "grid")]
         grid_auto_flow: self::grid_auto_flow(pos.grid_auto_flow),
         #[cfg(feature = "grid")]
-        grid_template_rows: self::grid_template_tracks(&pos.grid_template_rows),
+        grid_template_rows: match is_grid_container {
+            true => self::grid_template_tracks(&pos.grid_template_rows),
+            false => Vec::new(),
+        },
         #[cfg(feature = "grid")]
-        grid_template_columns: self::grid_template_tracks(&pos.grid_template_columns),
+        grid_template_columns: match is_grid_container {
+            true => self::grid_template_tracks(&pos.grid_template_columns),
+            false => Vec::new(),
+        },
         #[cfg(feature = "grid")]
         grid_template_row_names: match self::grid_template_line_names(&pos.grid_template_rows) {
-            Some(iter) => iter
+            Some(iter) if is_grid_container => iter
                 .map(|line_name_set| line_name_set.cloned().collect::<Vec<_>>())
                 .collect::<Vec<_>>(),
-            None => Vec::new(),
+            _ => Vec::new(),
         },
         #[cfg(feature = "grid")]
         grid_template_column_names: match self::grid_template_line_names(&pos.grid_template_columns)
         {
-            Some(iter) => iter
+            Some(iter) if is_grid_container => iter
                 .map(|line_name_set| line_name_set.cloned().collect::<Vec<_>>())
                 .collect::<Vec<_>>(),
-            None => Vec::new(),
+            _ => Vec::new(),
         },
         #[cfg(feature = "grid")]
-        grid_template_areas: self::grid_template_areas(&pos.grid_template_areas),
+        grid_template_areas: match is_grid_container {
+            true => self::grid_template_areas(&pos.grid_template_areas),
+            false => Vec::new(),
+        },
         #[cfg(feature = "grid")]
-        grid_auto_rows: self::grid_auto_tracks(&pos.grid_auto_rows),
+        grid_auto_rows: match is_grid_container {
+            true => self::grid_auto_tracks(&pos.grid_auto_rows),
+            false => Vec::new(),
+        },
         #[cfg(feature = "grid")]
-        grid_auto_columns: self::grid_auto_tracks(&pos.grid_auto_columns),
+        grid_auto_columns: match is_grid_container {
+            true => self::grid_auto_tracks(&pos.grid_auto_columns),
+            false => Vec::new(),
+        },
         #[cfg(feature = "grid")]
         grid_row: taffy::Line {
             start: self::grid_line(&pos.grid_row_start),
@@ -714,5 +985,81 @@ pub fn to_taffy_style(style: &stylo::ComputedValues) -> taffy::Style<Atom> {
             start: self::grid_line(&pos.grid_column_start),
             end: self::grid_line(&pos.grid_column_end),
         },
-    }
+    };
+
+    #[cfg(feature = "grid")]
+    self::apply_grid_container_writing_mode(&mut taffy_style, writing_mode);
+    #[cfg(not(any(feature = "flexbox", feature = "grid")))]
+    let _ = writing_mode;
+
+    taffy_style
+}
+
+#[cfg(test)]
+fn vertical_rl() -> stylo::WritingMode {
+    stylo::WritingMode::VERTICAL
+}
+
+#[test]
+#[cfg(all(feature = "flexbox", feature = "grid"))]
+fn vertical_flex_container_keeps_alignment() {
+    // The main axis of a row flex container runs top-to-bottom, and lines stack right-to-left
+    assert_eq!(
+        flex_flow_for_writing_mode(
+            taffy::FlexDirection::Row,
+            taffy::FlexWrap::Wrap,
+            vertical_rl()
+        ),
+        (taffy::FlexDirection::Column, taffy::FlexWrap::WrapReverse)
+    );
+
+    // Flex alignment is relative to the main and cross axes, so isn't swapped like grid alignment
+    let mut style = taffy::Style::<Atom> {
+        display: taffy::Display::Flex,
+        justify_content: Some(taffy::JustifyContent::Center),
+        align_self: Some(taffy::AlignSelf::End),
+        ..Default::default()
+    };
+    apply_grid_container_writing_mode(&mut style, vertical_rl());
+    assert_eq!(style.justify_content, Some(taffy::JustifyContent::Center));
+    assert_eq!(style.align_content, None);
+    assert_eq!(style.align_self, Some(taffy::AlignSelf::End));
+}
+
+#[test]
+#[cfg(feature = "grid")]
+fn vertical_grid_swaps_axes() {
+    use taffy::style_helpers::{length, line};
+
+    let mut container = taffy::Style::<Atom> {
+        display: taffy::Display::Grid,
+        grid_template_rows: vec![taffy::GridTemplateComponent::Single(length(10.0))],
+        gap: taffy::Size {
+            width: length(1.0),
+            height: length(2.0),
+        },
+        justify_items: Some(taffy::AlignItems::Center),
+        ..Default::default()
+    };
+    apply_grid_container_writing_mode(&mut container, vertical_rl());
+    assert!(container.grid_template_rows.is_empty());
+    assert_eq!(
+        container.grid_template_columns,
+        vec![taffy::GridTemplateComponent::Single(length(10.0))]
+    );
+    assert_eq!(container.gap.width, length(2.0));
+    assert_eq!(container.align_items, Some(taffy::AlignItems::Center));
+    assert_eq!(container.grid_auto_flow, taffy::GridAutoFlow::Column);
+
+    // Items are mapped against their container's writing mode, not their own
+    let mut item = taffy::Style::<Atom> {
+        grid_row: line(2),
+        align_self: Some(taffy::AlignSelf::End),
+        ..Default::default()
+    };
+    apply_grid_item_writing_mode(&mut item, vertical_rl());
+    assert_eq!(item.grid_column, line(2));
+    assert_eq!(item.grid_row, taffy::Style::<Atom>::default().grid_row);
+    assert_eq!(item.justify_self, Some(taffy::AlignSelf::End));
+    assert_eq!(item.align_self, None);
 }
@@ -0,0 +1,85 @@
+//! Resolution of custom properties (`--panel-w: 240px`) into Taffy values
+//!
+//! Stylo substitutes `var()` references when computing styles, so `width: var(--panel-w)` is already a concrete
+//! length by the time it reaches [`to_taffy_style`](crate::to_taffy_style). However, embedders driving layout
+//! from design tokens often need to read the custom properties themselves (e.g. to size native widgets or
+//! to apply tokens which are not referenced from stylesheets), which is what this module provides.
+
+use style::Atom;
+use style::properties::{ComputedValues, PropertyDeclarationId};
+use taffy::style_helpers::{length, percent};
+
+/// A hook for resolving custom property values which are not plain `px`/`%` lengths
+/// (e.g. `10rem`, `calc(var(--gutter) * 2)` or embedder-defined design tokens)
+pub trait CustomPropertyResolver {
+    /// Resolve the (serialized) `value` of the custom property `name` (including the leading `--`)
+    fn resolve(
+        &self,
+        name: &str,
+        value: &str,
+        style: &ComputedValues,
+    ) -> Option<taffy::LengthPercentage>;
+}
+
+impl<F> CustomPropertyResolver for F
+where
+    F: Fn(&str, &str, &ComputedValues) -> Option<taffy::LengthPercentage>,
+{
+    fn resolve(
+        &self,
+        name: &str,
+        value: &str,
+        style: &ComputedValues,
+    ) -> Option<taffy::LengthPercentage> {
+        self(name, value, style)
+    }
+}
+
+/// Get the computed value of the custom property `name` (including the leading `--`) as a string
+pub fn custom_property_value(style: &ComputedValues, name: &str) -> Option<String> {
+    let ident = Atom::from(name.strip_prefix("--")?);
+    let value = style.computed_value_to_string(PropertyDeclarationId::Custom(&ident));
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Parse a plain length (`240px`, `50%`, `0`) into a [`taffy::LengthPercentage`]
+pub fn parse_length_percentage(value: &str) -> Option<taffy::LengthPercentage> {
+    let value = value.trim();
+    if let Some(px) = value.strip_suffix("px") {
+        return px.trim().parse::<f32>().ok().map(length);
+    }
+    if let Some(pct) = value.strip_suffix('%') {
+        return pct
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .map(|pct| percent(pct / 100.0));
+    }
+    match value.parse::<f32>() {
+        Ok(zero) if zero == 0.0 => Some(length(0.0)),
+        _ => None,
+    }
+}
+
+/// Resolve the custom property `name` (including the leading `--`) into a [`taffy::LengthPercentage`].
+///
+/// Plain `px` and `%` values (which is what registered `<length-percentage>` properties compute to) are
+/// parsed directly. Any other value is passed to the `resolver` (if provided).
+pub fn custom_property_length(
+    style: &ComputedValues,
+    name: &str,
+    resolver: Option<&dyn CustomPropertyResolver>,
+) -> Option<taffy::LengthPercentage> {
+    let value = custom_property_value(style, name)?;
+    parse_length_percentage(&value)
+        .or_else(|| resolver.and_then(|resolver| resolver.resolve(name, &value, style)))
+}
+
+#[test]
+fn parses_plain_lengths() {
+    assert_eq!(parse_length_percentage("240px"), Some(length(240.0)));
+    assert_eq!(parse_length_percentage(" 50% "), Some(percent(0.5)));
+    assert_eq!(parse_length_percentage("0"), Some(length(0.0)));
+    assert_eq!(parse_length_percentage("10rem"), None);
+}
@@ -6,8 +6,18 @@
 mod wrapper;
 pub use wrapper::TaffyStyloStyle;
 
+mod cache;
+pub use cache::TaffyStyleCache;
+
+pub mod custom;
+pub use custom::{CustomPropertyResolver, custom_property_length};
+
 pub mod convert;
 #[doc(inline)]
 pub use convert::to_taffy_style;
 
+pub mod to_css;
+#[doc(inline)]
+pub use to_css::{computed_to_css_strings, layout_to_css_strings, to_css_strings};
+
 pub use style::Atom;
@@ -0,0 +1,393 @@
+//! Conversion functions from Taffy types back into CSS declaration strings
+//!
+//! This is the reverse of the conversion in [`convert`](crate::convert), and is useful for debugging tools
+//! (such as a computed style panel) and for tests which want to assert on the inputs and outputs of layout.
+
+use style::Atom;
+use style_traits::ToCss;
+use taffy::CompactLength;
+
+use crate::convert::{calc_length_percentage, stylo, to_taffy_style};
+
+/// Format a number without a trailing `.0` for integral values
+fn number(value: f32) -> String {
+    format!("{value}")
+}
+
+/// Convert a `Debug` representation (e.g. `SpaceBetween`) into a CSS keyword (e.g. `space-between`)
+fn keyword(value: impl core::fmt::Debug) -> String {
+    let debug = format!("{value:?}");
+    let mut out = String::with_capacity(debug.len() + 4);
+    for (idx, ch) in debug.chars().enumerate() {
+        if ch.is_ascii_uppercase() {
+            if idx != 0 {
+                out.push('-');
+            }
+            out.push(ch.to_ascii_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+fn compact_length(value: CompactLength) -> String {
+    if value.is_calc() {
+        // SAFETY: this is only reached from `to_css_strings`, whose caller guarantees that calc
+        // values were created by this crate and that their computed values are still alive
+        let calc = unsafe { calc_length_percentage(value.calc_value()) };
+        return calc.to_css_string();
+    }
+
+    match value.tag() {
+        CompactLength::LENGTH_TAG => format!("{}px", number(value.value())),
+        CompactLength::PERCENT_TAG => format!("{}%", number(value.value() * 100.0)),
+        CompactLength::AUTO_TAG => String::from("auto"),
+        CompactLength::FR_TAG => format!("{}fr", number(value.value())),
+        CompactLength::MIN_CONTENT_TAG => String::from("min-content"),
+        CompactLength::MAX_CONTENT_TAG => String::from("max-content"),
+        CompactLength::FIT_CONTENT_PX_TAG => format!("fit-content({}px)", number(value.value())),
+        CompactLength::FIT_CONTENT_PERCENT_TAG => {
+            format!("fit-content({}%)", number(value.value() * 100.0))
+        }
+        _ => String::from("auto"),
+    }
+}
+
+fn length_percentage(value: taffy::LengthPercentage) -> String {
+    compact_length(value.into_raw())
+}
+
+fn length_percentage_auto(value: taffy::LengthPercentageAuto) -> String {
+    compact_length(value.into_raw())
+}
+
+fn dimension(value: taffy::Dimension) -> String {
+    compact_length(value.into_raw())
+}
+
+fn max_dimension(value: taffy::Dimension) -> String {
+    match value.is_auto() {
+        true => String::from("none"),
+        false => dimension(value),
+    }
+}
+
+#[cfg(any(feature = "flexbox", feature = "grid"))]
+fn alignment<T: core::fmt::Debug>(value: Option<T>, default: &str) -> String {
+    match value {
+        Some(value) => keyword(value),
+        None => String::from(default),
+    }
+}
+
+#[cfg(feature = "grid")]
+fn track_sizing_function(value: &taffy::TrackSizingFunction) -> String {
+    let min = value.min.into_raw();
+    let max = value.max.into_raw();
+
+    let is_fit_content = matches!(
+        max.tag(),
+        CompactLength::FIT_CONTENT_PX_TAG | CompactLength::FIT_CONTENT_PERCENT_TAG
+    );
+    let is_auto_min = min.tag() == CompactLength::AUTO_TAG && !min.is_calc();
+    let is_fr = max.tag() == CompactLength::FR_TAG && !max.is_calc();
+
+    if min == max || (is_auto_min && (is_fr || is_fit_content)) {
+        compact_length(max)
+    } else {
+        format!("minmax({}, {})", compact_length(min), compact_length(max))
+    }
+}
+
+#[cfg(feature = "grid")]
+fn line_names(names: &[Atom]) -> String {
+    let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+    format!("[{}]", names.join(" "))
+}
+
+#[cfg(feature = "grid")]
+fn track_list(tracks: &[taffy::GridTemplateComponent<Atom>], names: &[Vec<Atom>]) -> String {
+    if tracks.is_empty() {
+        return String::from("none");
+    }
+
+    let mut parts = Vec::new();
+    for (idx, track) in tracks.iter().enumerate() {
+        if let Some(names) = names.get(idx).filter(|names| !names.is_empty()) {
+            parts.push(line_names(names));
+        }
+        parts.push(match track {
+            taffy::GridTemplateComponent::Single(track) => track_sizing_function(track),
+            taffy::GridTemplateComponent::Repeat(repeat) => {
+                let count = match repeat.count {
+                    taffy::RepetitionCount::Count(count) => count.to_string(),
+                    taffy::RepetitionCount::AutoFill => String::from("auto-fill"),
+                    taffy::RepetitionCount::AutoFit => String::from("auto-fit"),
+                };
+                let mut inner = Vec::new();
+                for (idx, track) in repeat.tracks.iter().enumerate() {
+                    if let Some(names) = repeat.line_names.get(idx).filter(|n| !n.is_empty()) {
+                        inner.push(line_names(names));
+                    }
+                    inner.push(track_sizing_function(track));
+                }
+                if let Some(names) = repeat
+                    .line_names
+                    .get(repeat.tracks.len())
+                    .filter(|n| !n.is_empty())
+                {
+                    inner.push(line_names(names));
+                }
+                format!("repeat({count}, {})", inner.join(" "))
+            }
+        });
+    }
+    if let Some(names) = names.get(tracks.len()).filter(|names| !names.is_empty()) {
+        parts.push(line_names(names));
+    }
+
+    parts.join(" ")
+}
+
+#[cfg(feature = "grid")]
+fn auto_tracks(tracks: &[taffy::TrackSizingFunction]) -> String {
+    if tracks.is_empty() {
+        return String::from("auto");
+    }
+    let tracks: Vec<String> = tracks.iter().map(track_sizing_function).collect();
+    tracks.join(" ")
+}
+
+#[cfg(feature = "grid")]
+fn grid_placement(value: &taffy::GridPlacement<Atom>) -> String {
+    match value {
+        taffy::GridPlacement::Auto => String::from("auto"),
+        taffy::GridPlacement::Line(line) => line.as_i16().to_string(),
+        taffy::GridPlacement::NamedLine(name, 0) => name.to_string(),
+        taffy::GridPlacement::NamedLine(name, idx) => format!("{idx} {name}"),
+        taffy::GridPlacement::Span(span) => format!("span {span}"),
+        taffy::GridPlacement::NamedSpan(name, 1) => format!("span {name}"),
+        taffy::GridPlacement::NamedSpan(name, span) => format!("span {span} {name}"),
+    }
+}
+
+#[cfg(feature = "grid")]
+fn grid_line(value: &taffy::Line<taffy::GridPlacement<Atom>>) -> String {
+    format!(
+        "{} / {}",
+        grid_placement(&value.start),
+        grid_placement(&value.end)
+    )
+}
+
+#[cfg(feature = "grid")]
+fn grid_template_areas(areas: &[taffy::GridTemplateArea<Atom>]) -> String {
+    if areas.is_empty() {
+        return String::from("none");
+    }
+
+    let rows = areas.iter().map(|area| area.row_end).max().unwrap_or(1) as usize - 1;
+    let columns = areas.iter().map(|area| area.column_end).max().unwrap_or(1) as usize - 1;
+    let mut cells = vec![vec![String::from("."); columns]; rows];
+    for area in areas {
+        let rows = (area.row_start as usize - 1)..(area.row_end as usize - 1);
+        let columns = (area.column_start as usize - 1)..(area.column_end as usize - 1);
+        for row in &mut cells[rows] {
+            for cell in &mut row[columns.clone()] {
+                *cell = area.name.to_string();
+            }
+        }
+    }
+
+    let rows: Vec<String> = cells
+        .iter()
+        .map(|row| format!("\"{}\"", row.join(" ")))
+        .collect();
+    rows.join(" ")
+}
+
+/// Convert Stylo computed values into the list of canonical CSS declarations (e.g. `width: 100px`)
+/// for the [`taffy::Style`] which they are converted to
+pub fn computed_to_css_strings(style: &stylo::ComputedValues) -> Vec<String> {
+    let style = to_taffy_style(style);
+    // SAFETY: the style was just converted by this crate, and the computed values its calc values
+    // point into are borrowed for the duration of this call
+    unsafe { to_css_strings(&style) }
+}
+
+/// Convert a [`taffy::Style`] into a list of canonical CSS declarations (e.g. `width: 100px`)
+///
+/// # Safety
+///
+/// `calc()` values are formatted by reading the Stylo expression they point to. Every calc value in
+/// `style` must have been created by this crate's conversion functions, and the
+/// [`stylo::ComputedValues`] it was converted from must still be alive. Styles without calc values
+/// (such as those built with `taffy::style_helpers`) are always safe to format.
+/// [`computed_to_css_strings`] is a safe alternative for formatting Stylo styles.
+pub unsafe fn to_css_strings(style: &taffy::Style<Atom>) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut push = |property: &str, value: String| out.push(format!("{property}: {value}"));
+
+    push("display", keyword(style.display));
+    push("box-sizing", keyword(style.box_sizing));
+    push("position", keyword(style.position));
+    push("overflow-x", keyword(style.overflow.x));
+    push("overflow-y", keyword(style.overflow.y));
+    #[cfg(feature = "floats")]
+    push("float", keyword(style.float));
+    #[cfg(feature = "floats")]
+    push("clear", keyword(style.clear));
+
+    push("width", dimension(style.size.width));
+    push("height", dimension(style.size.height));
+    push("min-width", dimension(style.min_size.width));
+    push("min-height", dimension(style.min_size.height));
+    push("max-width", max_dimension(style.max_size.width));
+    push("max-height", max_dimension(style.max_size.height));
+    push(
+        "aspect-ratio",
+        match style.aspect_ratio {
+            Some(ratio) => number(ratio),
+            None => String::from("auto"),
+        },
+    );
+
+    push("top", length_percentage_auto(style.inset.top));
+    push("right", length_percentage_auto(style.inset.right));
+    push("bottom", length_percentage_auto(style.inset.bottom));
+    push("left", length_percentage_auto(style.inset.left));
+    push("margin-top", length_percentage_auto(style.margin.top));
+    push("margin-right", length_percentage_auto(style.margin.right));
+    push("margin-bottom", length_percentage_auto(style.margin.bottom));
+    push("margin-left", length_percentage_auto(style.margin.left));
+    push("padding-top", length_percentage(style.padding.top));
+    push("padding-right", length_percentage(style.padding.right));
+    push("padding-bottom", length_percentage(style.padding.bottom));
+    push("padding-left", length_percentage(style.padding.left));
+    push("border-top-width", length_percentage(style.border.top));
+    push("border-right-width", length_percentage(style.border.right));
+    push(
+        "border-bottom-width",
+        length_percentage(style.border.bottom),
+    );
+    push("border-left-width", length_percentage(style.border.left));
+
+    #[cfg(any(feature = "flexbox", feature = "grid"))]
+    {
+        push("row-gap", length_percentage(style.gap.height));
+        push("column-gap", length_percentage(style.gap.width));
+        push("align-content", alignment(style.align_content, "normal"));
+        push(
+            "justify-content",
+            alignment(style.justify_content, "normal"),
+        );
+        push("align-items", alignment(style.align_items, "normal"));
+        push("align-self", alignment(style.align_self, "auto"));
+    }
+    #[cfg(feature = "grid")]
+    {
+        push("justify-items", alignment(style.justify_items, "normal"));
+        push("justify-self", alignment(style.justify_self, "auto"));
+    }
+
+    #[cfg(feature = "flexbox")]
+    {
+        push("flex-direction", keyword(style.flex_direction));
+        push(
+            "flex-wrap",
+            match style.flex_wrap {
+                taffy::FlexWrap::NoWrap => String::from("nowrap"),
+                flex_wrap => keyword(flex_wrap),
+            },
+        );
+        push("flex-grow", number(style.flex_grow));
+        push("flex-shrink", number(style.flex_shrink));
+        push("flex-basis", dimension(style.flex_basis));
+    }
+
+    #[cfg(feature = "grid")]
+    {
+        push(
+            "grid-auto-flow",
+            match style.grid_auto_flow {
+                taffy::GridAutoFlow::Row => String::from("row"),
+                taffy::GridAutoFlow::Column => String::from("column"),
+                taffy::GridAutoFlow::RowDense => String::from("row dense"),
+                taffy::GridAutoFlow::ColumnDense => String::from("column dense"),
+            },
+        );
+        push(
+            "grid-template-rows",
+            track_list(&style.grid_template_rows, &style.grid_template_row_names),
+        );
+        push(
+            "grid-template-columns",
+            track_list(
+                &style.grid_template_columns,
+                &style.grid_template_column_names,
+            ),
+        );
+        push(
+            "grid-template-areas",
+            grid_template_areas(&style.grid_template_areas),
+        );
+        push("grid-auto-rows", auto_tracks(&style.grid_auto_rows));
+        push("grid-auto-columns", auto_tracks(&style.grid_auto_columns));
+        push("grid-row", grid_line(&style.grid_row));
+        push("grid-column", grid_line(&style.grid_column));
+    }
+
+    out
+}
+
+/// Convert the result of laying out a node into a list of CSS-like declarations
+/// (e.g. `width: 100px`, `left: 8px`). All values are in CSS pixels.
+pub fn layout_to_css_strings(layout: &taffy::Layout) -> Vec<String> {
+    let px = |value: f32| format!("{}px", number(value));
+    vec![
+        format!("left: {}", px(layout.location.x)),
+        format!("top: {}", px(layout.location.y)),
+        format!("width: {}", px(layout.size.width)),
+        format!("height: {}", px(layout.size.height)),
+        format!("margin-top: {}", px(layout.margin.top)),
+        format!("margin-right: {}", px(layout.margin.right)),
+        format!("margin-bottom: {}", px(layout.margin.bottom)),
+        format!("margin-left: {}", px(layout.margin.left)),
+        format!("padding-top: {}", px(layout.padding.top)),
+        format!("padding-right: {}", px(layout.padding.right)),
+        format!("padding-bottom: {}", px(layout.padding.bottom)),
+        format!("padding-left: {}", px(layout.padding.left)),
+        format!("border-top-width: {}", px(layout.border.top)),
+        format!("border-right-width: {}", px(layout.border.right)),
+        format!("border-bottom-width: {}", px(layout.border.bottom)),
+        format!("border-left-width: {}", px(layout.border.left)),
+    ]
+}
+
+#[test]
+fn formats_default_style() {
+    let style = taffy::Style::<Atom>::default();
+    // SAFETY: the style doesn't contain any calc values
+    let css = unsafe { to_css_strings(&style) };
+    assert!(css.contains(&String::from("width: auto")));
+    assert!(css.contains(&String::from("max-width: none")));
+    assert!(css.contains(&String::from("padding-top: 0px")));
+}
+
+#[test]
+fn formats_lengths_and_percentages() {
+    use taffy::style_helpers::{length, percent};
+
+    let style = taffy::Style::<Atom> {
+        size: taffy::Size {
+            width: length(240.0),
+            height: percent(0.5),
+        },
+        ..Default::default()
+    };
+    // SAFETY: the style doesn't contain any calc values
+    let css = unsafe { to_css_strings(&style) };
+    assert!(css.contains(&String::from("width: 240px")));
+    assert!(css.contains(&String::from("height: 50%")));
+}
@@ -14,6 +14,9 @@ use style::values::{
 
 /// A wrapper struct for anything that `Deref`s to a [`stylo::ComputedValues`](ComputedValues) (can be pointed to by an `&` reference, [`Arc`](std::sync::Arc),
 /// [`Ref`](std::cell::Ref), etc). It implements [`taffy`]'s [layout traits](taffy::traits) and can used with Taffy's [layout algorithms](taffy::compute).
+///
+/// Unlike [`to_taffy_style`](crate::to_taffy_style), reading a style through this wrapper never allocates: grid
+/// tracks, line names and areas are borrowed from the stylo style and converted lazily as they are iterated.
 pub struct TaffyStyloStyle<T: Deref<Target = ComputedValues>>(pub T);
 
 // Deref<stylo::ComputedValues> impl
@@ -180,12 +183,24 @@ impl<T: Deref<Target = ComputedValues>> taffy::BlockItemStyle for TaffyStyloStyl
 impl<T: Deref<Target = ComputedValues>> taffy::FlexboxContainerStyle for TaffyStyloStyle<T> {
     #[inline]
     fn flex_direction(&self) -> taffy::FlexDirection {
-        convert::flex_direction(self.0.get_position().flex_direction)
+        let position_styles = self.0.get_position();
+        convert::flex_flow_for_writing_mode(
+            convert::flex_direction(position_styles.flex_direction),
+            convert::flex_wrap(position_styles.flex_wrap),
+            self.0.writing_mode,
+        )
+        .0
     }
 
     #[inline]
     fn flex_wrap(&self) -> taffy::FlexWrap {
-        convert::flex_wrap(self.0.get_position().flex_wrap)
+        let position_styles = self.0.get_position();
+        convert::flex_flow_for_writing_mode(
+            convert::flex_direction(position_styles.flex_direction),
+            convert::flex_wrap(position_styles.flex_wrap),
+            self.0.writing_mode,
+        )
+        .1
     }
 
     #[inline]
@@ -327,6 +342,14 @@ impl taffy::GenericRepetition for RepetitionWrapper<'_> {
     }
 }
 
+#[cfg(feature = "grid")]
+type StyloTemplateTrackList<'a> = core::iter::Map<
+    core::slice::Iter<'a, TrackListValue<LengthPercentage, i32>>,
+    fn(
+        &'a TrackListValue<LengthPercentage, i32>,
+    ) -> taffy::GenericGridTemplateComponent<Atom, RepetitionWrapper<'a>>,
+>;
+
 #[cfg(feature = "grid")]
 impl<T: Deref<Target = ComputedValues>> taffy::GridContainerStyle for TaffyStyloStyle<T> {
     type Repetition<'a>
@@ -335,12 +358,7 @@ impl<T: Deref<Target = ComputedValues>> taffy::GridContainerStyle for TaffyStylo
         Self: 'a;
 
     type TemplateTrackList<'a>
-        = core::iter::Map<
-        core::slice::Iter<'a, TrackListValue<LengthPercentage, i32>>,
-        fn(
-            &'a TrackListValue<LengthPercentage, i32>,
-        ) -> taffy::GenericGridTemplateComponent<Atom, RepetitionWrapper<'a>>,
-    >
+        = StyloTemplateTrackList<'a>
     where
         Self: 'a;
 
@@ -360,141 +378,197 @@ impl<T: Deref<Target = ComputedValues>> taffy::GridContainerStyle for TaffyStylo
 
     #[inline]
     fn grid_template_rows(&self) -> Option<Self::TemplateTrackList<'_>> {
-        match &self.0.get_position().grid_template_rows {
-            stylo::GenericGridTemplateComponent::None => None,
-            stylo::GenericGridTemplateComponent::TrackList(list) => {
-                Some(list.values.iter().map(|track| match track {
-                    stylo::TrackListValue::TrackSize(size) => {
-                        taffy::GenericGridTemplateComponent::Single(convert::track_size(size))
-                    }
-                    stylo::TrackListValue::TrackRepeat(repeat) => {
-                        taffy::GenericGridTemplateComponent::Repeat(RepetitionWrapper(repeat))
-                    }
-                }))
-            }
-
-            // TODO: Implement subgrid and masonry
-            stylo::GenericGridTemplateComponent::Subgrid(_) => None,
-            stylo::GenericGridTemplateComponent::Masonry => None,
-        }
+        let position_styles = self.0.get_position();
+        template_track_list(match self.grid_axes_are_swapped() {
+            true => &position_styles.grid_template_columns,
+            false => &position_styles.grid_template_rows,
+        })
     }
 
     #[inline]
     fn grid_template_columns(&self) -> Option<Self::TemplateTrackList<'_>> {
-        match &self.0.get_position().grid_template_columns {
-            stylo::GenericGridTemplateComponent::None => None,
-            stylo::GenericGridTemplateComponent::TrackList(list) => {
-                Some(list.values.iter().map(|track| match track {
-                    stylo::TrackListValue::TrackSize(size) => {
-                        taffy::GenericGridTemplateComponent::Single(convert::track_size(size))
-                    }
-                    stylo::TrackListValue::TrackRepeat(repeat) => {
-                        taffy::GenericGridTemplateComponent::Repeat(RepetitionWrapper(repeat))
-                    }
-                }))
-            }
-
-            // TODO: Implement subgrid and masonry
-            stylo::GenericGridTemplateComponent::Subgrid(_) => None,
-            stylo::GenericGridTemplateComponent::Masonry => None,
-        }
+        let position_styles = self.0.get_position();
+        template_track_list(match self.grid_axes_are_swapped() {
+            true => &position_styles.grid_template_rows,
+            false => &position_styles.grid_template_columns,
+        })
     }
 
     #[inline]
     fn grid_auto_rows(&self) -> Self::AutoTrackList<'_> {
-        self.0
-            .get_position()
-            .grid_auto_rows
-            .0
-            .iter()
-            .map(convert::track_size)
+        let position_styles = self.0.get_position();
+        let tracks = match self.grid_axes_are_swapped() {
+            true => &position_styles.grid_auto_columns,
+            false => &position_styles.grid_auto_rows,
+        };
+        tracks.0.iter().map(convert::track_size)
     }
 
     #[inline]
     fn grid_auto_columns(&self) -> Self::AutoTrackList<'_> {
-        self.0
-            .get_position()
-            .grid_auto_columns
-            .0
-            .iter()
-            .map(convert::track_size)
+        let position_styles = self.0.get_position();
+        let tracks = match self.grid_axes_are_swapped() {
+            true => &position_styles.grid_auto_rows,
+            false => &position_styles.grid_auto_columns,
+        };
+        tracks.0.iter().map(convert::track_size)
     }
 
     fn grid_template_areas(&self) -> Option<Self::GridTemplateAreas<'_>> {
         match &self.0.get_position().grid_template_areas {
-            GridTemplateAreas::Areas(areas) => {
-                Some(areas.0.areas.iter().map(|area| taffy::GridTemplateArea {
+            GridTemplateAreas::Areas(areas) => Some(match self.grid_axes_are_swapped() {
+                true => areas.0.areas.iter().map(|area| taffy::GridTemplateArea {
+                    name: area.name.clone(),
+                    row_start: area.columns.start as u16,
+                    row_end: area.columns.end as u16,
+                    column_start: area.rows.start as u16,
+                    column_end: area.rows.end as u16,
+                }),
+                false => areas.0.areas.iter().map(|area| taffy::GridTemplateArea {
                     name: area.name.clone(),
                     row_start: area.rows.start as u16,
                     row_end: area.rows.end as u16,
                     column_start: area.columns.start as u16,
                     column_end: area.columns.end as u16,
-                }))
-            }
+                }),
+            }),
             GridTemplateAreas::None => None,
         }
     }
 
     fn grid_template_column_names(&self) -> Option<Self::TemplateLineNames<'_>> {
-        match &self.0.get_position().grid_template_columns {
-            stylo::GenericGridTemplateComponent::None => None,
-            stylo::GenericGridTemplateComponent::TrackList(list) => {
-                Some(StyloLineNameIter::new(&list.line_names))
-            }
-            // TODO: Implement subgrid and masonry
-            stylo::GenericGridTemplateComponent::Subgrid(_) => None,
-            stylo::GenericGridTemplateComponent::Masonry => None,
-        }
+        let position_styles = self.0.get_position();
+        template_line_names(match self.grid_axes_are_swapped() {
+            true => &position_styles.grid_template_rows,
+            false => &position_styles.grid_template_columns,
+        })
     }
 
     fn grid_template_row_names(&self) -> Option<Self::TemplateLineNames<'_>> {
-        match &self.0.get_position().grid_template_rows {
-            stylo::GenericGridTemplateComponent::None => None,
-            stylo::GenericGridTemplateComponent::TrackList(list) => {
-                Some(StyloLineNameIter::new(&list.line_names))
-            }
-            // TODO: Implement subgrid and masonry
-            stylo::GenericGridTemplateComponent::Subgrid(_) => None,
-            stylo::GenericGridTemplateComponent::Masonry => None,
-        }
+        let position_styles = self.0.get_position();
+        template_line_names(match self.grid_axes_are_swapped() {
+            true => &position_styles.grid_template_columns,
+            false => &position_styles.grid_template_rows,
+        })
     }
 
     #[inline]
     fn grid_auto_flow(&self) -> taffy::GridAutoFlow {
-        convert::grid_auto_flow(self.0.get_position().grid_auto_flow)
+        let flow = convert::grid_auto_flow(self.0.get_position().grid_auto_flow);
+        match self.grid_axes_are_swapped() {
+            true => match flow {
+                taffy::GridAutoFlow::Row => taffy::GridAutoFlow::Column,
+                taffy::GridAutoFlow::Column => taffy::GridAutoFlow::Row,
+                taffy::GridAutoFlow::RowDense => taffy::GridAutoFlow::ColumnDense,
+                taffy::GridAutoFlow::ColumnDense => taffy::GridAutoFlow::RowDense,
+            },
+            false => flow,
+        }
     }
 
     #[inline]
     fn gap(&self) -> taffy::Size<taffy::LengthPercentage> {
         let position_styles = self.0.get_position();
-        taffy::Size {
-            width: convert::gap(&position_styles.column_gap),
-            height: convert::gap(&position_styles.row_gap),
+        let column_gap = convert::gap(&position_styles.column_gap);
+        let row_gap = convert::gap(&position_styles.row_gap);
+        match self.grid_axes_are_swapped() {
+            true => taffy::Size {
+                width: row_gap,
+                height: column_gap,
+            },
+            false => taffy::Size {
+                width: column_gap,
+                height: row_gap,
+            },
         }
     }
 
     #[inline]
     fn align_content(&self) -> Option<taffy::AlignContent> {
-        convert::content_alignment(self.0.get_position().align_content)
+        let position_styles = self.0.get_position();
+        convert::content_alignment(match self.grid_axes_are_swapped() {
+            true => position_styles.justify_content,
+            false => position_styles.align_content,
+        })
     }
 
     #[inline]
     fn justify_content(&self) -> Option<taffy::JustifyContent> {
-        convert::content_alignment(self.0.get_position().justify_content)
+        let position_styles = self.0.get_position();
+        convert::content_alignment(match self.grid_axes_are_swapped() {
+            true => position_styles.align_content,
+            false => position_styles.justify_content,
+        })
     }
 
     #[inline]
     fn align_items(&self) -> Option<taffy::AlignItems> {
-        convert::item_alignment(self.0.get_position().align_items.0)
+        let position_styles = self.0.get_position();
+        match self.grid_axes_are_swapped() {
+            true => convert::item_alignment((position_styles.justify_items.computed.0).0),
+            false => convert::item_alignment(position_styles.align_items.0),
+        }
     }
 
     #[inline]
     fn justify_items(&self) -> Option<taffy::AlignItems> {
-        convert::item_alignment((self.0.get_position().justify_items.computed.0).0)
+        let position_styles = self.0.get_position();
+        match self.grid_axes_are_swapped() {
+            true => convert::item_alignment(position_styles.align_items.0),
+            false => convert::item_alignment((position_styles.justify_items.computed.0).0),
+        }
+    }
+}
+
+#[cfg(feature = "grid")]
+impl<T: Deref<Target = ComputedValues>> TaffyStyloStyle<T> {
+    /// Whether grid rows and columns map to physical columns and rows because the grid container has
+    /// a vertical writing mode (see [`convert::apply_grid_container_writing_mode`])
+    #[inline]
+    fn grid_axes_are_swapped(&self) -> bool {
+        self.0.writing_mode.is_vertical()
+    }
+}
+
+#[cfg(feature = "grid")]
+fn template_track_list(input: &stylo::GridTemplateComponent) -> Option<StyloTemplateTrackList<'_>> {
+    match input {
+        stylo::GenericGridTemplateComponent::None => None,
+        stylo::GenericGridTemplateComponent::TrackList(list) => {
+            Some(list.values.iter().map(|track| match track {
+                stylo::TrackListValue::TrackSize(size) => {
+                    taffy::GenericGridTemplateComponent::Single(convert::track_size(size))
+                }
+                stylo::TrackListValue::TrackRepeat(repeat) => {
+                    taffy::GenericGridTemplateComponent::Repeat(RepetitionWrapper(repeat))
+                }
+            }))
+        }
+
+        // TODO: Implement subgrid and masonry
+        stylo::GenericGridTemplateComponent::Subgrid(_) => None,
+        stylo::GenericGridTemplateComponent::Masonry => None,
+    }
+}
+
+#[cfg(feature = "grid")]
+fn template_line_names(input: &stylo::GridTemplateComponent) -> Option<StyloLineNameIter<'_>> {
+    match input {
+        stylo::GenericGridTemplateComponent::None => None,
+        stylo::GenericGridTemplateComponent::TrackList(list) => {
+            Some(StyloLineNameIter::new(&list.line_names))
+        }
+        // TODO: Implement subgrid and masonry
+        stylo::GenericGridTemplateComponent::Subgrid(_) => None,
+        stylo::GenericGridTemplateComponent::Masonry => None,
     }
 }
 
 // GridItemStyle impl
+//
+// Grid item placement and self-alignment are relative to the axes of the item's grid container, so they are
+// returned unmapped here. Callers which lay out grids in vertical writing modes must map them against the
+// container's writing mode (see [`convert::apply_grid_item_writing_mode`]).
 #[cfg(feature = "grid")]
 impl<T: Deref<Target = ComputedValues>> taffy::GridItemStyle for TaffyStyloStyle<T> {
     #[inline]
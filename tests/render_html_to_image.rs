@@ -0,0 +1,32 @@
+//! Rendering documents to images with `bliss::render_html_to_image`
+
+use bliss::RenderError;
+use bliss::shell::Config;
+
+#[test]
+fn renders_document_at_requested_size() {
+    let html = r#"
+        <body style="margin: 0; background: white">
+            <div style="width: 50px; height: 20px; background: red"></div>
+        </body>
+    "#;
+
+    let image = bliss::render_html_to_image(html, 100, 40, Config::default())
+        .expect("document should render");
+
+    assert_eq!(image.dimensions(), (100, 40));
+    assert_eq!(image.get_pixel(10, 10).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(80, 30).0, [255, 255, 255, 255]);
+}
+
+#[test]
+fn rejects_empty_image() {
+    let result = bliss::render_html_to_image("<p>Hello</p>", 0, 40, Config::default());
+    assert!(matches!(
+        result,
+        Err(RenderError::InvalidSize {
+            width: 0,
+            height: 40
+        })
+    ));
+}
@@ -0,0 +1,45 @@
+//! Rendering of text with multiple `text-shadow`s
+
+use anyrender_vello_cpu::VelloCpuImageRenderer;
+use bliss_dom::DocumentConfig;
+use bliss_html::HtmlDocument;
+use bliss_shell::HeadlessDocumentLoop;
+
+const WIDTH: u32 = 200;
+const HEIGHT: u32 = 300;
+
+fn count_pixels(rgba: &[u8], matches: impl Fn(u8, u8, u8) -> bool) -> usize {
+    rgba.chunks_exact(4)
+        .filter(|px| matches(px[0], px[1], px[2]))
+        .count()
+}
+
+#[test]
+fn paints_every_shadow_with_the_first_on_top() {
+    // The red and blue shadows exactly overlap, so only the red one (listed first) should be
+    // visible. The green shadow doesn't overlap anything.
+    let html = r#"
+        <body style="margin: 0; background: white">
+            <div style="font-size: 48px; line-height: 60px; color: black;
+                text-shadow: red 0 80px, blue 0 80px, lime 0 160px">&bull;&bull;&bull;</div>
+        </body>
+    "#;
+
+    let mut event_loop = HeadlessDocumentLoop::new();
+    let doc = HtmlDocument::from_html(html, DocumentConfig::default());
+    let doc_id = event_loop.add_document(Box::new(doc), WIDTH, HEIGHT, 1.0);
+    event_loop.run_until_idle();
+    let rgba = event_loop
+        .render::<VelloCpuImageRenderer>(doc_id)
+        .expect("document was added to the loop");
+
+    let red = count_pixels(&rgba, |r, g, b| r > 200 && g < 50 && b < 50);
+    let blue = count_pixels(&rgba, |r, g, b| r < 50 && g < 50 && b > 200);
+    let green = count_pixels(&rgba, |r, g, b| r < 50 && g > 200 && b < 50);
+    assert!(red > 0, "the first shadow should be painted");
+    assert!(green > 0, "the last shadow should be painted");
+    assert_eq!(
+        blue, 0,
+        "the first shadow should be painted over the second"
+    );
+}